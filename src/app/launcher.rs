@@ -1,9 +1,11 @@
 // Application launcher and mode detection logic
 
-use crate::cli::CliArgs;
+use crate::cli::{CliArgs, PlaybackCommand, SetlistCommand, TapCommand};
 use crate::error::Result;
 
 /// Application mode enumeration
+// Created once at startup, so the size of the CLI arguments does not matter
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone)]
 pub enum AppMode {
     /// CLI mode with parsed arguments
@@ -12,6 +14,28 @@ pub enum AppMode {
     Gui,
 }
 
+/// Exit code of a CLI session that stopped at its time or bar limit
+pub const SESSION_LIMIT_EXIT_CODE: i32 = 3;
+
+/// How the application finished
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppExit {
+    /// Closed or stopped with Ctrl+C
+    Stopped,
+    /// Stopped on its own at the practice session limit
+    SessionLimitReached,
+}
+
+impl AppExit {
+    /// Get the process exit code for this outcome
+    pub fn exit_code(self) -> i32 {
+        match self {
+            AppExit::Stopped => 0,
+            AppExit::SessionLimitReached => SESSION_LIMIT_EXIT_CODE,
+        }
+    }
+}
+
 /// Determine the application mode based on command line arguments
 pub fn determine_mode() -> Result<AppMode> {
     // Parse CLI arguments - returns None if no BPM provided (GUI mode)
@@ -22,31 +46,51 @@ pub fn determine_mode() -> Result<AppMode> {
 }
 
 /// Launch the application in the appropriate mode
-pub fn launch_app(mode: AppMode) -> Result<()> {
+pub fn launch_app(mode: AppMode) -> Result<AppExit> {
     match mode {
+        AppMode::Cli(cli_args) if cli_args.tap_only => {
+            launch_tap_mode(cli_args).map(|()| AppExit::Stopped)
+        }
         AppMode::Cli(cli_args) => {
             launch_cli_mode(cli_args)
         }
         AppMode::Gui => {
-            launch_gui_mode()
+            launch_gui_mode().map(|()| AppExit::Stopped)
         }
     }
 }
 
-/// Launch the application in CLI mode
-fn launch_cli_mode(cli_args: CliArgs) -> Result<()> {
+/// Launch the application in CLI mode, returning whether it stopped at the session limit
+fn launch_cli_mode(cli_args: CliArgs) -> Result<AppExit> {
     use crate::metronome::MetronomeController;
     use crate::display::DisplayEngine;
 
-    use std::time::Instant;
-    use std::thread;
+    use std::sync::mpsc::RecvTimeoutError;
+    use std::time::Duration;
     
     // Note: Help is handled by clap automatically, so we don't need to check for it here
     
     // Initialize components
     let mut controller = MetronomeController::new(cli_args.bpm)?;
+    controller.get_metronome().set_time_signature(cli_args.time_signature);
+    controller.get_metronome().set_subdivision(cli_args.subdivision);
+    controller.get_metronome().set_swing(cli_args.swing)?;
+    controller.get_metronome().set_tempo_ramp(cli_args.tempo_ramp);
+    if cli_args.speed_trainer.is_some() {
+        controller.get_metronome().set_speed_trainer(cli_args.speed_trainer);
+    }
+    controller.get_metronome().set_gap_click(cli_args.gap_click);
+    controller.get_metronome().set_dropout(cli_args.dropout);
+    controller.get_metronome().set_count_in(cli_args.count_in.clone());
+    controller.get_metronome().set_session_limit(cli_args.session_limit);
+    controller.get_metronome().set_change_timing(cli_args.change_timing);
+    let mut setlist = cli_args.setlist.clone();
+    if let Some(song) = setlist.as_ref().and_then(|setlist| setlist.current_song()) {
+        controller.get_metronome().apply_song(song)?;
+    }
     let display = DisplayEngine::new();
-    let mut audio = crate::audio::CrossPlatformAudio::new();
+    let mut audio = crate::audio::CrossPlatformAudio::new()
+        .with_resample_quality(cli_args.resample_quality);
     
     // Get initial state for display
     let initial_state = controller.get_metronome().get_state();
@@ -62,57 +106,227 @@ fn launch_cli_mode(cli_args: CliArgs) -> Result<()> {
     
     // Show startup information with time signature
     display.show_startup_info(cli_args.bpm, initial_state.time_signature, &audio_status);
+    display.show_subdivision_info(initial_state.get_click_subdivision(), initial_state.swing);
+    if let Some(ramp) = &cli_args.tempo_ramp {
+        display.show_ramp_info(ramp);
+    }
+    if let Some(trainer) = &cli_args.speed_trainer {
+        display.show_trainer_info(trainer);
+    }
+    if let Some(gap_click) = &cli_args.gap_click {
+        display.show_gap_info(gap_click);
+    }
+    if let Some(dropout) = &cli_args.dropout {
+        display.show_dropout_info(dropout);
+    }
+    if let Some(count_in) = &cli_args.count_in {
+        display.show_count_in_info(count_in);
+    }
+    if let Some(setlist) = &setlist {
+        display.show_setlist_info(setlist);
+    }
+    if let Some(tap_tempo) = &cli_args.tap_tempo {
+        display.show_tap_info(tap_tempo);
+    }
+    if let Some(session_limit) = &cli_args.session_limit {
+        display.show_session_limit_info(session_limit);
+    }
     
     // Setup signal handling
     controller.setup_ctrl_c_handler()?;
     
-    // Start metronome
+    // Start metronome and its timer thread
+    let beats = controller.get_metronome().start_scheduler()?;
     controller.start()?;
     
-    let mut last_beat_time = Instant::now();
+    // Song changes are typed on the terminal while a setlist plays
+    let setlist_commands = setlist.as_ref().map(|_| crate::cli::spawn_setlist_input());
     
-    // Main loop
+    // Taps on the terminal set the tempo of the running metronome
+    let mut tap_tempo = cli_args.tap_tempo.clone();
+    let tap_commands = tap_tempo.as_ref().map(|_| crate::cli::spawn_tap_input());
+    
+    // Otherwise Enter pauses and resumes
+    let playback_commands = (setlist.is_none() && tap_tempo.is_none()).then(crate::cli::spawn_playback_input);
+    
+    // Trainer step and tempo last announced
+    let mut announced_step = 0;
+    let mut announced_bpm = initial_state.bpm;
+    let mut exit = AppExit::Stopped;
+    
+    // Main loop: wait for the scheduler to hand over each beat, waking
+    // periodically to notice Ctrl+C
     while controller.should_continue() {
-        let should_beat = {
+        if let (Some(setlist), Some(commands)) = (setlist.as_mut(), &setlist_commands) {
+            for command in commands.try_iter() {
+                let song = match command {
+                    SetlistCommand::Next => setlist.next_song(),
+                    SetlistCommand::Previous => setlist.previous_song(),
+                    SetlistCommand::TogglePause => {
+                        toggle_pause(&controller, &display);
+                        continue;
+                    }
+                }.cloned();
+                match song {
+                    Some(song) => {
+                        controller.get_metronome().apply_song(&song)?;
+                        display.show_song(setlist.current_index() + 1, setlist.len(), &song);
+                    }
+                    None => display.show_setlist_boundary(command == SetlistCommand::Next),
+                }
+            }
+        }
+        
+        if let (Some(tap_tempo), Some(commands)) = (tap_tempo.as_mut(), &tap_commands) {
+            let mut quit = false;
+            for command in commands.try_iter() {
+                match command {
+                    TapCommand::Tap(time) => {
+                        let tempo = tap_tempo.tap_at(time);
+                        display.show_tap(tap_tempo.tap_count(), tempo);
+                        if let Err(e) = controller.get_metronome().apply_tap_tempo(tap_tempo) {
+                            eprintln!("Tap tempo: {}", e);
+                        }
+                    }
+                    TapCommand::Reset => {
+                        tap_tempo.reset();
+                        display.show_tap_reset();
+                    }
+                    TapCommand::TogglePause => toggle_pause(&controller, &display),
+                    TapCommand::Quit => quit = true,
+                }
+            }
+            if quit {
+                break;
+            }
+        }
+        
+        if let Some(commands) = &playback_commands {
+            for command in commands.try_iter() {
+                match command {
+                    PlaybackCommand::TogglePause => toggle_pause(&controller, &display),
+                }
+            }
+        }
+        
+        let beat = match beats.recv_timeout(Duration::from_millis(100)) {
+            Ok(beat) => beat,
+            // No beats follow the session limit, so the wait lets the last click ring out
+            Err(RecvTimeoutError::Timeout) if controller.get_metronome().is_session_complete() => {
+                exit = AppExit::SessionLimitReached;
+                break;
+            }
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+        
+        // Schedule audio if available, using the subdivision sound between beats, so the
+        // click starts on the frame of its grid time; muted, gap and dropped beats only
+        // update the display
+        if audio.is_audio_available() && !beat.is_muted() {
+            let (sound_type, volume) = controller.get_metronome().get_sound_for_beat(&beat);
+            if let Err(e) = audio.schedule_beat(&beat, &sound_type, volume) {
+                eprintln!("Audio playback error: {}", e);
+            }
+        }
+        
+        // The count-in has its own display; the status line starts with the real first beat
+        if beat.count_in {
+            display.show_count_in(&beat);
+            continue;
+        }
+        
+        // Show enhanced visual indicator with beat information
+        let state = {
             let metronome = controller.get_metronome();
-            metronome.should_play_beat(last_beat_time)
+            metronome.get_state()
         };
         
-        if should_beat {
-            let beat = controller.get_metronome_mut().increment_beat();
-            
-            // Play audio if available
-            if audio.is_audio_available() {
-                if let Err(e) = audio.play_beat_sound() {
-                    eprintln!("Audio playback error: {}", e);
-                }
+        // Announce each song section as its first bar begins
+        if beat.is_first_beat() && beat.bar_in_section == 1 {
+            if let Some(section) = state.get_current_section() {
+                display.show_section_change(state.section_index, state.sections.len(), section);
+            }
+        }
+        
+        // Announce each speed trainer step above the status line
+        if let Some(trainer) = &state.speed_trainer {
+            if state.trainer_step != announced_step {
+                display.show_trainer_step(state.trainer_step, announced_bpm, state.bpm, trainer);
+                announced_step = state.trainer_step;
+                announced_bpm = state.bpm;
             }
-            
-            // Show enhanced visual indicator with beat information
-            display.show_visual_beat(&beat);
-            
-            // Show enhanced status with time signature and beat position
-            let state = {
-                let metronome = controller.get_metronome();
-                metronome.get_state()
-            };
-            display.show_status(
-                state.bpm, 
-                state.beat_count, 
-                state.get_elapsed_time(),
-                state.time_signature,
-                state.current_beat_in_measure
-            );
-            
-            last_beat_time = Instant::now();
-        }
-        
-        // Small sleep to prevent excessive CPU usage
-        thread::sleep(std::time::Duration::from_millis(1));
+        }
+        
+        display.show_visual_beat(&beat);
+        
+        // Show enhanced status with time signature and beat position
+        display.show_status(
+            state.bpm, 
+            state.beat_count, 
+            state.get_elapsed_time(),
+            state.time_signature,
+            state.current_beat_in_measure
+        );
+        if let (Some(target_bpm), Some(progress)) = (state.get_target_bpm(), state.get_ramp_progress()) {
+            display.show_ramp_status(state.get_current_tempo(), target_bpm, progress);
+        }
+        if let (Some(gap_click), Some((silent, bar))) = (state.gap_click, state.get_gap_position()) {
+            display.show_gap_status(&gap_click, silent, bar);
+        }
+        if let (Some(section_name), Some(section)) = (&beat.section_name, state.get_current_section()) {
+            display.show_section_status(section_name, beat.bar_in_section, section.bars);
+        }
+        if let Some(remaining) = state.get_session_remaining() {
+            display.show_session_status(&remaining);
+        }
+        display.show_subdivision_position(&beat);
     }
     
+    // Summarize the session before the state is reset by stopping
+    let state = controller.get_metronome().get_state();
     controller.stop();
+    controller.get_metronome().stop_scheduler();
+    if exit == AppExit::SessionLimitReached {
+        display.show_session_complete(state.get_elapsed_time(), state.get_bars_played(), state.beat_count);
+    }
     display.show_goodbye();
+    Ok(exit)
+}
+
+/// Pause or resume the metronome and show where it stands
+fn toggle_pause(controller: &crate::metronome::MetronomeController, display: &crate::display::DisplayEngine) {
+    let metronome = controller.get_metronome();
+    let paused = metronome.toggle_pause();
+    display.show_pause(paused, &metronome.get_state());
+}
+
+/// Detect a tempo from Enter presses on the terminal without playing the metronome
+fn launch_tap_mode(cli_args: CliArgs) -> Result<()> {
+    use crate::display::DisplayEngine;
+    
+    let display = DisplayEngine::new();
+    let mut tap_tempo = cli_args.tap_tempo.unwrap_or_default();
+    display.show_tap_info(&tap_tempo);
+    
+    // Ends when q is typed or standard input closes
+    for command in crate::cli::spawn_tap_input() {
+        match command {
+            TapCommand::Tap(time) => {
+                let tempo = tap_tempo.tap_at(time);
+                display.show_tap(tap_tempo.tap_count(), tempo);
+            }
+            TapCommand::Reset => {
+                tap_tempo.reset();
+                display.show_tap_reset();
+            }
+            // Nothing plays to pause
+            TapCommand::TogglePause => {}
+            TapCommand::Quit => break,
+        }
+    }
+    
+    display.show_tap_result(tap_tempo.bpm());
     Ok(())
 }
 
@@ -127,8 +341,8 @@ fn launch_gui_mode() -> Result<()> {
         
         let options = eframe::NativeOptions {
             viewport: egui::ViewportBuilder::default()
-                .with_inner_size([680.0, 580.0])
-                .with_min_inner_size([580.0, 480.0])
+                .with_inner_size([900.0, 580.0])
+                .with_min_inner_size([800.0, 480.0])
                 .with_title("CLI Metronome"),
             ..Default::default()
         };
@@ -161,7 +375,7 @@ mod tests {
     
     #[test]
     fn test_app_mode_variants() {
-        let cli_args = CliArgs { bpm: 120 };
+        let cli_args = CliArgs::new(120);
         let cli_mode = AppMode::Cli(cli_args);
         let gui_mode = AppMode::Gui;
         
@@ -176,11 +390,18 @@ mod tests {
         }
     }
     
+    #[test]
+    fn test_session_limit_exit_code() {
+        assert_eq!(AppExit::Stopped.exit_code(), 0);
+        assert_eq!(AppExit::SessionLimitReached.exit_code(), SESSION_LIMIT_EXIT_CODE);
+        assert_ne!(SESSION_LIMIT_EXIT_CODE, 1);
+    }
+    
     #[test]
     fn test_determine_mode_logic() {
         // This test demonstrates the logic, but actual testing would require
         // mocking command line arguments, which is better done in integration tests
-        let cli_args = CliArgs { bpm: 120 };
+        let cli_args = CliArgs::new(120);
         let cli_mode = AppMode::Cli(cli_args);
         let gui_mode = AppMode::Gui;
         
@@ -2,4 +2,4 @@
 
 pub mod launcher;
 
-pub use launcher::{AppMode, determine_mode, launch_app};
\ No newline at end of file
+pub use launcher::{AppExit, AppMode, determine_mode, launch_app, SESSION_LIMIT_EXIT_CODE};
\ No newline at end of file
@@ -0,0 +1,166 @@
+// Format detection and decoding of custom sound files
+
+use crate::error::AudioError;
+use super::wav;
+
+/// Decoded audio as interleaved f32 samples in the range -1.0..=1.0
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedAudio {
+    pub samples: Vec<f32>,
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+/// Container formats that custom sounds can be loaded from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SoundFormat {
+    Wav,
+    Mp3,
+    OggVorbis,
+    Flac,
+}
+
+impl SoundFormat {
+    /// Identify the format from the first bytes of a file
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WAVE" {
+            Some(Self::Wav)
+        } else if bytes.starts_with(b"fLaC") {
+            Some(Self::Flac)
+        } else if bytes.starts_with(b"OggS") {
+            Some(Self::OggVorbis)
+        } else if bytes.starts_with(b"ID3") || is_mp3_frame_header(bytes) {
+            Some(Self::Mp3)
+        } else {
+            None
+        }
+    }
+
+    /// Map a file extension (case-insensitive) to a format
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_lowercase().as_str() {
+            "wav" | "wave" => Some(Self::Wav),
+            "mp3" => Some(Self::Mp3),
+            "ogg" | "oga" => Some(Self::OggVorbis),
+            "flac" => Some(Self::Flac),
+            _ => None,
+        }
+    }
+
+    /// Get the display name of the format
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Wav => "WAV",
+            Self::Mp3 => "MP3",
+            Self::OggVorbis => "OGG Vorbis",
+            Self::Flac => "FLAC",
+        }
+    }
+}
+
+impl std::fmt::Display for SoundFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Work out the format of a file from its contents, falling back to its extension
+///
+/// The contents win when they disagree with the extension, so a FLAC file saved as
+/// `.wav` still decodes. The extension is only used for streams without a signature,
+/// such as MP3 files that start with padding.
+pub fn identify(bytes: &[u8], extension: Option<&str>) -> Result<SoundFormat, AudioError> {
+    if let Some(format) = SoundFormat::detect(bytes) {
+        return Ok(format);
+    }
+
+    match extension {
+        Some(ext) => SoundFormat::from_extension(ext)
+            .ok_or_else(|| AudioError::UnsupportedFormat(format!("Unsupported file format: {}", ext))),
+        None => Err(AudioError::UnsupportedFormat(
+            "File is not a recognized WAV, MP3, OGG Vorbis or FLAC stream".to_string(),
+        )),
+    }
+}
+
+/// Decode a complete sound file held in memory
+pub fn decode(bytes: &[u8], format: SoundFormat) -> Result<DecodedAudio, AudioError> {
+    let decoded = match format {
+        SoundFormat::Wav => wav::decode(bytes)?,
+        _ => decode_compressed(bytes, format, None)?,
+    };
+    check_channels(decoded.channels, format)?;
+    Ok(decoded)
+}
+
+/// Check that a file can be decoded without decoding all of it
+///
+/// WAV files are parsed completely since that is cheap; compressed streams are opened
+/// and their first packet is decoded.
+pub fn probe(bytes: &[u8], format: SoundFormat) -> Result<(), AudioError> {
+    let decoded = match format {
+        SoundFormat::Wav => wav::decode(bytes)?,
+        _ => decode_compressed(bytes, format, Some(1))?,
+    };
+    check_channels(decoded.channels, format)
+}
+
+fn check_channels(channels: u16, format: SoundFormat) -> Result<(), AudioError> {
+    if (1..=2).contains(&channels) {
+        Ok(())
+    } else {
+        Err(AudioError::UnsupportedFormat(format!(
+            "{}-channel {} data (only mono and stereo are supported)",
+            channels, format
+        )))
+    }
+}
+
+/// Decode MP3, OGG Vorbis or FLAC data, stopping after `limit` samples if given
+#[cfg(feature = "audio")]
+fn decode_compressed(bytes: &[u8], format: SoundFormat, limit: Option<usize>) -> Result<DecodedAudio, AudioError> {
+    use rodio::{Decoder, Source};
+    use std::io::Cursor;
+
+    let cursor = Cursor::new(bytes.to_vec());
+    let decoder = match format {
+        SoundFormat::Mp3 => Decoder::new_mp3(cursor),
+        SoundFormat::OggVorbis => Decoder::new_vorbis(cursor),
+        SoundFormat::Flac => Decoder::new_flac(cursor),
+        SoundFormat::Wav => unreachable!("WAV files are decoded by the built-in parser"),
+    }
+    .map_err(|e| AudioError::SoundLoadError(format!("Not a valid {} stream: {}", format, e)))?;
+
+    let channels = decoder.channels();
+    let sample_rate = decoder.sample_rate();
+    if sample_rate == 0 {
+        return Err(AudioError::SoundLoadError(format!("{} stream has a sample rate of 0 Hz", format)));
+    }
+
+    let samples: Vec<f32> = decoder
+        .take(limit.unwrap_or(usize::MAX))
+        .map(|sample| sample as f32 / 32_768.0)
+        .collect();
+    if samples.is_empty() {
+        return Err(AudioError::SoundLoadError(format!("{} stream contains no audio samples", format)));
+    }
+
+    Ok(DecodedAudio {
+        samples,
+        sample_rate,
+        channels,
+    })
+}
+
+#[cfg(not(feature = "audio"))]
+fn decode_compressed(_bytes: &[u8], format: SoundFormat, _limit: Option<usize>) -> Result<DecodedAudio, AudioError> {
+    Err(AudioError::UnsupportedFormat(format!(
+        "{} decoding requires the audio feature",
+        format
+    )))
+}
+
+/// Check for an MPEG audio layer III frame sync at the start of the data
+fn is_mp3_frame_header(bytes: &[u8]) -> bool {
+    bytes.len() >= 2 && bytes[0] == 0xFF && bytes[1] & 0xE0 == 0xE0 && (bytes[1] >> 1) & 0b11 == 0b01
+}
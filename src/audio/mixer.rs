@@ -0,0 +1,576 @@
+// Long-lived output source that mixes every click into one stream
+
+use crate::error::{AudioError, Result};
+use super::OutputFormat;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Most clicks that can sound at once; the oldest one is cut off to make room
+pub const MAX_VOICES: usize = 32;
+
+/// Most click events that can wait for the audio thread to pick them up
+pub const QUEUE_CAPACITY: usize = 256;
+
+/// Delay between a click's scheduled time and its frame that the mixer starts with
+pub const DEFAULT_LATENCY: Duration = Duration::from_millis(20);
+
+/// Largest delay the mixer grows to when clicks keep reaching it too late
+pub const MAX_LATENCY: Duration = Duration::from_millis(250);
+
+/// Level where the soft limiter starts bending the mix towards full scale
+const LIMITER_THRESHOLD: f32 = 0.8;
+
+/// Most sounds the mixer can hold for the control side: one per queued click and voice
+const RETIRED_CAPACITY: usize = QUEUE_CAPACITY + MAX_VOICES;
+
+/// A click waiting to be picked up by the audio thread
+struct ClickEvent {
+    sound: Arc<[f32]>,
+    gain: f32,
+    /// Mixer frame to start on, or `None` to start on the next frame
+    start_frame: Option<u64>,
+}
+
+/// A click being mixed
+struct Voice {
+    sound: Arc<[f32]>,
+    gain: f32,
+    start_frame: u64,
+    /// Frame the click was scheduled for, if it was scheduled
+    target_frame: Option<u64>,
+    /// Index of the next interleaved sample to mix
+    position: usize,
+}
+
+/// State shared between the mixer on the audio thread and its handle
+struct Shared {
+    events: Mutex<VecDeque<ClickEvent>>,
+    /// Length of the event queue, so the audio thread only locks when there's work
+    pending: AtomicUsize,
+    /// Sounds of finished voices, released on the control side so the audio thread
+    /// never frees memory
+    retired: Mutex<Vec<Arc<[f32]>>>,
+    frames_rendered: AtomicU64,
+    closed: AtomicBool,
+    timing: TimingCounters,
+}
+
+/// Start offsets of scheduled clicks, in frames, written by the audio thread
+#[derive(Default)]
+struct TimingCounters {
+    clicks: AtomicU64,
+    late_clicks: AtomicU64,
+    total_offset: AtomicU64,
+    last_offset: AtomicU64,
+    max_offset: AtomicU64,
+}
+
+impl TimingCounters {
+    fn record(&self, offset: u64) {
+        self.clicks.fetch_add(1, Ordering::Relaxed);
+        if offset > 0 {
+            self.late_clicks.fetch_add(1, Ordering::Relaxed);
+        }
+        self.total_offset.fetch_add(offset, Ordering::Relaxed);
+        self.last_offset.store(offset, Ordering::Relaxed);
+        self.max_offset.fetch_max(offset, Ordering::Relaxed);
+    }
+
+    fn reset(&self) {
+        for counter in [&self.clicks, &self.late_clicks, &self.total_offset, &self.last_offset, &self.max_offset] {
+            counter.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Difference between the times clicks were scheduled for and the times they were rendered
+///
+/// Offsets are measured on the mixer's sample clock: zero means the click started on
+/// exactly the frame its scheduled time maps to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ClickTiming {
+    /// Scheduled clicks that have started playing
+    pub clicks: u64,
+    /// Clicks that reached the mixer after their frame and started on a later one
+    pub late_clicks: u64,
+    pub last_offset: Duration,
+    pub mean_offset: Duration,
+    pub max_offset: Duration,
+    /// Delay between a scheduled time and its frame, leaving room for the click to arrive
+    pub latency: Duration,
+    /// Times the mapping from clock time to frames was set up again, shifting later clicks
+    pub resyncs: u64,
+}
+
+impl ClickTiming {
+    /// Check whether every scheduled click started on its exact frame
+    pub fn is_sample_accurate(&self) -> bool {
+        self.late_clicks == 0
+    }
+}
+
+impl std::fmt::Display for ClickTiming {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} clicks, {} late, max offset {:.2} ms, latency {} ms",
+            self.clicks,
+            self.late_clicks,
+            self.max_offset.as_secs_f64() * 1000.0,
+            self.latency.as_millis()
+        )
+    }
+}
+
+/// Mapping from clock time to mixer frames, kept on the control side
+struct Timeline {
+    /// A clock time and the frame it maps to, set when the first click is scheduled
+    anchor: Option<(Instant, u64)>,
+    latency: Duration,
+    resyncs: u64,
+}
+
+/// Source that sums scheduled clicks into a single interleaved stream
+///
+/// It is handed to the output stream once and plays silence between clicks. All
+/// buffers are allocated up front, so rendering never allocates: clicks come in
+/// through a queue that is only polled with `try_lock`, voices live in a fixed
+/// array and sounds are shared `Arc` slices converted to the output format.
+/// Sounds are never dropped here: finished ones are parked until the control side
+/// can collect them.
+pub struct ClickMixer {
+    shared: Arc<Shared>,
+    format: OutputFormat,
+    voices: Vec<Option<Voice>>,
+    /// Sounds waiting to be handed to the control side while its list is locked or
+    /// full; the capacity is fixed and never exceeded
+    parked: Vec<Arc<[f32]>>,
+    /// The frame being played, one sample per channel
+    frame: Vec<f32>,
+    channel: usize,
+    clock: u64,
+}
+
+impl ClickMixer {
+    /// Create a mixer for the output format and the handle that feeds it
+    pub fn new(format: OutputFormat) -> (Self, MixerHandle) {
+        let format = OutputFormat::new(format.sample_rate, format.channels.max(1));
+        let shared = Arc::new(Shared {
+            events: Mutex::new(VecDeque::with_capacity(QUEUE_CAPACITY)),
+            pending: AtomicUsize::new(0),
+            retired: Mutex::new(Vec::with_capacity(RETIRED_CAPACITY)),
+            frames_rendered: AtomicU64::new(0),
+            closed: AtomicBool::new(false),
+            timing: TimingCounters::default(),
+        });
+
+        let mixer = Self {
+            shared: Arc::clone(&shared),
+            format,
+            voices: (0..MAX_VOICES).map(|_| None).collect(),
+            parked: Vec::with_capacity(RETIRED_CAPACITY),
+            frame: vec![0.0; format.channels as usize],
+            channel: 0,
+            clock: 0,
+        };
+        let timeline = Mutex::new(Timeline {
+            anchor: None,
+            latency: DEFAULT_LATENCY,
+            resyncs: 0,
+        });
+        (mixer, MixerHandle { shared, format, timeline })
+    }
+
+    /// Format the mixer renders in
+    pub fn format(&self) -> OutputFormat {
+        self.format
+    }
+
+    /// Number of voices currently playing or waiting for their start frame
+    pub fn active_voices(&self) -> usize {
+        self.voices.iter().filter(|voice| voice.is_some()).count()
+    }
+
+    /// Move queued clicks into voices, unless the control side holds the queue
+    ///
+    /// A click may cut off a voice or carry no samples, leaving a sound to park, so
+    /// clicks wait in the queue while there's no room to park one.
+    fn take_events(&mut self) {
+        if self.shared.pending.load(Ordering::Acquire) == 0 {
+            return;
+        }
+        let Ok(mut events) = self.shared.events.try_lock() else {
+            return;
+        };
+
+        while has_room(&self.parked) {
+            let Some(event) = events.pop_front() else {
+                break;
+            };
+            start_voice(&mut self.voices, &mut self.parked, event, self.clock);
+        }
+        self.shared.pending.store(events.len(), Ordering::Release);
+    }
+
+    /// Move parked sounds to the control side, unless it holds the retired list
+    fn hand_over_parked(&mut self) {
+        if self.parked.is_empty() {
+            return;
+        }
+        let Ok(mut retired) = self.shared.retired.try_lock() else {
+            return;
+        };
+
+        while has_room(&retired) {
+            let Some(sound) = self.parked.pop() else {
+                break;
+            };
+            retired.push(sound);
+        }
+    }
+
+    /// Mix the next frame of every voice that has started
+    fn render_frame(&mut self) {
+        self.take_events();
+        self.frame.fill(0.0);
+
+        let channels = self.frame.len();
+        for slot in self.voices.iter_mut() {
+            let finished = match slot {
+                Some(voice) if voice.start_frame <= self.clock => {
+                    if voice.position == 0 {
+                        if let Some(target) = voice.target_frame {
+                            self.shared.timing.record(self.clock - target);
+                        }
+                    }
+                    if let Some(samples) = voice.sound.get(voice.position..) {
+                        for (output, sample) in self.frame.iter_mut().zip(samples) {
+                            *output += sample * voice.gain;
+                        }
+                        voice.position += channels;
+                    }
+                    voice.position >= voice.sound.len()
+                }
+                _ => false,
+            };
+            // A finished voice keeps its slot until its sound can be parked
+            if finished && has_room(&self.parked) {
+                if let Some(voice) = slot.take() {
+                    self.parked.push(voice.sound);
+                }
+            }
+        }
+
+        for sample in self.frame.iter_mut() {
+            *sample = soft_limit(*sample);
+        }
+        self.hand_over_parked();
+
+        self.clock += 1;
+        self.shared.frames_rendered.store(self.clock, Ordering::Release);
+    }
+}
+
+impl Iterator for ClickMixer {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.channel == 0 {
+            // Only stop on a frame boundary so the channels stay aligned
+            if self.shared.closed.load(Ordering::Acquire) {
+                return None;
+            }
+            self.render_frame();
+        }
+
+        let sample = self.frame[self.channel];
+        self.channel = (self.channel + 1) % self.frame.len();
+        Some(sample)
+    }
+}
+
+#[cfg(feature = "audio")]
+impl rodio::Source for ClickMixer {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        self.format.channels
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.format.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<std::time::Duration> {
+        None
+    }
+}
+
+/// Control side of a [`ClickMixer`]; dropping it ends the mixer's stream
+pub struct MixerHandle {
+    shared: Arc<Shared>,
+    format: OutputFormat,
+    timeline: Mutex<Timeline>,
+}
+
+impl MixerHandle {
+    /// Start a sound on the next frame the mixer renders
+    ///
+    /// The samples must already be in the mixer's output format.
+    pub fn play(&self, sound: Arc<[f32]>, gain: f32) -> Result<()> {
+        self.push(ClickEvent { sound, gain, start_frame: None })
+    }
+
+    /// Start a sound on a frame of the mixer clock
+    ///
+    /// A sound that reaches the mixer after its frame has passed starts right away.
+    pub fn play_at(&self, sound: Arc<[f32]>, gain: f32, start_frame: u64) -> Result<()> {
+        self.push(ClickEvent { sound, gain, start_frame: Some(start_frame) })
+    }
+
+    /// Start a sound on the frame that a point in time maps to
+    ///
+    /// Sample-accurate placement needs the click to reach the mixer before that frame
+    /// is rendered, so the frame lies the latency after the time. When a click would
+    /// still arrive too late the latency is doubled, up to [`MAX_LATENCY`].
+    pub fn play_at_time(&self, sound: Arc<[f32]>, gain: f32, at: Instant) -> Result<()> {
+        let start_frame = self.frame_for_time(at);
+        self.play_at(sound, gain, start_frame)
+    }
+
+    /// Map a point in time to a frame of the mixer clock, including the latency
+    ///
+    /// The mapping is anchored to the frames rendered when it is first used, so
+    /// clicks are spaced by exact frame counts however unevenly they are handed
+    /// over. It is set up again if the device clock has drifted past the latency.
+    pub fn frame_for_time(&self, at: Instant) -> u64 {
+        let now = Instant::now();
+        let rendered = self.frames_rendered();
+        let mut timeline = self.timeline.lock().unwrap();
+
+        if let Some(anchor) = timeline.anchor {
+            let now_frame = self.map_time(anchor, now);
+            let latency = self.duration_to_frames(timeline.latency);
+            if now_frame < rendered {
+                // The mixer has already rendered past clicks due now
+                timeline.latency = (timeline.latency * 2).min(MAX_LATENCY);
+                timeline.anchor = None;
+            } else if now_frame > rendered + 2 * latency {
+                // The mixer fell behind, e.g. because the device stalled
+                timeline.anchor = None;
+            }
+            if timeline.anchor.is_none() {
+                timeline.resyncs += 1;
+            }
+        }
+
+        let latency = self.duration_to_frames(timeline.latency);
+        let anchor = *timeline.anchor.get_or_insert((now, rendered + latency));
+        self.map_time(anchor, at)
+    }
+
+    /// Set the delay between a scheduled time and its frame, starting a new mapping
+    pub fn set_latency(&self, latency: Duration) {
+        let mut timeline = self.timeline.lock().unwrap();
+        timeline.latency = latency.min(MAX_LATENCY);
+        timeline.anchor = None;
+    }
+
+    /// Get the delay between a scheduled time and its frame
+    pub fn latency(&self) -> Duration {
+        self.timeline.lock().unwrap().latency
+    }
+
+    /// Report how far scheduled clicks started from their frames
+    pub fn timing(&self) -> ClickTiming {
+        let counters = &self.shared.timing;
+        let clicks = counters.clicks.load(Ordering::Relaxed);
+        let total_offset = counters.total_offset.load(Ordering::Relaxed);
+        let timeline = self.timeline.lock().unwrap();
+
+        ClickTiming {
+            clicks,
+            late_clicks: counters.late_clicks.load(Ordering::Relaxed),
+            last_offset: self.frames_to_duration(counters.last_offset.load(Ordering::Relaxed)),
+            mean_offset: self.frames_to_duration(total_offset.checked_div(clicks).unwrap_or(0)),
+            max_offset: self.frames_to_duration(counters.max_offset.load(Ordering::Relaxed)),
+            latency: timeline.latency,
+            resyncs: timeline.resyncs,
+        }
+    }
+
+    /// Clear the click timing measurements
+    pub fn reset_timing(&self) {
+        self.shared.timing.reset();
+        self.timeline.lock().unwrap().resyncs = 0;
+    }
+
+    /// Number of frames the mixer has rendered so far
+    pub fn frames_rendered(&self) -> u64 {
+        self.shared.frames_rendered.load(Ordering::Acquire)
+    }
+
+    /// Format the mixer renders in
+    pub fn format(&self) -> OutputFormat {
+        self.format
+    }
+
+    fn map_time(&self, (instant, frame): (Instant, u64), at: Instant) -> u64 {
+        let rate = self.format.sample_rate as f64;
+        if at >= instant {
+            frame + ((at - instant).as_secs_f64() * rate).round() as u64
+        } else {
+            frame.saturating_sub(((instant - at).as_secs_f64() * rate).round() as u64)
+        }
+    }
+
+    fn duration_to_frames(&self, duration: Duration) -> u64 {
+        (duration.as_secs_f64() * self.format.sample_rate as f64).round() as u64
+    }
+
+    fn frames_to_duration(&self, frames: u64) -> Duration {
+        Duration::from_secs_f64(frames as f64 / self.format.sample_rate.max(1) as f64)
+    }
+
+    fn push(&self, event: ClickEvent) -> Result<()> {
+        // Free the sounds of finished voices here rather than on the audio thread
+        if let Ok(mut retired) = self.shared.retired.lock() {
+            retired.clear();
+        }
+
+        let mut events = self.shared.events.lock()
+            .map_err(|_| AudioError::PlaybackFailed("Click queue is unavailable".to_string()))?;
+        if events.len() >= QUEUE_CAPACITY {
+            return Err(AudioError::PlaybackFailed(format!(
+                "Click queue is full ({} events waiting)",
+                QUEUE_CAPACITY
+            )).into());
+        }
+        events.push_back(event);
+        self.shared.pending.store(events.len(), Ordering::Release);
+        Ok(())
+    }
+}
+
+impl Drop for MixerHandle {
+    fn drop(&mut self) {
+        self.shared.closed.store(true, Ordering::Release);
+    }
+}
+
+/// Put a click into a free voice, cutting off the oldest voice when all are busy
+///
+/// There must be room to park one sound, for the cut-off voice or an empty click.
+fn start_voice(voices: &mut [Option<Voice>], parked: &mut Vec<Arc<[f32]>>, event: ClickEvent, clock: u64) {
+    if event.sound.is_empty() {
+        parked.push(event.sound);
+        return;
+    }
+
+    let index = match voices.iter().position(|voice| voice.is_none()) {
+        Some(index) => index,
+        None => voices
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, voice)| voice.as_ref().map_or(0, |voice| voice.start_frame))
+            .map_or(0, |(index, _)| index),
+    };
+    if let Some(stolen) = voices[index].take() {
+        parked.push(stolen.sound);
+    }
+
+    voices[index] = Some(Voice {
+        sound: event.sound,
+        gain: event.gain,
+        start_frame: event.start_frame.map_or(clock, |frame| frame.max(clock)),
+        target_frame: event.start_frame,
+        position: 0,
+    });
+}
+
+/// Check whether a list of sounds can take another without growing
+fn has_room(sounds: &Vec<Arc<[f32]>>) -> bool {
+    sounds.len() < sounds.capacity()
+}
+
+/// Pass quiet samples through unchanged and bend louder ones smoothly towards ±1.0
+///
+/// Below the threshold the curve is linear; above it a tanh knee with matching slope
+/// takes over, so summed clicks never exceed full scale and don't hard clip.
+pub fn soft_limit(sample: f32) -> f32 {
+    let level = sample.abs();
+    if level <= LIMITER_THRESHOLD {
+        return sample;
+    }
+    let headroom = 1.0 - LIMITER_THRESHOLD;
+    let over = (level - LIMITER_THRESHOLD) / headroom;
+    sample.signum() * (LIMITER_THRESHOLD + headroom * over.tanh())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mixer_parks_sounds_while_the_control_side_is_busy() {
+        let (mut mixer, handle) = ClickMixer::new(OutputFormat::new(48000, 1));
+        let sound: Arc<[f32]> = vec![0.01; 10].into();
+        for start in 0..(MAX_VOICES as u64 + 8) {
+            handle.play_at(Arc::clone(&sound), 1.0, start).unwrap();
+        }
+        handle.play(vec![].into(), 1.0).unwrap();
+
+        // While the control side holds the retired list nothing is dropped
+        let retired = handle.shared.retired.lock().unwrap();
+        mixer.by_ref().take(100).for_each(drop);
+        assert_eq!(mixer.active_voices(), 0);
+        assert_eq!(mixer.parked.len(), MAX_VOICES + 9);
+        assert_eq!(Arc::strong_count(&sound), MAX_VOICES + 9);
+        drop(retired);
+
+        // The parked sounds are handed over on the next frame and freed on the next push
+        mixer.next();
+        assert!(mixer.parked.is_empty());
+        assert_eq!(Arc::strong_count(&sound), MAX_VOICES + 9);
+        handle.play(vec![].into(), 1.0).unwrap();
+        assert_eq!(Arc::strong_count(&sound), 1);
+    }
+
+    #[test]
+    fn test_mixer_keeps_finished_voices_when_nothing_can_be_parked() {
+        let (mut mixer, handle) = ClickMixer::new(OutputFormat::new(48000, 1));
+        let sound: Arc<[f32]> = vec![0.01; 10].into();
+        let filler: Arc<[f32]> = vec![].into();
+        while has_room(&mixer.parked) {
+            mixer.parked.push(Arc::clone(&filler));
+        }
+        handle.play(Arc::clone(&sound), 1.0).unwrap();
+
+        // With the retired list held and no parking room, the click waits in the queue
+        let mut retired = handle.shared.retired.lock().unwrap();
+        mixer.by_ref().take(20).for_each(drop);
+        assert_eq!(mixer.active_voices(), 0);
+        assert_eq!(handle.shared.pending.load(Ordering::Acquire), 1);
+
+        // Once there's room the click plays; when it ends with no room left, its voice stays
+        mixer.parked.pop();
+        mixer.next();
+        assert_eq!(mixer.active_voices(), 1);
+        mixer.parked.push(Arc::clone(&filler));
+        mixer.by_ref().take(20).for_each(drop);
+        assert_eq!(mixer.active_voices(), 1);
+        assert_eq!(Arc::strong_count(&sound), 2);
+
+        // Handing the parked sounds over makes room for it on the following frame
+        retired.clear();
+        drop(retired);
+        mixer.by_ref().take(2).for_each(drop);
+        assert_eq!(mixer.active_voices(), 0);
+        assert_eq!(mixer.parked.len(), 1);
+        assert_eq!(Arc::strong_count(&sound), 2);
+    }
+}
@@ -1,7 +1,17 @@
 use crate::error::{AudioError, Result};
-use crate::models::SoundType;
+use crate::models::{Beat, ResampleQuality, SoundType};
 use std::collections::HashMap;
 use std::path::Path;
+use std::time::Instant;
+
+pub mod decode;
+pub mod mixer;
+pub mod resample;
+pub mod wav;
+
+pub use decode::SoundFormat;
+pub use mixer::{ClickMixer, ClickTiming, MixerHandle};
+pub use resample::OutputFormat;
 
 /// Sound data structure for caching audio samples
 #[derive(Debug, Clone)]
@@ -40,59 +50,67 @@ impl SoundData {
             return Err(AudioError::SoundLoadError("File too large (max 10MB)".to_string()).into());
         }
         
-        let sound_type = SoundType::Custom(path.to_path_buf());
+        let bytes = fs::read(path)
+            .map_err(|e| AudioError::SoundLoadError(format!("Cannot read file: {}", e)))?;
         
-        // Validate file format by extension
-        let extension = path.extension()
-            .and_then(|ext| ext.to_str())
-            .map(|s| s.to_lowercase());
+        // Identify the format by content, using the extension only as a fallback
+        let extension = path.extension().and_then(|ext| ext.to_str());
+        let format = decode::identify(&bytes, extension)?;
+        let decoded = decode::decode(&bytes, format)?;
         
-        match extension.as_deref() {
-            Some("wav") => Self::load_wav_file(path, sound_type),
-            Some("mp3") => Self::load_mp3_file(path, sound_type),
-            Some("ogg") => Self::load_ogg_file(path, sound_type),
-            Some(ext) => Err(AudioError::UnsupportedFormat(format!("Unsupported file format: {}", ext)).into()),
-            None => Err(AudioError::UnsupportedFormat("No file extension found".to_string()).into()),
-        }
+        Ok(Self::from_samples(
+            SoundType::Custom(path.to_path_buf()),
+            &decoded.samples,
+            decoded.sample_rate,
+            decoded.channels,
+        ))
     }
     
-    /// Load WAV file (placeholder implementation)
-    fn load_wav_file(path: &Path, sound_type: SoundType) -> Result<Self> {
-        // For now, we'll generate a placeholder sound
-        // In a real implementation, this would use a library like hound to parse WAV files
-        
-        // Try to read file to validate it exists and is readable
-        std::fs::File::open(path)
-            .map_err(|e| AudioError::SoundLoadError(format!("Cannot open WAV file: {}", e)))?;
+    /// Create sound data from interleaved f32 samples
+    pub fn from_samples(sound_type: SoundType, samples: &[f32], sample_rate: u32, channels: u16) -> Self {
+        let mut data = Vec::with_capacity(samples.len() * 4);
+        for sample in samples {
+            data.extend_from_slice(&sample.to_le_bytes());
+        }
         
-        // Generate placeholder sound based on filename
-        Ok(Self::generate_placeholder_custom_sound(sound_type))
+        Self {
+            sound_type,
+            data,
+            sample_rate,
+            channels,
+        }
     }
     
-    /// Load MP3 file (placeholder implementation)
-    fn load_mp3_file(path: &Path, sound_type: SoundType) -> Result<Self> {
-        // For now, we'll generate a placeholder sound
-        // In a real implementation, this would use a library like symphonia to parse MP3 files
-        
-        // Try to read file to validate it exists and is readable
-        std::fs::File::open(path)
-            .map_err(|e| AudioError::SoundLoadError(format!("Cannot open MP3 file: {}", e)))?;
-        
-        // Generate placeholder sound based on filename
-        Ok(Self::generate_placeholder_custom_sound(sound_type))
+    /// Get the interleaved f32 samples stored in the data buffer
+    pub fn samples(&self) -> Vec<f32> {
+        self.data
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect()
     }
     
-    /// Load OGG file (placeholder implementation)
-    fn load_ogg_file(path: &Path, sound_type: SoundType) -> Result<Self> {
-        // For now, we'll generate a placeholder sound
-        // In a real implementation, this would use a library like lewton or symphonia to parse OGG files
-        
-        // Try to read file to validate it exists and is readable
-        std::fs::File::open(path)
-            .map_err(|e| AudioError::SoundLoadError(format!("Cannot open OGG file: {}", e)))?;
-        
-        // Generate placeholder sound based on filename
-        Ok(Self::generate_placeholder_custom_sound(sound_type))
+    /// Get the samples with all channels averaged into one
+    pub fn mono_samples(&self) -> Vec<f32> {
+        let channels = self.channels.max(1) as usize;
+        self.samples()
+            .chunks_exact(channels)
+            .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+            .collect()
+    }
+    
+    /// Get the samples converted to the sample rate and channel count of an output stream
+    pub fn to_output_format(&self, output: OutputFormat, quality: ResampleQuality) -> Vec<f32> {
+        resample::convert(&self.samples(), self.sample_rate, self.channels, output, quality)
+    }
+    
+    /// Get the number of sample frames (one sample per channel)
+    pub fn frame_count(&self) -> usize {
+        self.data.len() / 4 / self.channels.max(1) as usize
+    }
+    
+    /// Get the playback length of the sound
+    pub fn duration(&self) -> std::time::Duration {
+        std::time::Duration::from_secs_f64(self.frame_count() as f64 / self.sample_rate.max(1) as f64)
     }
     
     /// Generate click sound data
@@ -193,52 +211,6 @@ impl SoundData {
             channels: 1,
         }
     }
-    
-    /// Generate placeholder custom sound (for demonstration)
-    fn generate_placeholder_custom_sound(sound_type: SoundType) -> Self {
-        // For now, generate a unique sound based on the filename
-        let sample_rate = 44100;
-        let duration_ms = 120;
-        let samples = (sample_rate * duration_ms / 1000) as usize;
-        
-        // Use a different frequency based on the sound type
-        let frequency = match &sound_type {
-            SoundType::Custom(path) => {
-                // Generate frequency based on filename hash for uniqueness
-                let filename = path.file_name()
-                    .and_then(|n| n.to_str())
-                    .unwrap_or("default");
-                let hash = filename.chars().fold(0u32, |acc, c| acc.wrapping_add(c as u32));
-                600.0 + (hash % 800) as f32 // Frequency between 600-1400 Hz
-            }
-            _ => 800.0,
-        };
-        
-        let mut data = Vec::with_capacity(samples * 4);
-        for i in 0..samples {
-            let t = i as f32 / sample_rate as f32;
-            let amplitude = 0.35;
-            
-            // Smooth envelope
-            let envelope = if i < 2000 {
-                i as f32 / 2000.0
-            } else if i > samples - 2000 {
-                (samples - i) as f32 / 2000.0
-            } else {
-                1.0
-            };
-            
-            let sample = (t * frequency * 2.0 * std::f32::consts::PI).sin() * amplitude * envelope;
-            data.extend_from_slice(&sample.to_le_bytes());
-        }
-        
-        Self {
-            sound_type,
-            data,
-            sample_rate,
-            channels: 1,
-        }
-    }
 }
 
 pub trait AudioPlayer {
@@ -248,11 +220,37 @@ pub trait AudioPlayer {
     fn initialize(&mut self) -> Result<()>;
     fn preload_sounds(&mut self, sounds: &[SoundType]) -> Result<()>;
     fn as_any(&self) -> &dyn std::any::Any;
+    
+    /// Change the interpolation used to convert sounds to the output format
+    fn set_resample_quality(&mut self, _quality: ResampleQuality) -> Result<()> {
+        Ok(())
+    }
+    
+    /// Get the sample rate and channel count of the output stream, if known
+    fn output_format(&self) -> Option<OutputFormat> {
+        None
+    }
+    
+    /// Play a sound on the output frame that a point in time maps to
+    ///
+    /// Players without a sample clock play the sound right away.
+    fn play_sound_at(&self, sound_type: &SoundType, volume: f32, _at: Instant) -> Result<()> {
+        self.play_sound_with_volume(sound_type, volume)
+    }
+    
+    /// Report how far scheduled sounds started from their frames, if measured
+    fn click_timing(&self) -> Option<ClickTiming> {
+        None
+    }
+    
+    /// Clear the click timing measurements
+    fn reset_click_timing(&self) {}
 }
 
 pub struct CrossPlatformAudio {
     player: Option<Box<dyn AudioPlayer>>,
     fallback_enabled: bool,
+    resample_quality: ResampleQuality,
     sound_cache: HashMap<SoundType, SoundData>,
 }
 
@@ -261,6 +259,7 @@ impl CrossPlatformAudio {
         Self {
             player: None,
             fallback_enabled: true,
+            resample_quality: ResampleQuality::default(),
             sound_cache: HashMap::new(),
         }
     }
@@ -309,7 +308,7 @@ impl CrossPlatformAudio {
     
     #[cfg(feature = "audio")]
     fn create_platform_player(&self) -> Result<Box<dyn AudioPlayer>> {
-        Ok(Box::new(RodioAudioPlayer::new()?))
+        Ok(Box::new(RodioAudioPlayer::new()?.with_resample_quality(self.resample_quality)))
     }
     
     pub fn play_sound(&self, sound_type: &SoundType) -> Result<()> {
@@ -332,6 +331,37 @@ impl CrossPlatformAudio {
         }
     }
     
+    /// Play a sound on the output frame that a point in time maps to
+    pub fn play_sound_at(&self, sound_type: &SoundType, volume: f32, at: Instant) -> Result<()> {
+        match &self.player {
+            Some(player) => player.play_sound_at(sound_type, volume, at),
+            None => {
+                // Fallback to visual indication only
+                Ok(())
+            }
+        }
+    }
+    
+    /// Hand a beat's click to the audio path to start at the beat's scheduled time
+    ///
+    /// The click lands on the sample frame computed from the beat's grid position
+    /// rather than whenever this call happens, so thread wake-up jitter is not heard.
+    pub fn schedule_beat(&self, beat: &Beat, sound_type: &SoundType, volume: f32) -> Result<()> {
+        self.play_sound_at(sound_type, volume, beat.scheduled_time)
+    }
+    
+    /// Report how far scheduled clicks started from their scheduled times
+    pub fn click_timing(&self) -> Option<ClickTiming> {
+        self.player.as_ref().and_then(|player| player.click_timing())
+    }
+    
+    /// Clear the click timing measurements, e.g. when playback starts
+    pub fn reset_click_timing(&self) {
+        if let Some(player) = &self.player {
+            player.reset_click_timing();
+        }
+    }
+    
     pub fn play_beat_sound(&self) -> Result<()> {
         self.play_sound(&SoundType::BuiltinClick)
     }
@@ -368,6 +398,29 @@ impl CrossPlatformAudio {
         self
     }
     
+    pub fn with_resample_quality(mut self, quality: ResampleQuality) -> Self {
+        self.resample_quality = quality;
+        self
+    }
+    
+    /// Change the resampling quality, converting the player's sounds again
+    pub fn set_resample_quality(&mut self, quality: ResampleQuality) -> Result<()> {
+        self.resample_quality = quality;
+        match &mut self.player {
+            Some(player) => player.set_resample_quality(quality),
+            None => Ok(()),
+        }
+    }
+    
+    pub fn get_resample_quality(&self) -> ResampleQuality {
+        self.resample_quality
+    }
+    
+    /// Get the sample rate and channel count sounds are converted to, if audio is running
+    pub fn output_format(&self) -> Option<OutputFormat> {
+        self.player.as_ref().and_then(|player| player.output_format())
+    }
+    
     #[cfg(feature = "audio")]
     pub fn test_audio_system(&self) -> bool {
         // Simple test that doesn't require thread safety
@@ -548,21 +601,19 @@ impl CrossPlatformAudio {
             return Err(AudioError::SoundLoadError("File too large (max 10MB)".to_string()).into());
         }
         
-        // Check file extension
-        let extension = path.extension()
-            .and_then(|ext| ext.to_str())
-            .map(|s| s.to_lowercase());
+        // Identify the format and probe the stream so broken files are caught up front
+        let bytes = fs::read(path)
+            .map_err(|e| AudioError::SoundLoadError(format!("Cannot read file: {}", e)))?;
+        let extension = path.extension().and_then(|ext| ext.to_str());
+        let format = decode::identify(&bytes, extension)?;
+        decode::probe(&bytes, format)?;
         
-        match extension.as_deref() {
-            Some("wav") | Some("mp3") | Some("ogg") => Ok(()),
-            Some(ext) => Err(AudioError::UnsupportedFormat(format!("Unsupported file format: {}", ext)).into()),
-            None => Err(AudioError::UnsupportedFormat("No file extension found".to_string()).into()),
-        }
+        Ok(())
     }
     
     /// Get supported file extensions
     pub fn get_supported_extensions() -> &'static [&'static str] {
-        &["wav", "mp3", "ogg"]
+        &["wav", "mp3", "ogg", "flac"]
     }
 }
 
@@ -595,143 +646,92 @@ impl std::fmt::Display for AudioStatus {
 #[cfg(feature = "audio")]
 mod rodio_player {
     use super::*;
+    use rodio::cpal::traits::{DeviceTrait, HostTrait};
     use rodio::{OutputStream, OutputStreamHandle, Sink};
-    use std::time::Duration;
+    use std::sync::Arc;
 
     pub struct RodioAudioPlayer {
         _stream: OutputStream,
         stream_handle: OutputStreamHandle,
         sink: Option<Sink>,
-        sound_cache: HashMap<SoundType, Vec<f32>>,
+        /// Handle of the mixer that plays every click on the stream
+        mixer: MixerHandle,
+        /// Format of the device stream that every cached sound is converted to
+        output_format: OutputFormat,
+        resample_quality: ResampleQuality,
+        sound_cache: HashMap<SoundType, Arc<[f32]>>,
     }
 
     impl RodioAudioPlayer {
         pub fn new() -> Result<Self> {
-            let (stream, stream_handle) = OutputStream::try_default()
+            let (stream, stream_handle, output_format) = Self::open_output_stream()?;
+            
+            // One mixer stays on the stream for the player's lifetime
+            let (mixer_source, mixer) = ClickMixer::new(output_format);
+            stream_handle.play_raw(mixer_source)
                 .map_err(|e| AudioError::InitializationFailed(e.to_string()))?;
             
             Ok(Self {
                 _stream: stream,
                 stream_handle,
                 sink: None,
+                mixer,
+                output_format,
+                resample_quality: ResampleQuality::default(),
                 sound_cache: HashMap::new(),
             })
         }
         
-        fn generate_beep_sound(&self, frequency: f32, duration: Duration) -> Vec<f32> {
-            let sample_rate = 44100;
-            let samples = (sample_rate as f32 * duration.as_secs_f32()) as usize;
-            
-            (0..samples)
-                .map(move |i| {
-                    let t = i as f32 / sample_rate as f32;
-                    let amplitude = 0.3; // Volume level
-                    let wave = (t * frequency * 2.0 * std::f32::consts::PI).sin();
-                    
-                    // Apply envelope to avoid clicks
-                    let envelope = if i < 1000 {
-                        i as f32 / 1000.0
-                    } else if i > samples - 1000 {
-                        (samples - i) as f32 / 1000.0
-                    } else {
-                        1.0
-                    };
-                    
-                    wave * amplitude * envelope
-                })
-                .collect()
-        }
-        
-        fn generate_sound_samples(&self, sound_type: &SoundType) -> Result<Vec<f32>> {
-            match sound_type {
-                SoundType::BuiltinClick => Ok(self.generate_click_samples()),
-                SoundType::BuiltinWood => Ok(self.generate_wood_samples()),
-                SoundType::BuiltinBeep => Ok(self.generate_beep_sound(1000.0, Duration::from_millis(100))),
-                SoundType::Custom(path) => self.load_custom_samples(path),
-            }
+        pub fn with_resample_quality(mut self, quality: ResampleQuality) -> Self {
+            self.resample_quality = quality;
+            self
         }
         
-        fn generate_click_samples(&self) -> Vec<f32> {
-            let sample_rate = 44100;
-            let duration_ms = 50;
-            let samples = (sample_rate * duration_ms / 1000) as usize;
+        /// Open the default output device with its default configuration, falling back
+        /// to the other devices, and report the format the stream runs at
+        fn open_output_stream() -> Result<(OutputStream, OutputStreamHandle, OutputFormat)> {
+            let open = |device: &rodio::cpal::Device| -> Result<(OutputStream, OutputStreamHandle, OutputFormat)> {
+                let config = device.default_output_config()
+                    .map_err(|e| AudioError::InitializationFailed(e.to_string()))?;
+                let format = OutputFormat::new(config.sample_rate().0, config.channels());
+                let (stream, handle) = OutputStream::try_from_device_config(device, config)
+                    .map_err(|e| AudioError::InitializationFailed(e.to_string()))?;
+                Ok((stream, handle, format))
+            };
             
-            (0..samples)
-                .map(|i| {
-                    let t = i as f32 / sample_rate as f32;
-                    let frequency = 2000.0; // High frequency for click
-                    let amplitude = 0.5;
-                    
-                    // Sharp attack, quick decay
-                    let envelope = if i < samples / 10 {
-                        1.0
-                    } else {
-                        (1.0 - (i - samples / 10) as f32 / (samples - samples / 10) as f32).max(0.0)
-                    };
-                    
-                    (t * frequency * 2.0 * std::f32::consts::PI).sin() * amplitude * envelope
-                })
-                .collect()
+            let host = rodio::cpal::default_host();
+            let default_device = host.default_output_device().ok_or(AudioError::DeviceNotAvailable)?;
+            open(&default_device).or_else(|original_err| {
+                // The default device didn't work, try the other ones
+                host.output_devices()
+                    .ok()
+                    .and_then(|mut devices| devices.find_map(|device| open(&device).ok()))
+                    .ok_or(original_err)
+            })
         }
         
-        fn generate_wood_samples(&self) -> Vec<f32> {
-            let sample_rate = 44100;
-            let duration_ms = 80;
-            let samples = (sample_rate * duration_ms / 1000) as usize;
-            
-            (0..samples)
-                .map(|i| {
-                    let t = i as f32 / sample_rate as f32;
-                    let fundamental = 800.0;
-                    let amplitude = 0.4;
-                    
-                    // Wood block has multiple harmonics
-                    let harmonic1 = (t * fundamental * 2.0 * std::f32::consts::PI).sin() * 0.6;
-                    let harmonic2 = (t * fundamental * 3.0 * 2.0 * std::f32::consts::PI).sin() * 0.3;
-                    let harmonic3 = (t * fundamental * 5.0 * 2.0 * std::f32::consts::PI).sin() * 0.1;
-                    
-                    // Exponential decay envelope
-                    let envelope = (-t * 8.0).exp();
-                    
-                    (harmonic1 + harmonic2 + harmonic3) * amplitude * envelope
-                })
-                .collect()
+        /// Load a sound and convert it to the output format
+        fn generate_sound_samples(&self, sound_type: &SoundType) -> Result<Vec<f32>> {
+            let sound = match sound_type {
+                SoundType::Custom(path) => SoundData::from_file(path)?,
+                builtin => SoundData::from_builtin(builtin.clone())?,
+            };
+            Ok(sound.to_output_format(self.output_format, self.resample_quality))
         }
         
-        fn load_custom_samples(&self, path: &Path) -> Result<Vec<f32>> {
-            // For now, generate a placeholder sound based on filename
-            // In a real implementation, this would use a library like symphonia or hound
-            let filename = path.file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("default");
-            let hash = filename.chars().fold(0u32, |acc, c| acc.wrapping_add(c as u32));
-            let frequency = 600.0 + (hash % 800) as f32; // Frequency between 600-1400 Hz
-            
-            Ok(self.generate_beep_sound(frequency, Duration::from_millis(120)))
+        /// Get samples in the output format, from the cache if they were preloaded
+        fn sound_samples(&self, sound_type: &SoundType) -> Result<Arc<[f32]>> {
+            match self.sound_cache.get(sound_type) {
+                Some(samples) => Ok(Arc::clone(samples)),
+                // Generate sound on-the-fly if not cached
+                None => Ok(self.generate_sound_samples(sound_type)?.into()),
+            }
         }
     }
 
     impl AudioPlayer for RodioAudioPlayer {
         fn play_sound(&self, sound_type: &SoundType) -> Result<()> {
-            use rodio::buffer::SamplesBuffer;
-            
-            // Create a new sink for each sound to avoid blocking
-            let sink = Sink::try_new(&self.stream_handle)
-                .map_err(|e| AudioError::PlaybackFailed(e.to_string()))?;
-            
-            // Get sound samples from cache or generate them
-            let samples = if let Some(cached_samples) = self.sound_cache.get(sound_type) {
-                cached_samples.clone()
-            } else {
-                // Generate sound on-the-fly if not cached
-                self.generate_sound_samples(sound_type)?
-            };
-            
-            let sound_source = SamplesBuffer::new(1, 44100, samples);
-            sink.append(sound_source);
-            sink.detach(); // Let it play independently
-            
-            Ok(())
+            self.mixer.play(self.sound_samples(sound_type)?, 1.0)
         }
         
         fn is_available(&self) -> bool {
@@ -752,38 +752,46 @@ mod rodio_player {
             for sound_type in sounds {
                 if !self.sound_cache.contains_key(sound_type) {
                     let samples = self.generate_sound_samples(sound_type)?;
-                    self.sound_cache.insert(sound_type.clone(), samples);
+                    self.sound_cache.insert(sound_type.clone(), samples.into());
                 }
             }
             Ok(())
         }
         
         fn play_sound_with_volume(&self, sound_type: &SoundType, volume: f32) -> Result<()> {
-            use rodio::buffer::SamplesBuffer;
-            
             // Clamp volume to valid range
             let volume = volume.clamp(0.0, 1.0);
             
-            // Create a new sink for each sound to avoid blocking
-            let sink = Sink::try_new(&self.stream_handle)
-                .map_err(|e| AudioError::PlaybackFailed(e.to_string()))?;
-            
-            // Set volume on the sink
-            sink.set_volume(volume);
-            
-            // Get sound samples from cache or generate them
-            let samples = if let Some(cached_samples) = self.sound_cache.get(sound_type) {
-                cached_samples.clone()
-            } else {
-                // Generate sound on-the-fly if not cached
-                self.generate_sound_samples(sound_type)?
-            };
-            
-            let sound_source = SamplesBuffer::new(1, 44100, samples);
-            sink.append(sound_source);
-            sink.detach(); // Let it play independently
+            // The mixer applies the volume while summing, no sink per click
+            self.mixer.play(self.sound_samples(sound_type)?, volume)
+        }
+        
+        fn play_sound_at(&self, sound_type: &SoundType, volume: f32, at: Instant) -> Result<()> {
+            let volume = volume.clamp(0.0, 1.0);
+            self.mixer.play_at_time(self.sound_samples(sound_type)?, volume, at)
+        }
+        
+        fn click_timing(&self) -> Option<ClickTiming> {
+            Some(self.mixer.timing())
+        }
+        
+        fn reset_click_timing(&self) {
+            self.mixer.reset_timing();
+        }
+        
+        fn set_resample_quality(&mut self, quality: ResampleQuality) -> Result<()> {
+            if quality == self.resample_quality {
+                return Ok(());
+            }
             
-            Ok(())
+            // Convert the cached sounds again with the new interpolation
+            self.resample_quality = quality;
+            let sounds: Vec<SoundType> = self.sound_cache.drain().map(|(sound_type, _)| sound_type).collect();
+            self.preload_sounds(&sounds)
+        }
+        
+        fn output_format(&self) -> Option<OutputFormat> {
+            Some(self.output_format)
         }
         
         fn as_any(&self) -> &dyn std::any::Any {
@@ -862,15 +870,37 @@ impl AudioEngine {
     }
     
     /// Play sound based on beat information
-    pub fn play_beat_from_info(&self, beat: &crate::models::Beat) -> Result<()> {
+    pub fn play_beat_from_info(&self, beat: &Beat) -> Result<()> {
+        if beat.is_muted() {
+            return Ok(());
+        }
         self.play_beat_with_accent(beat.is_accent)
     }
     
+    /// Schedule the sound of a beat at its grid time instead of playing it now
+    pub fn schedule_beat_from_info(&self, beat: &Beat) -> Result<()> {
+        if beat.is_muted() {
+            return Ok(());
+        }
+        let sound_type = if beat.is_accent { &self.accent_sound } else { &self.beat_sound };
+        self.audio_system.schedule_beat(beat, sound_type, 1.0)
+    }
+    
+    /// Report how far scheduled beats started from their scheduled times
+    pub fn click_timing(&self) -> Option<ClickTiming> {
+        self.audio_system.click_timing()
+    }
+    
     /// Load a custom sound file and return the sound type
     pub fn load_custom_sound(&mut self, path: &Path) -> Result<SoundType> {
         self.audio_system.load_custom_sound(path)
     }
     
+    /// Change the interpolation used to convert sounds to the output sample rate
+    pub fn set_resample_quality(&mut self, quality: ResampleQuality) -> Result<()> {
+        self.audio_system.set_resample_quality(quality)
+    }
+    
     /// Check if audio is available
     pub fn is_available(&self) -> bool {
         self.audio_system.is_audio_available()
@@ -923,6 +953,7 @@ impl Default for AudioEngine {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::Arc;
     
     #[test]
     fn test_audio_creation() {
@@ -936,6 +967,27 @@ mod tests {
         assert_eq!(audio.get_audio_status(), AudioStatus::FallbackMode);
     }
     
+    #[test]
+    fn test_resample_quality_without_player() {
+        let mut audio = CrossPlatformAudio::new().with_resample_quality(ResampleQuality::High);
+        assert_eq!(audio.get_resample_quality(), ResampleQuality::High);
+        assert_eq!(audio.output_format(), None);
+        
+        assert!(audio.set_resample_quality(ResampleQuality::Fast).is_ok());
+        assert_eq!(audio.get_resample_quality(), ResampleQuality::Fast);
+    }
+    
+    #[test]
+    fn test_scheduled_beats_without_player() {
+        let audio = CrossPlatformAudio::new();
+        let beat = Beat::new(1, crate::models::TimeSignature::new(4, 4), 120);
+        
+        // Without a player there is no sample clock to measure against
+        assert!(audio.schedule_beat(&beat, &SoundType::BuiltinClick, 0.5).is_ok());
+        assert_eq!(audio.click_timing(), None);
+        audio.reset_click_timing();
+    }
+    
     #[test]
     fn test_audio_status_display() {
         assert_eq!(AudioStatus::Available.to_string(), "Audio available");
@@ -1031,8 +1083,429 @@ mod tests {
         assert!(supported.contains(&"wav"));
         assert!(supported.contains(&"mp3"));
         assert!(supported.contains(&"ogg"));
+        assert!(supported.contains(&"flac"));
+    }
+    
+    /// Build a WAV file from a format tag, channel count, sample rate, bit depth and raw data
+    fn wav_bytes(format_tag: u16, channels: u16, sample_rate: u32, bits: u16, data: &[u8]) -> Vec<u8> {
+        let block_align = channels * bits / 8;
+        let mut fmt = Vec::new();
+        fmt.extend_from_slice(&format_tag.to_le_bytes());
+        fmt.extend_from_slice(&channels.to_le_bytes());
+        fmt.extend_from_slice(&sample_rate.to_le_bytes());
+        fmt.extend_from_slice(&(sample_rate * block_align as u32).to_le_bytes());
+        fmt.extend_from_slice(&block_align.to_le_bytes());
+        fmt.extend_from_slice(&bits.to_le_bytes());
+        
+        let mut bytes = b"RIFF".to_vec();
+        bytes.extend_from_slice(&((20 + fmt.len() + data.len()) as u32).to_le_bytes());
+        bytes.extend_from_slice(b"WAVE");
+        bytes.extend_from_slice(b"fmt ");
+        bytes.extend_from_slice(&(fmt.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&fmt);
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(data);
+        bytes
+    }
+    
+    #[test]
+    fn test_wav_decoding_sample_formats() {
+        let decoded = wav::decode(&wav_bytes(1, 1, 8000, 8, &[0, 128, 255])).unwrap();
+        assert_eq!(decoded.samples, vec![-1.0, 0.0, 127.0 / 128.0]);
+        assert_eq!(decoded.sample_rate, 8000);
+        
+        let data: Vec<u8> = [i16::MIN, 0, 16384].iter().flat_map(|s| s.to_le_bytes()).collect();
+        let decoded = wav::decode(&wav_bytes(1, 1, 44100, 16, &data)).unwrap();
+        assert_eq!(decoded.samples, vec![-1.0, 0.0, 0.5]);
+        
+        // 24-bit samples: -8388608, 4194304
+        let decoded = wav::decode(&wav_bytes(1, 1, 48000, 24, &[0x00, 0x00, 0x80, 0x00, 0x00, 0x40])).unwrap();
+        assert_eq!(decoded.samples, vec![-1.0, 0.5]);
+        
+        let data: Vec<u8> = [i32::MIN, i32::MAX / 2 + 1].iter().flat_map(|s| s.to_le_bytes()).collect();
+        let decoded = wav::decode(&wav_bytes(1, 1, 96000, 32, &data)).unwrap();
+        assert_eq!(decoded.samples, vec![-1.0, 0.5]);
+        
+        let data: Vec<u8> = [0.25f32, -0.75, f32::NAN].iter().flat_map(|s| s.to_le_bytes()).collect();
+        let decoded = wav::decode(&wav_bytes(3, 1, 44100, 32, &data)).unwrap();
+        assert_eq!(decoded.samples, vec![0.25, -0.75, 0.0]);
+        
+        let data: Vec<u8> = [0.5f64, 2.0].iter().flat_map(|s| s.to_le_bytes()).collect();
+        let decoded = wav::decode(&wav_bytes(3, 1, 44100, 64, &data)).unwrap();
+        assert_eq!(decoded.samples, vec![0.5, 1.0]);
+    }
+    
+    #[test]
+    fn test_wav_decoding_stereo_and_extensible() {
+        let data: Vec<u8> = [16384i16, -16384, 0, 8192, 1].iter().flat_map(|s| s.to_le_bytes()).collect();
+        let decoded = wav::decode(&wav_bytes(1, 2, 48000, 16, &data)).unwrap();
+        assert_eq!(decoded.channels, 2);
+        // The incomplete trailing frame is dropped
+        assert_eq!(decoded.samples, vec![0.5, -0.5, 0.0, 0.25]);
+        
+        let sound = SoundData::from_samples(SoundType::BuiltinClick, &decoded.samples, 48000, 2);
+        assert_eq!(sound.samples(), decoded.samples);
+        assert_eq!(sound.mono_samples(), vec![0.0, 0.125]);
+        assert_eq!(sound.frame_count(), 2);
+        
+        // WAVE_FORMAT_EXTENSIBLE with a PCM sub-format and an extra chunk before the data
+        let mut bytes = wav_bytes(1, 1, 22050, 16, &[0x00, 0x40]);
+        let mut extension = vec![22, 0, 16, 0, 4, 0, 0, 0, 1, 0];
+        extension.extend_from_slice(&[0; 14]);
+        bytes[20..22].copy_from_slice(&0xFFFEu16.to_le_bytes());
+        bytes[16..20].copy_from_slice(&40u32.to_le_bytes());
+        let data_chunk = bytes.split_off(36);
+        bytes.extend_from_slice(&extension);
+        bytes.extend_from_slice(b"LIST\x03\0\0\0abc\0");
+        bytes.extend_from_slice(&data_chunk);
+        let decoded = wav::decode(&bytes).unwrap();
+        assert_eq!(decoded.samples, vec![0.5]);
+        assert_eq!(decoded.sample_rate, 22050);
+    }
+    
+    #[test]
+    fn test_malformed_wav_files() {
+        let reason = |bytes: &[u8]| wav::decode(bytes).unwrap_err().to_string();
+        
+        assert!(reason(b"OggS not a wave file").contains("Not a RIFF/WAVE file"));
+        assert!(reason(&wav_bytes(1, 1, 44100, 12, &[0, 0])).contains("12-bit integer"));
+        assert!(reason(&wav_bytes(3, 1, 44100, 16, &[0, 0])).contains("16-bit float"));
+        assert!(reason(&wav_bytes(2, 1, 44100, 4, &[0, 0])).contains("0x0002"));
+        assert!(reason(&wav_bytes(1, 6, 44100, 16, &[0; 12])).contains("only mono and stereo"));
+        assert!(reason(&wav_bytes(1, 1, 0, 16, &[0, 0])).contains("0 Hz"));
+        assert!(reason(&wav_bytes(1, 1, 44100, 16, &[])).contains("no audio samples"));
+        
+        let mut bad_align = wav_bytes(1, 2, 44100, 16, &[0; 8]);
+        bad_align[32] = 2;
+        assert!(reason(&bad_align).contains("block align"));
+        
+        let no_data = wav_bytes(1, 1, 44100, 16, &[]);
+        assert!(reason(&no_data[..36]).contains("no data chunk"));
+        assert!(reason(&no_data[..30]).contains("fmt chunk is truncated"));
+    }
+    
+    #[cfg(feature = "audio")]
+    /// Build a FLAC file holding one verbatim 16-bit frame of interleaved samples
+    fn flac_bytes(samples: &[i16], sample_rate: u32, channels: u16) -> Vec<u8> {
+        fn crc8(data: &[u8]) -> u8 {
+            data.iter().fold(0u8, |crc, &b| {
+                (0..8).fold(crc ^ b, |crc, _| if crc & 0x80 != 0 { (crc << 1) ^ 0x07 } else { crc << 1 })
+            })
+        }
+        fn crc16(data: &[u8]) -> u16 {
+            data.iter().fold(0u16, |crc, &b| {
+                (0..8).fold(crc ^ ((b as u16) << 8), |crc, _| if crc & 0x8000 != 0 { (crc << 1) ^ 0x8005 } else { crc << 1 })
+            })
+        }
+        
+        let channels_usize = channels as usize;
+        let frames = samples.len() / channels_usize;
+        
+        // STREAMINFO: block sizes, unknown frame sizes, packed rate/channels/bits/length, no MD5
+        let mut bytes = b"fLaC".to_vec();
+        bytes.extend_from_slice(&[0x80, 0, 0, 34]);
+        bytes.extend_from_slice(&(frames as u16).to_be_bytes());
+        bytes.extend_from_slice(&(frames as u16).to_be_bytes());
+        bytes.extend_from_slice(&[0; 6]);
+        let packed = (sample_rate as u64) << 44 | (channels as u64 - 1) << 41 | 15 << 36 | frames as u64;
+        bytes.extend_from_slice(&packed.to_be_bytes());
+        bytes.extend_from_slice(&[0; 16]);
+        
+        // Frame header: fixed blocking, 8-bit block size, rate from STREAMINFO, 16-bit samples
+        let mut frame = vec![0xFF, 0xF8, 0x60, ((channels as u8 - 1) << 4) | 0x08, 0x00, (frames - 1) as u8];
+        frame.push(crc8(&frame));
+        for channel in 0..channels_usize {
+            frame.push(0x02); // verbatim subframe
+            for i in 0..frames {
+                frame.extend_from_slice(&samples[i * channels_usize + channel].to_be_bytes());
+            }
+        }
+        let crc = crc16(&frame);
+        frame.extend_from_slice(&crc.to_be_bytes());
+        
+        bytes.extend_from_slice(&frame);
+        bytes
+    }
+    
+    #[test]
+    fn test_sound_format_detection() {
+        use decode::identify;
+        
+        assert_eq!(SoundFormat::detect(&wav_bytes(1, 1, 44100, 16, &[0, 0])), Some(SoundFormat::Wav));
+        assert_eq!(SoundFormat::detect(b"fLaC\0\0\0\x22"), Some(SoundFormat::Flac));
+        assert_eq!(SoundFormat::detect(b"OggS\0\x02"), Some(SoundFormat::OggVorbis));
+        assert_eq!(SoundFormat::detect(b"ID3\x04\0"), Some(SoundFormat::Mp3));
+        assert_eq!(SoundFormat::detect(&[0xFF, 0xFB, 0x90, 0x64]), Some(SoundFormat::Mp3));
+        assert_eq!(SoundFormat::detect(b"plain text"), None);
+        
+        // Content wins over the extension; the extension is only a fallback
+        assert_eq!(identify(b"fLaC", Some("wav")).unwrap(), SoundFormat::Flac);
+        assert_eq!(identify(&[0, 0, 0, 0], Some("MP3")).unwrap(), SoundFormat::Mp3);
+        assert!(identify(b"plain text", Some("txt")).unwrap_err().to_string().contains("txt"));
+        assert!(identify(b"plain text", None).is_err());
+        assert_eq!(SoundFormat::OggVorbis.to_string(), "OGG Vorbis");
+    }
+    
+    #[test]
+    #[cfg(feature = "audio")]
+    fn test_flac_decoding() {
+        let samples: Vec<i16> = (0..16).flat_map(|i| [i * 1000, -i * 1000]).collect();
+        let bytes = flac_bytes(&samples, 48000, 2);
+        
+        assert!(decode::probe(&bytes, SoundFormat::Flac).is_ok());
+        let decoded = decode::decode(&bytes, SoundFormat::Flac).unwrap();
+        assert_eq!(decoded.sample_rate, 48000);
+        assert_eq!(decoded.channels, 2);
+        let expected: Vec<f32> = samples.iter().map(|&s| s as f32 / 32768.0).collect();
+        assert_eq!(decoded.samples, expected);
+        
+        let mono = flac_bytes(&[8192; 16], 44100, 1);
+        assert_eq!(decode::decode(&mono, SoundFormat::Flac).unwrap().samples, vec![0.25; 16]);
+    }
+    
+    #[test]
+    #[cfg(feature = "audio")]
+    fn test_malformed_compressed_streams() {
+        let reason = |bytes: &[u8]| {
+            let format = SoundFormat::detect(bytes).unwrap();
+            decode::probe(bytes, format).unwrap_err().to_string()
+        };
+        
+        assert!(reason(b"fLaC but not really a FLAC stream").contains("Not a valid FLAC stream"));
+        assert!(reason(b"OggS but not really an Ogg stream").contains("Not a valid OGG Vorbis stream"));
+        assert!(reason(b"ID3 but not really an MP3 stream").contains("MP3"));
+        
+        // A FLAC frame with a broken checksum decodes to nothing
+        let mut bytes = flac_bytes(&[100; 16], 44100, 1);
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xFF;
+        assert!(decode::decode(&bytes, SoundFormat::Flac).unwrap_err().to_string().contains("no audio samples"));
     }
     
+    #[test]
+    fn test_channel_conversion() {
+        use resample::convert_channels;
+        
+        let stereo = [0.5, -0.5, 1.0, 0.0];
+        assert_eq!(convert_channels(&stereo, 2, 1), vec![0.0, 0.5]);
+        assert_eq!(convert_channels(&[0.25, -0.5], 1, 2), vec![0.25, 0.25, -0.5, -0.5]);
+        assert_eq!(convert_channels(&stereo, 2, 4), vec![0.5, -0.5, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0]);
+        assert_eq!(convert_channels(&stereo, 2, 2), stereo.to_vec());
+    }
+    
+    #[test]
+    fn test_resampling_keeps_pitch_and_length() {
+        // 100 ms of a 1 kHz tone
+        let tone = |sample_rate: u32| -> Vec<f32> {
+            (0..sample_rate as usize / 10)
+                .map(|i| (i as f32 * 1000.0 * 2.0 * std::f32::consts::PI / sample_rate as f32).sin() * 0.5)
+                .collect()
+        };
+        let input = tone(44100);
+        let expected = tone(48000);
+        
+        for (&quality, max_error) in ResampleQuality::all().iter().zip([0.002, 0.0002, 0.0001]) {
+            let output = resample::resample(&input, 1, 44100, 48000, quality);
+            assert_eq!(output.len(), expected.len());
+            
+            // Compare away from the edges, where the tone starts from and stops into silence
+            let error = output[200..4600].iter()
+                .zip(&expected[200..4600])
+                .map(|(a, b)| (a - b).abs())
+                .fold(0.0, f32::max);
+            assert!(error < max_error, "{:?} resampling error {}", quality, error);
+        }
+        
+        assert_eq!(resample::resample(&input, 1, 44100, 44100, ResampleQuality::High), input);
+    }
+    
+    #[test]
+    fn test_high_quality_resampling_filters_aliasing() {
+        // A 30 kHz tone at 96 kHz cannot be represented at 44.1 kHz and should be removed
+        let ultrasonic: Vec<f32> = (0..9600)
+            .map(|i| (i as f32 * 30000.0 * 2.0 * std::f32::consts::PI / 96000.0).sin() * 0.5)
+            .collect();
+        let level = |quality| {
+            let output = resample::resample(&ultrasonic, 1, 96000, 44100, quality);
+            let middle = &output[500..3900];
+            (middle.iter().map(|s| s * s).sum::<f32>() / middle.len() as f32).sqrt()
+        };
+        
+        assert!(level(ResampleQuality::Fast) > 0.05);
+        assert!(level(ResampleQuality::High) < 0.01);
+    }
+    
+    #[test]
+    fn test_sound_data_to_output_format() {
+        let click = SoundData::from_builtin(SoundType::BuiltinClick).unwrap();
+        let output = OutputFormat::new(48000, 2);
+        let converted = click.to_output_format(output, ResampleQuality::Fast);
+        
+        // 2205 frames at 44.1 kHz last as long as 2400 frames at 48 kHz
+        assert_eq!(click.frame_count(), 2205);
+        assert_eq!(converted.len(), 2400 * 2);
+        assert_eq!(converted[100], converted[101]);
+        
+        // A 96 kHz stereo sound folds down to half as many mono frames at 48 kHz
+        let samples: Vec<f32> = (0..960).flat_map(|i| [i as f32 / 960.0, 0.0]).collect();
+        let hi_res = SoundData::from_samples(SoundType::BuiltinBeep, &samples, 96000, 2);
+        let converted = hi_res.to_output_format(OutputFormat::new(48000, 1), ResampleQuality::Balanced);
+        assert_eq!(converted.len(), 480);
+        assert!((converted[240] - 0.25).abs() < 0.01);
+        assert_eq!(OutputFormat::new(48000, 2).to_string(), "48000 Hz stereo");
+    }
+
+    #[test]
+    fn test_mixer_starts_clicks_on_their_frame() {
+        let (mut mixer, handle) = ClickMixer::new(OutputFormat::new(48000, 2));
+        let sound: Arc<[f32]> = vec![0.5, -0.5, 0.25, -0.25].into();
+
+        handle.play_at(Arc::clone(&sound), 1.0, 3).unwrap();
+        let output: Vec<f32> = mixer.by_ref().take(12).collect();
+        assert_eq!(output, vec![0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.5, -0.5, 0.25, -0.25, 0.0, 0.0]);
+        assert_eq!(handle.frames_rendered(), 6);
+        assert_eq!(mixer.active_voices(), 0);
+
+        // Immediate clicks start on the next frame and take the volume as gain
+        handle.play(Arc::clone(&sound), 0.5).unwrap();
+        let output: Vec<f32> = mixer.by_ref().take(4).collect();
+        assert_eq!(output, vec![0.25, -0.25, 0.125, -0.125]);
+
+        // A click that arrives after its frame has passed starts right away
+        handle.play_at(sound, 1.0, 2).unwrap();
+        assert_eq!(mixer.next(), Some(0.5));
+    }
+
+    #[test]
+    fn test_mixer_sums_overlapping_clicks_with_soft_limiter() {
+        let (mut mixer, handle) = ClickMixer::new(OutputFormat::new(44100, 1));
+
+        handle.play(vec![0.3, 0.3].into(), 1.0).unwrap();
+        handle.play(vec![0.2].into(), 1.0).unwrap();
+        assert!((mixer.next().unwrap() - 0.5).abs() < 1e-6);
+        assert!((mixer.next().unwrap() - 0.3).abs() < 1e-6);
+
+        // Loud overlaps are squeezed below full scale instead of clipping
+        handle.play(vec![0.5].into(), 1.0).unwrap();
+        handle.play(vec![0.5].into(), 1.0).unwrap();
+        let loud = mixer.next().unwrap();
+        assert!(loud > 0.9 && loud < 1.0);
+
+        assert_eq!(mixer::soft_limit(0.8), 0.8);
+        assert_eq!(mixer::soft_limit(-0.3), -0.3);
+        assert!(mixer::soft_limit(1.2) < 1.0 && mixer::soft_limit(1.2) > mixer::soft_limit(1.0));
+        assert!(mixer::soft_limit(-50.0) >= -1.0);
+
+        // Nudging past the threshold barely moves the output, so the knee is smooth
+        assert!((mixer::soft_limit(0.81) - 0.81).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_mixer_voice_and_queue_limits() {
+        let (mut mixer, handle) = ClickMixer::new(OutputFormat::new(48000, 1));
+        let sound: Arc<[f32]> = vec![0.01; 100].into();
+
+        // More clicks than voices cut off the oldest ones instead of growing
+        for start in 0..(mixer::MAX_VOICES as u64 + 8) {
+            handle.play_at(Arc::clone(&sound), 1.0, start).unwrap();
+        }
+        mixer.next();
+        assert_eq!(mixer.active_voices(), mixer::MAX_VOICES);
+        mixer.by_ref().take(200).for_each(drop);
+        assert_eq!(mixer.active_voices(), 0);
+
+        // Events beyond the queue capacity are rejected while the mixer is stalled
+        for _ in 0..mixer::QUEUE_CAPACITY {
+            handle.play(Arc::clone(&sound), 1.0).unwrap();
+        }
+        assert!(handle.play(Arc::clone(&sound), 1.0).is_err());
+
+        // Finished sounds are handed back rather than freed on the audio thread
+        mixer.by_ref().take(200).for_each(drop);
+        handle.play(vec![].into(), 1.0).unwrap();
+        assert_eq!(Arc::strong_count(&sound), 1);
+    }
+
+    #[test]
+    fn test_mixer_places_scheduled_clicks_on_exact_frames() {
+        use std::time::Duration;
+        
+        let (mut mixer, handle) = ClickMixer::new(OutputFormat::new(48000, 1));
+        handle.set_latency(Duration::from_millis(100));
+        let sound: Arc<[f32]> = vec![0.5, 0.25].into();
+
+        // Quarter notes at 120 BPM are 24000 frames apart at 48 kHz; hand them over
+        // out of order to show that only their times matter
+        let start = Instant::now();
+        let first_frame = handle.frame_for_time(start);
+        assert!((4700..=4800).contains(&first_frame));
+        for beat in [0u32, 2, 1, 3] {
+            handle.play_at_time(Arc::clone(&sound), 1.0, start + Duration::from_millis(500) * beat).unwrap();
+        }
+
+        let output: Vec<f32> = mixer.by_ref().take(4 * 24000 + 4800).collect();
+        let onsets: Vec<u64> = (0..output.len()).filter(|&i| output[i] == 0.5).map(|i| i as u64).collect();
+        assert_eq!(onsets, (0..4).map(|beat| first_frame + beat * 24000).collect::<Vec<_>>());
+
+        let timing = handle.timing();
+        assert_eq!(timing.clicks, 4);
+        assert!(timing.is_sample_accurate());
+        assert_eq!(timing.max_offset, Duration::ZERO);
+        assert_eq!(timing.latency, Duration::from_millis(100));
+        assert_eq!(timing.resyncs, 0);
+        assert_eq!(timing.to_string(), "4 clicks, 0 late, max offset 0.00 ms, latency 100 ms");
+    }
+
+    #[test]
+    fn test_mixer_reports_late_clicks() {
+        use std::time::Duration;
+        
+        let (mut mixer, handle) = ClickMixer::new(OutputFormat::new(48000, 2));
+        let sound: Arc<[f32]> = vec![0.5, 0.5].into();
+        handle.frame_for_time(Instant::now());
+
+        // A click that reaches the mixer 6 frames after its frame starts late
+        mixer.by_ref().take(20).for_each(drop);
+        handle.play_at(Arc::clone(&sound), 1.0, 4).unwrap();
+        mixer.by_ref().take(4).for_each(drop);
+        let timing = handle.timing();
+        assert_eq!((timing.clicks, timing.late_clicks), (1, 1));
+        assert!(!timing.is_sample_accurate());
+        assert!((timing.max_offset.as_secs_f64() - 6.0 / 48000.0).abs() < 1e-9);
+        assert_eq!(timing.mean_offset, timing.max_offset);
+
+        // When the mixer has rendered past the mapped time the latency grows and the
+        // mapping starts over, so the click still lands on its frame
+        mixer.by_ref().take(2 * 48000).for_each(drop);
+        handle.play_at_time(Arc::clone(&sound), 1.0, Instant::now()).unwrap();
+        assert_eq!(handle.latency(), mixer::DEFAULT_LATENCY * 2);
+        mixer.by_ref().take(2 * 4800).for_each(drop);
+        let timing = handle.timing();
+        assert_eq!((timing.clicks, timing.late_clicks, timing.resyncs), (2, 1, 1));
+        assert_eq!(timing.last_offset, Duration::ZERO);
+
+        handle.reset_timing();
+        assert_eq!(handle.timing().clicks, 0);
+        assert_eq!(handle.timing().resyncs, 0);
+    }
+
+    #[test]
+    fn test_mixer_stops_when_handle_dropped() {
+        let (mut mixer, handle) = ClickMixer::new(OutputFormat::new(48000, 2));
+        assert_eq!(handle.format(), OutputFormat::new(48000, 2));
+        assert_eq!(mixer.format(), handle.format());
+
+        // Silence keeps flowing between clicks
+        assert_eq!(mixer.by_ref().take(1000).filter(|sample| *sample == 0.0).count(), 1000);
+
+        // Stopping waits for the end of the frame so channels stay aligned
+        assert_eq!(mixer.next(), Some(0.0));
+        drop(handle);
+        assert_eq!(mixer.next(), Some(0.0));
+        assert_eq!(mixer.next(), None);
+    }
+
     #[test]
     fn test_custom_sound_fallback() {
         let mut audio = CrossPlatformAudio::new();
@@ -1126,11 +1599,15 @@ mod tests {
         let engine = AudioEngine::default();
         
         // Test playing from beat info
-        let regular_beat = Beat::new(2, TimeSignature::Four, 120); // Second beat in 4/4 (weak)
-        let accent_beat = Beat::new(1, TimeSignature::Four, 120);  // First beat in 4/4 (strong)
+        let regular_beat = Beat::new(2, TimeSignature::new(4, 4), 120); // Second beat in 4/4 (weak)
+        let accent_beat = Beat::new(1, TimeSignature::new(4, 4), 120);  // First beat in 4/4 (strong)
         
         assert!(engine.play_beat_from_info(&regular_beat).is_ok());
         assert!(engine.play_beat_from_info(&accent_beat).is_ok());
+        
+        // Scheduling at the beats' grid times works with or without a device
+        assert!(engine.schedule_beat_from_info(&regular_beat).is_ok());
+        assert!(engine.schedule_beat_from_info(&accent_beat).is_ok());
     }
     
     #[test]
@@ -1142,6 +1619,7 @@ mod tests {
         assert!(extensions.contains(&"wav"));
         assert!(extensions.contains(&"mp3"));
         assert!(extensions.contains(&"ogg"));
+        assert!(extensions.contains(&"flac"));
         
         // Test file validation
         let non_existent = std::path::PathBuf::from("non_existent.wav");
@@ -0,0 +1,159 @@
+// Sample-rate and channel conversion from a sound's native format to the output format
+
+use crate::models::ResampleQuality;
+
+/// Zero crossings on each side of the windowed sinc kernel used for high quality
+const SINC_ZERO_CROSSINGS: f64 = 16.0;
+
+/// Sample rate and channel count of an audio stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OutputFormat {
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+impl OutputFormat {
+    pub fn new(sample_rate: u32, channels: u16) -> Self {
+        Self { sample_rate, channels }
+    }
+}
+
+impl std::fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let channels = match self.channels {
+            1 => "mono".to_string(),
+            2 => "stereo".to_string(),
+            n => format!("{} channels", n),
+        };
+        write!(f, "{} Hz {}", self.sample_rate, channels)
+    }
+}
+
+/// Convert interleaved samples in `sample_rate`/`channels` to the output format
+///
+/// Downmixing happens before resampling and upmixing after it, so the resampler
+/// always works on the smaller number of channels.
+pub fn convert(samples: &[f32], sample_rate: u32, channels: u16, output: OutputFormat, quality: ResampleQuality) -> Vec<f32> {
+    if output.channels < channels {
+        let mixed = convert_channels(samples, channels, output.channels);
+        resample(&mixed, output.channels, sample_rate, output.sample_rate, quality)
+    } else {
+        let resampled = resample(samples, channels, sample_rate, output.sample_rate, quality);
+        convert_channels(&resampled, channels, output.channels)
+    }
+}
+
+/// Change the number of interleaved channels
+///
+/// Mixing down to mono averages all channels and mono is copied to every output
+/// channel. Otherwise channels are matched by position, dropping the extra input
+/// channels or leaving the extra output channels silent.
+pub fn convert_channels(samples: &[f32], from: u16, to: u16) -> Vec<f32> {
+    let (from, to) = (from.max(1) as usize, to.max(1) as usize);
+    if from == to {
+        return samples.to_vec();
+    }
+
+    let mut output = Vec::with_capacity(samples.len() / from * to);
+    for frame in samples.chunks_exact(from) {
+        if to == 1 {
+            output.push(frame.iter().sum::<f32>() / from as f32);
+        } else if from == 1 {
+            output.extend(std::iter::repeat_n(frame[0], to));
+        } else {
+            output.extend((0..to).map(|channel| frame.get(channel).copied().unwrap_or(0.0)));
+        }
+    }
+    output
+}
+
+/// Change the sample rate of interleaved samples, keeping pitch and length
+///
+/// The output holds enough frames to cover the input's duration. Fast uses linear
+/// interpolation, Balanced a four-point Catmull-Rom cubic and High a Blackman-windowed
+/// sinc filter whose cutoff drops to the new Nyquist frequency when downsampling.
+pub fn resample(samples: &[f32], channels: u16, from_rate: u32, to_rate: u32, quality: ResampleQuality) -> Vec<f32> {
+    if from_rate == to_rate || from_rate == 0 || to_rate == 0 || samples.is_empty() {
+        return samples.to_vec();
+    }
+
+    let channels = channels.max(1) as usize;
+    let frames = samples.len() / channels;
+    let step = from_rate as f64 / to_rate as f64;
+    let output_frames = (frames as f64 / step).ceil() as usize;
+
+    // Samples outside the sound are silence
+    let sample_at = |frame: i64, channel: usize| -> f32 {
+        if frame < 0 || frame as usize >= frames {
+            0.0
+        } else {
+            samples[frame as usize * channels + channel]
+        }
+    };
+
+    let cutoff = (to_rate as f64 / from_rate as f64).min(1.0);
+    let half_width = SINC_ZERO_CROSSINGS / cutoff;
+
+    let mut output = Vec::with_capacity(output_frames * channels);
+    for frame in 0..output_frames {
+        let position = frame as f64 * step;
+        let index = position.floor() as i64;
+        let fraction = (position - index as f64) as f32;
+
+        for channel in 0..channels {
+            let value = match quality {
+                ResampleQuality::Fast => {
+                    let (a, b) = (sample_at(index, channel), sample_at(index + 1, channel));
+                    a + (b - a) * fraction
+                }
+                ResampleQuality::Balanced => catmull_rom(
+                    sample_at(index - 1, channel),
+                    sample_at(index, channel),
+                    sample_at(index + 1, channel),
+                    sample_at(index + 2, channel),
+                    fraction,
+                ),
+                ResampleQuality::High => {
+                    let first = (position - half_width).ceil() as i64;
+                    let last = (position + half_width).floor() as i64;
+                    (first..=last)
+                        .map(|k| {
+                            let distance = k as f64 - position;
+                            let weight = cutoff * sinc(cutoff * distance) * blackman(distance / half_width);
+                            weight as f32 * sample_at(k, channel)
+                        })
+                        .sum()
+                }
+            };
+            output.push(value);
+        }
+    }
+    output
+}
+
+/// Cubic interpolation between `y1` and `y2` at `t` in 0.0..1.0
+fn catmull_rom(y0: f32, y1: f32, y2: f32, y3: f32, t: f32) -> f32 {
+    let a = -0.5 * y0 + 1.5 * y1 - 1.5 * y2 + 0.5 * y3;
+    let b = y0 - 2.5 * y1 + 2.0 * y2 - 0.5 * y3;
+    let c = -0.5 * y0 + 0.5 * y2;
+    ((a * t + b) * t + c) * t + y1
+}
+
+/// Normalized sinc, sin(πx) / πx
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-9 {
+        1.0
+    } else {
+        let x = std::f64::consts::PI * x;
+        x.sin() / x
+    }
+}
+
+/// Blackman window over -1.0..=1.0
+fn blackman(x: f64) -> f64 {
+    if x.abs() > 1.0 {
+        return 0.0;
+    }
+    let x = std::f64::consts::PI * x;
+    0.42 + 0.5 * x.cos() + 0.08 * (2.0 * x).cos()
+}
@@ -0,0 +1,202 @@
+// RIFF/WAVE parser that turns PCM and IEEE float data into f32 samples
+
+use crate::error::AudioError;
+use super::decode::DecodedAudio;
+
+/// Format tag for integer PCM data
+const FORMAT_PCM: u16 = 0x0001;
+
+/// Format tag for IEEE float data
+const FORMAT_IEEE_FLOAT: u16 = 0x0003;
+
+/// Format tag whose real format is stored in the extension's sub-format GUID
+const FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+/// Sample encoding described by the `fmt ` chunk
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SampleFormat {
+    Int(u16),
+    Float(u16),
+}
+
+/// Contents of the `fmt ` chunk that matter for decoding
+#[derive(Debug, Clone, Copy)]
+struct WavFormat {
+    sample_format: SampleFormat,
+    channels: u16,
+    sample_rate: u32,
+    block_align: u16,
+}
+
+/// Decode a complete WAV file held in memory
+///
+/// Supports 8-bit unsigned, 16/24/32-bit signed integer and 32/64-bit float data in
+/// mono or stereo, including `WAVE_FORMAT_EXTENSIBLE` headers. Chunks other than
+/// `fmt ` and `data` are skipped.
+pub fn decode(bytes: &[u8]) -> Result<DecodedAudio, AudioError> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err(AudioError::UnsupportedFormat("Not a RIFF/WAVE file".to_string()));
+    }
+
+    let mut format = None;
+    let mut data = None;
+    let mut offset = 12;
+
+    while offset + 8 <= bytes.len() {
+        let id = &bytes[offset..offset + 4];
+        let size = read_u32(bytes, offset + 4) as usize;
+        let start = offset + 8;
+        // Writers that stream audio often leave the data size unset, so clamp to the file
+        let end = start.saturating_add(size).min(bytes.len());
+
+        match id {
+            b"fmt " => {
+                if end - start < size {
+                    return Err(AudioError::SoundLoadError("WAV fmt chunk is truncated".to_string()));
+                }
+                format = Some(parse_format(&bytes[start..end])?);
+            }
+            b"data" => data = Some(&bytes[start..end]),
+            _ => {}
+        }
+
+        // Chunks are padded to an even number of bytes
+        offset = end + (size & 1);
+    }
+
+    let format = format
+        .ok_or_else(|| AudioError::SoundLoadError("WAV file has no fmt chunk".to_string()))?;
+    let data = data
+        .ok_or_else(|| AudioError::SoundLoadError("WAV file has no data chunk".to_string()))?;
+
+    let samples = decode_samples(data, &format);
+    if samples.is_empty() {
+        return Err(AudioError::SoundLoadError("WAV file contains no audio samples".to_string()));
+    }
+
+    Ok(DecodedAudio {
+        samples,
+        sample_rate: format.sample_rate,
+        channels: format.channels,
+    })
+}
+
+/// Parse and validate the `fmt ` chunk
+fn parse_format(chunk: &[u8]) -> Result<WavFormat, AudioError> {
+    if chunk.len() < 16 {
+        return Err(AudioError::SoundLoadError(format!(
+            "WAV fmt chunk is too short ({} bytes, expected at least 16)",
+            chunk.len()
+        )));
+    }
+
+    let mut format_tag = read_u16(chunk, 0);
+    let channels = read_u16(chunk, 2);
+    let sample_rate = read_u32(chunk, 4);
+    let block_align = read_u16(chunk, 12);
+    let bits_per_sample = read_u16(chunk, 14);
+
+    if format_tag == FORMAT_EXTENSIBLE {
+        if chunk.len() < 40 {
+            return Err(AudioError::SoundLoadError("WAV extensible fmt chunk is truncated".to_string()));
+        }
+        // The first two bytes of the sub-format GUID hold the actual format tag
+        format_tag = read_u16(chunk, 24);
+    }
+
+    let sample_format = match (format_tag, bits_per_sample) {
+        (FORMAT_PCM, 8 | 16 | 24 | 32) => SampleFormat::Int(bits_per_sample),
+        (FORMAT_IEEE_FLOAT, 32 | 64) => SampleFormat::Float(bits_per_sample),
+        (FORMAT_PCM, bits) => {
+            return Err(AudioError::UnsupportedFormat(format!(
+                "{}-bit integer WAV data (supported: 8, 16, 24 and 32-bit)",
+                bits
+            )));
+        }
+        (FORMAT_IEEE_FLOAT, bits) => {
+            return Err(AudioError::UnsupportedFormat(format!(
+                "{}-bit float WAV data (supported: 32 and 64-bit)",
+                bits
+            )));
+        }
+        (tag, _) => {
+            return Err(AudioError::UnsupportedFormat(format!(
+                "WAV encoding 0x{:04X} (only PCM and IEEE float are supported)",
+                tag
+            )));
+        }
+    };
+
+    if !(1..=2).contains(&channels) {
+        return Err(AudioError::UnsupportedFormat(format!(
+            "{}-channel WAV data (only mono and stereo are supported)",
+            channels
+        )));
+    }
+
+    if sample_rate == 0 {
+        return Err(AudioError::SoundLoadError("WAV file has a sample rate of 0 Hz".to_string()));
+    }
+
+    let expected_align = channels * (bits_per_sample / 8);
+    if block_align != expected_align {
+        return Err(AudioError::SoundLoadError(format!(
+            "WAV block align is {} bytes but {} channel(s) of {}-bit samples need {}",
+            block_align, channels, bits_per_sample, expected_align
+        )));
+    }
+
+    Ok(WavFormat {
+        sample_format,
+        channels,
+        sample_rate,
+        block_align,
+    })
+}
+
+/// Convert the `data` chunk to f32 samples, dropping any incomplete trailing frame
+fn decode_samples(data: &[u8], format: &WavFormat) -> Vec<f32> {
+    let frames = data.len() / format.block_align as usize;
+    let data = &data[..frames * format.block_align as usize];
+
+    match format.sample_format {
+        SampleFormat::Int(8) => data.iter().map(|&b| (b as f32 - 128.0) / 128.0).collect(),
+        SampleFormat::Int(16) => data
+            .chunks_exact(2)
+            .map(|b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32_768.0)
+            .collect(),
+        SampleFormat::Int(24) => data
+            .chunks_exact(3)
+            .map(|b| (i32::from_le_bytes([0, b[0], b[1], b[2]]) >> 8) as f32 / 8_388_608.0)
+            .collect(),
+        SampleFormat::Int(_) => data
+            .chunks_exact(4)
+            .map(|b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f32 / 2_147_483_648.0)
+            .collect(),
+        SampleFormat::Float(32) => data
+            .chunks_exact(4)
+            .map(|b| sanitize(f32::from_le_bytes([b[0], b[1], b[2], b[3]])))
+            .collect(),
+        SampleFormat::Float(_) => data
+            .chunks_exact(8)
+            .map(|b| sanitize(f64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]) as f32))
+            .collect(),
+    }
+}
+
+/// Replace NaN and infinite float samples with silence and clamp the rest
+fn sanitize(sample: f32) -> f32 {
+    if sample.is_finite() {
+        sample.clamp(-1.0, 1.0)
+    } else {
+        0.0
+    }
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]])
+}
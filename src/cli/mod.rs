@@ -1,17 +1,223 @@
-use clap::{Arg, Command};
+use std::io::BufRead;
+#[cfg(feature = "gui")]
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant};
+use clap::{Arg, ArgAction, ArgMatches, Command};
 use crate::error::{CliError, Result};
+use crate::models::{BeatDropout, BeatUnit, ChangeTiming, CountIn, GapClick, ResampleQuality, SessionLimit, Setlist, SoundType, SpeedTrainer, Subdivision, TapTempo, Tempo, TempoCurve, TempoRamp, TempoRange, TimeSignature, TrainerEnd, MAX_BPM, MIN_BPM, STRAIGHT_SWING};
 
 #[derive(Debug, Clone)]
 pub struct CliArgs {
-    pub bpm: u32,
+    pub bpm: Tempo,
+    pub time_signature: TimeSignature,
+    pub subdivision: Subdivision,
+    /// Swing of subdivision clicks, 0.5 is straight
+    pub swing: f32,
+    pub tempo_ramp: Option<TempoRamp>,
+    pub speed_trainer: Option<SpeedTrainer>,
+    pub gap_click: Option<GapClick>,
+    pub dropout: Option<BeatDropout>,
+    pub count_in: Option<CountIn>,
+    /// Time or number of bars after which the metronome stops
+    pub session_limit: Option<SessionLimit>,
+    pub setlist: Option<Setlist>,
+    /// Tap tempo reading Enter presses from the terminal
+    pub tap_tempo: Option<TapTempo>,
+    /// Only detect the tapped tempo instead of playing the metronome
+    pub tap_only: bool,
+    /// When tempo changes made while playing take effect
+    pub change_timing: ChangeTiming,
+    /// Interpolation used to convert sounds to the output sample rate
+    pub resample_quality: ResampleQuality,
 }
 
 impl CliArgs {
-    pub fn new(bpm: u32) -> Self {
-        Self { bpm }
+    pub fn new(bpm: impl Into<Tempo>) -> Self {
+        Self {
+            bpm: bpm.into(),
+            time_signature: TimeSignature::default(),
+            subdivision: Subdivision::None,
+            swing: STRAIGHT_SWING,
+            tempo_ramp: None,
+            speed_trainer: None,
+            gap_click: None,
+            dropout: None,
+            count_in: None,
+            session_limit: None,
+            setlist: None,
+            tap_tempo: None,
+            tap_only: false,
+            change_timing: ChangeTiming::Immediate,
+            resample_quality: ResampleQuality::default(),
+        }
+    }
+    
+    pub fn with_time_signature(mut self, time_signature: TimeSignature) -> Self {
+        self.time_signature = time_signature;
+        self
+    }
+    
+    pub fn with_subdivision(mut self, subdivision: Subdivision) -> Self {
+        self.subdivision = subdivision;
+        self
+    }
+    
+    pub fn with_swing(mut self, swing: f32) -> Self {
+        self.swing = swing;
+        self
+    }
+    
+    pub fn with_tempo_ramp(mut self, tempo_ramp: TempoRamp) -> Self {
+        self.tempo_ramp = Some(tempo_ramp);
+        self
+    }
+    
+    pub fn with_speed_trainer(mut self, speed_trainer: SpeedTrainer) -> Self {
+        self.speed_trainer = Some(speed_trainer);
+        self
+    }
+    
+    pub fn with_gap_click(mut self, gap_click: GapClick) -> Self {
+        self.gap_click = Some(gap_click);
+        self
+    }
+    
+    pub fn with_dropout(mut self, dropout: BeatDropout) -> Self {
+        self.dropout = Some(dropout);
+        self
+    }
+    
+    pub fn with_count_in(mut self, count_in: CountIn) -> Self {
+        self.count_in = Some(count_in);
+        self
+    }
+    
+    pub fn with_session_limit(mut self, session_limit: SessionLimit) -> Self {
+        self.session_limit = Some(session_limit);
+        self
+    }
+    
+    pub fn with_setlist(mut self, setlist: Setlist) -> Self {
+        self.setlist = Some(setlist);
+        self
+    }
+    
+    pub fn with_change_timing(mut self, change_timing: ChangeTiming) -> Self {
+        self.change_timing = change_timing;
+        self
+    }
+    
+    pub fn with_resample_quality(mut self, resample_quality: ResampleQuality) -> Self {
+        self.resample_quality = resample_quality;
+        self
+    }
+    
+    /// Follow taps on the terminal; without `play` only the tempo is detected
+    pub fn with_tap_tempo(mut self, tap_tempo: TapTempo, play: bool) -> Self {
+        self.tap_tempo = Some(tap_tempo);
+        self.tap_only = !play;
+        self
+    }
+}
+
+/// Command typed on the terminal while the metronome plays without a setlist or taps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaybackCommand {
+    TogglePause,
+}
+
+impl PlaybackCommand {
+    /// Parse a typed line: Enter or "s" pauses and resumes
+    pub fn parse(line: &str) -> Option<Self> {
+        match line.trim().to_lowercase().as_str() {
+            "" | "s" | "pause" | "resume" => Some(PlaybackCommand::TogglePause),
+            _ => None,
+        }
+    }
+}
+
+/// Command typed on the terminal while a setlist plays
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetlistCommand {
+    Next,
+    Previous,
+    TogglePause,
+}
+
+impl SetlistCommand {
+    /// Parse a typed line: Enter or "n" for the next song, "p" for the previous one,
+    /// "s" to pause and resume
+    pub fn parse(line: &str) -> Option<Self> {
+        match line.trim().to_lowercase().as_str() {
+            "" | "n" | "next" => Some(SetlistCommand::Next),
+            "p" | "prev" | "previous" => Some(SetlistCommand::Previous),
+            "s" | "pause" | "resume" => Some(SetlistCommand::TogglePause),
+            _ => None,
+        }
+    }
+}
+
+/// Command typed on the terminal while tapping a tempo
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TapCommand {
+    /// Enter was pressed at the given time
+    Tap(Instant),
+    Reset,
+    Quit,
+    /// Pause or resume the metronome playing along with the taps
+    TogglePause,
+}
+
+impl TapCommand {
+    /// Parse a typed line: Enter taps, "r" starts over, "s" pauses and resumes and "q" finishes
+    pub fn parse(line: &str) -> Option<Self> {
+        match line.trim().to_lowercase().as_str() {
+            "" => Some(TapCommand::Tap(Instant::now())),
+            "r" | "reset" => Some(TapCommand::Reset),
+            "s" | "pause" | "resume" => Some(TapCommand::TogglePause),
+            "q" | "quit" => Some(TapCommand::Quit),
+            _ => None,
+        }
     }
 }
 
+/// Read pause commands from standard input on a background thread
+pub fn spawn_playback_input() -> Receiver<PlaybackCommand> {
+    spawn_line_input(PlaybackCommand::parse)
+}
+
+/// Read setlist commands from standard input on a background thread
+pub fn spawn_setlist_input() -> Receiver<SetlistCommand> {
+    spawn_line_input(SetlistCommand::parse)
+}
+
+/// Read tap commands from standard input on a background thread
+///
+/// Taps are timed as soon as their line is read, so a busy receiver does not
+/// skew the detected tempo.
+pub fn spawn_tap_input() -> Receiver<TapCommand> {
+    spawn_line_input(TapCommand::parse)
+}
+
+/// Parse lines from standard input on a background thread until input ends
+fn spawn_line_input<T: Send + 'static>(parse: fn(&str) -> Option<T>) -> Receiver<T> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in std::io::stdin().lock().lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if let Some(command) = parse(&line) {
+                if sender.send(command).is_err() {
+                    break;
+                }
+            }
+        }
+    });
+    receiver
+}
+
 pub fn build_cli() -> Command {
     Command::new("cli-metronome")
         .version("0.1.0")
@@ -22,52 +228,513 @@ pub fn build_cli() -> Command {
                      GUI MODE (default):\n\
                      Run without arguments to launch the graphical interface with:\n\
                      - BPM control with input field and buttons\n\
-                     - Time signature selection (2/4, 3/4, 4/4, 6/8)\n\
+                     - Time signature selection (presets or any N/D such as 11/8)\n\
                      - Sound selection (built-in and custom sounds)\n\
                      - Visual beat indicators and status display\n\
                      \n\
                      CLI MODE:\n\
                      Provide BPM as argument for command-line operation.\n\
                      Supports Windows and Linux with audio playback.\n\
-                     Press Ctrl+C to stop the metronome.")
+                     Press Ctrl+C to stop the metronome.\n\
+                     \n\
+                     Use the 'trainer' subcommand to step the tempo up every few bars,\n\
+                     the 'tap' subcommand to find a tempo by tapping Enter,\n\
+                     or --setlist to play through a list of songs.")
+        .args_conflicts_with_subcommands(true)
         .arg(
             Arg::new("bpm")
-                .help("Beats per minute (60-200) - enables CLI mode")
+                .help("Beats per minute (20-400, e.g. 72.5) - enables CLI mode")
                 .long_help("Set the metronome tempo in beats per minute for CLI mode.\n\
-                           Valid range: 60-200 BPM\n\
+                           Valid range: 20-400 BPM, fractions such as 72.5 are allowed\n\
                            Example: 120 for a moderate tempo\n\
                            \n\
                            If omitted, the application will start in GUI mode.")
                 .required(false)  // Changed to optional
-                .value_parser(clap::value_parser!(u32))
+                .value_parser(|s: &str| s.parse::<Tempo>())
                 .index(1)
         )
+        .args(setlist_arg())
+        .arg(
+            Arg::new("time-signature")
+                .long("time-signature")
+                .short('t')
+                .value_name("N/D")
+                .help("Time signature, e.g. 3/4, 11/8 or 2+2+3/8 (default 4/4)")
+                .long_help("Set the time signature as beats per measure over the note value.\n\
+                           The numerator may be 1-32, the denominator 1, 2, 4, 8, 16 or 32.\n\
+                           Write the numerator as a sum to group the beats; the first\n\
+                           beat of each group is accented.\n\
+                           Examples: 4/4, 6/8, 11/8, 15/16, 2+2+3/8, 3+3+2/8")
+                .value_parser(|s: &str| s.parse::<TimeSignature>())
+                .global(true)
+        )
+        .arg(
+            Arg::new("beat-unit")
+                .long("beat-unit")
+                .value_name("UNIT")
+                .help("Note value the tempo counts: half, quarter, dotted-quarter, eighth")
+                .long_help("Set the note value counted as one beat, which the BPM refers to.\n\
+                           By default this is the time signature's note value, so 2/2 counts\n\
+                           half notes. In 6/8, --beat-unit dotted-quarter plays two beats per\n\
+                           bar with the eighths as subdivision clicks. The measure must hold\n\
+                           a whole number of beats.")
+                .value_parser(|s: &str| s.parse::<BeatUnit>())
+                .global(true)
+        )
+        .arg(
+            Arg::new("subdivision")
+                .long("subdivision")
+                .short('s')
+                .value_name("SUBDIVISION")
+                .help("Click subdivision: none, eighths, triplets, sixteenths, quintuplets")
+                .long_help("Add evenly spaced clicks between the main beats.\n\
+                           Valid values: none, eighths, triplets, sixteenths, quintuplets\n\
+                           Subdivision clicks use their own, quieter sound.")
+                .value_parser(|s: &str| s.parse::<Subdivision>())
+                .global(true)
+        )
+        .arg(
+            Arg::new("swing")
+                .long("swing")
+                .value_name("PERCENT")
+                .help("Swing of subdivision clicks, from 50 (straight) to 75 (hard shuffle)")
+                .long_help("Delay every second subdivision click for a swing or shuffle feel.\n\
+                           The value is the share of each pair of clicks taken by the first\n\
+                           one: 50 is straight, 67 a triplet swing and 75 a hard shuffle.\n\
+                           The beats stay on the grid. Works with eighths and sixteenths.")
+                .value_parser(clap::value_parser!(u32).range(50..=75))
+                .requires("subdivision")
+                .global(true)
+        )
+        .arg(
+            Arg::new("gap")
+                .long("gap")
+                .value_name("PLAY:MUTE")
+                .help("Gap click training: play PLAY bars, then mute MUTE bars, e.g. 4:2")
+                .long_help("Alternate bars with the click and silent bars to test your internal time.\n\
+                           The beat counter keeps running through the silent bars and the\n\
+                           click comes back on the downbeat.\n\
+                           Example: 4:2 plays four bars, then leaves two bars silent")
+                .value_parser(|s: &str| s.parse::<GapClick>())
+                .global(true)
+        )
+        .arg(
+            Arg::new("dropout")
+                .long("dropout")
+                .value_name("PERCENT")
+                .help("Silence beats at random with this chance (0-100), keeping beat 1")
+                .long_help("Silence each click at random with the given chance in percent.\n\
+                           Subdivision clicks drop out on their own. The main click of\n\
+                           beat 1 always plays unless --dropout-all-beats is given.\n\
+                           Silent beats are still shown on screen.\n\
+                           Example: 25 drops about one beat in four")
+                .value_parser(clap::value_parser!(u32).range(0..=100))
+                .global(true)
+        )
+        .arg(
+            Arg::new("dropout-all-beats")
+                .long("dropout-all-beats")
+                .help("Let random dropout silence beat 1 as well")
+                .action(ArgAction::SetTrue)
+                .requires("dropout")
+                .global(true)
+        )
+        .arg(
+            Arg::new("dropout-seed")
+                .long("dropout-seed")
+                .value_name("SEED")
+                .help("Seed for random dropout, to repeat the same pattern")
+                .value_parser(clap::value_parser!(u64))
+                .requires("dropout")
+                .global(true)
+        )
+        .arg(
+            Arg::new("count-in")
+                .long("count-in")
+                .value_name("BARS")
+                .help("Count in this many bars (1-4) before the first beat")
+                .value_parser(clap::value_parser!(u32).range(1..=4))
+                .global(true)
+        )
+        .arg(
+            Arg::new("count-in-sound")
+                .long("count-in-sound")
+                .value_name("SOUND")
+                .help("Sound of the count-in: click, wood, beep (default beep)")
+                .value_parser(["click", "wood", "beep"])
+                .requires("count-in")
+                .global(true)
+        )
+        .arg(
+            Arg::new("duration")
+                .long("duration")
+                .value_name("LENGTH")
+                .help("Stop after this long, e.g. 10m, 90s, 1h30m or 2:30")
+                .long_help("Stop the metronome after the given time and show a summary.\n\
+                           Use h, m and s for hours, minutes and seconds, or MM:SS.\n\
+                           The time counts from the start, count-in included.\n\
+                           The program exits with code 3 when the limit is reached.")
+                .value_parser(SessionLimit::parse_duration)
+                .global(true)
+        )
+        .arg(
+            Arg::new("session-bars")
+                .long("bars")
+                .value_name("BARS")
+                .help("Stop after this many bars, not counting the count-in")
+                .long_help("Stop the metronome once this many bars have been played and show\n\
+                           a summary. The count-in is not included. The program exits with\n\
+                           code 3 when the limit is reached. In the trainer subcommand --bars\n\
+                           sets the bars per step instead; use --duration there.")
+                .value_parser(clap::value_parser!(u32).range(1..))
+                .conflicts_with("duration")
+        )
+        .arg(
+            Arg::new("resample-quality")
+                .long("resample-quality")
+                .value_name("QUALITY")
+                .help("Sample-rate conversion quality: fast, balanced, high (default balanced)")
+                .long_help("Set the interpolation used to convert sounds to the sample rate of the\n\
+                           audio device, so 44.1 kHz sounds keep their pitch on a 48 kHz device.\n\
+                           fast is linear, balanced is cubic and high uses a windowed sinc\n\
+                           filter. Sounds are converted once when they are loaded.")
+                .value_parser(|s: &str| s.parse::<ResampleQuality>())
+                .global(true)
+        )
+        .arg(
+            Arg::new("ramp-to")
+                .long("ramp-to")
+                .value_name("BPM")
+                .help("Gradually change the tempo to this BPM (20-400)")
+                .long_help("Ramp the tempo from the starting BPM to this target.\n\
+                           A higher target speeds up (accelerando), a lower one slows down\n\
+                           (ritardando). The target tempo is held once it is reached.")
+                .value_parser(|s: &str| s.parse::<Tempo>())
+        )
+        .arg(
+            Arg::new("ramp-bars")
+                .long("ramp-bars")
+                .value_name("BARS")
+                .help("Number of bars the tempo ramp lasts (default 8)")
+                .value_parser(clap::value_parser!(u32).range(1..))
+                .requires("ramp-to")
+        )
+        .arg(
+            Arg::new("ramp-curve")
+                .long("ramp-curve")
+                .value_name("CURVE")
+                .help("Tempo ramp curve: linear, exponential (default linear)")
+                .value_parser(|s: &str| s.parse::<TempoCurve>())
+                .requires("ramp-to")
+        )
+        .subcommand(build_trainer_command())
+        .subcommand(build_tap_command())
         .after_help("EXAMPLES:\n    \
                      cli-metronome           Start GUI mode (default)\n    \
                      cli-metronome 120       Start CLI mode at 120 BPM\n    \
                      cli-metronome 80        Start CLI mode at 80 BPM\n    \
-                     cli-metronome 180       Start CLI mode at 180 BPM")
+                     cli-metronome 180       Start CLI mode at 180 BPM\n    \
+                     cli-metronome 90 -s triplets   Practise triplets at 90 BPM\n    \
+                     cli-metronome 120 -s eighths --swing 67  Swung eighths at 120 BPM\n    \
+                     cli-metronome 140 -t 11/8      Play 11/8 at 140 BPM\n    \
+                     cli-metronome 160 -t 2+2+3/8   Play 7/8 grouped as 2+2+3\n    \
+                     cli-metronome 60 -t 6/8 --beat-unit dotted-quarter\n    \
+                                             Two dotted-quarter beats per bar at 60 BPM\n    \
+                     cli-metronome 90 --ramp-to 140 --ramp-bars 32\n    \
+                                             Speed up from 90 to 140 BPM over 32 bars\n    \
+                     cli-metronome 100 --gap 4:2    Play 4 bars, then 2 silent bars\n    \
+                     cli-metronome 100 --dropout 25 Silence about one beat in four\n    \
+                     cli-metronome 100 --count-in 2 Count in two bars before playing\n    \
+                     cli-metronome 100 --duration 10m  Practise for ten minutes, then stop\n    \
+                     cli-metronome 100 --bars 64    Stop after 64 bars\n    \
+                     cli-metronome 100 --resample-quality high\n    \
+                                             Best-quality conversion to the device's sample rate\n    \
+                     cli-metronome --setlist gig.json  Play the songs of a setlist\n    \
+                     cli-metronome trainer --start 80 --target 120 --increment 5 --bars 4\n    \
+                                             Add 5 BPM every 4 bars from 80 up to 120 BPM\n    \
+                     cli-metronome tap       Find a tempo by tapping Enter")
+}
+
+/// Build the `--setlist` option, which needs the JSON support of the gui feature
+#[cfg(feature = "gui")]
+fn setlist_arg() -> Option<Arg> {
+    Some(
+        Arg::new("setlist")
+            .long("setlist")
+            .value_name("FILE")
+            .help("Play through the songs of a setlist file - enables CLI mode")
+            .long_help("Load a JSON setlist and start with its first song. Each song sets its\n\
+                       own BPM, time signature, accent pattern and sounds.\n\
+                       Press Enter (or type n) for the next song, type p and Enter\n\
+                       for the previous one.")
+            .value_parser(clap::value_parser!(PathBuf))
+            .conflicts_with_all(["bpm", "ramp-to"])
+    )
+}
+
+/// Without the gui feature setlist files cannot be read, so there is no `--setlist` option
+#[cfg(not(feature = "gui"))]
+fn setlist_arg() -> Option<Arg> {
+    None
+}
+
+/// Build the `trainer` subcommand that steps the tempo up every few bars
+fn build_trainer_command() -> Command {
+    Command::new("trainer")
+        .about("Speed trainer: step the tempo up every few bars until a target")
+        .long_about("Start at one tempo and add a fixed number of BPM after every few bars\n\
+                     until the target tempo is reached. Each step is announced on screen.\n\
+                     Once the target has been played for a step the trainer holds it,\n\
+                     loops back to the start tempo, or drops back and climbs again.")
+        .arg(
+            Arg::new("start")
+                .long("start")
+                .value_name("BPM")
+                .help("Starting tempo (20-400)")
+                .value_parser(|s: &str| s.parse::<Tempo>())
+                .required(true)
+        )
+        .arg(
+            Arg::new("target")
+                .long("target")
+                .value_name("BPM")
+                .help("Tempo to work up to (20-400)")
+                .value_parser(|s: &str| s.parse::<Tempo>())
+                .required(true)
+        )
+        .arg(
+            Arg::new("increment")
+                .long("increment")
+                .short('i')
+                .value_name("BPM")
+                .help("BPM added at each step (default 5)")
+                .value_parser(clap::value_parser!(u32).range(1..))
+                .default_value("5")
+        )
+        .arg(
+            Arg::new("bars")
+                .long("bars")
+                .short('b')
+                .value_name("BARS")
+                .help("Bars played at each tempo (default 4)")
+                .value_parser(clap::value_parser!(u32).range(1..))
+                .default_value("4")
+        )
+        .arg(
+            Arg::new("end")
+                .long("end")
+                .value_name("BEHAVIOUR")
+                .help("What to do at the target: hold, loop, drop (default hold)")
+                .value_parser(["hold", "loop", "drop"])
+                .default_value("hold")
+        )
+        .arg(
+            Arg::new("drop-by")
+                .long("drop-by")
+                .value_name("BPM")
+                .help("BPM to drop back by with --end drop (default 10)")
+                .value_parser(clap::value_parser!(u32).range(1..))
+                .default_value("10")
+        )
+        .after_help("EXAMPLES:\n    \
+                     cli-metronome trainer --start 80 --target 120\n    \
+                     cli-metronome trainer --start 100 --target 160 -i 10 -b 8 --end loop\n    \
+                     cli-metronome trainer --start 90 --target 130 --end drop --drop-by 15 -t 6/8")
+}
+
+/// Build the `tap` subcommand that finds a tempo from Enter key presses
+fn build_tap_command() -> Command {
+    Command::new("tap")
+        .about("Tap tempo: find a tempo by pressing Enter in time")
+        .long_about("Press Enter in time with the music to measure its tempo. The tempo is\n\
+                     averaged over the most recent taps, stray taps far from the others are\n\
+                     ignored, and a pause starts a new measurement. Type r and Enter to start\n\
+                     over, q and Enter to finish.\n\
+                     \n\
+                     With --play the metronome runs and each detected tempo is applied to it,\n\
+                     or with --on-bar at the start of the next bar.")
+        .arg(
+            Arg::new("play")
+                .long("play")
+                .value_name("BPM")
+                .help("Run the metronome from BPM and follow the tapped tempo")
+                .value_parser(|s: &str| s.parse::<Tempo>())
+        )
+        .arg(
+            Arg::new("on-bar")
+                .long("on-bar")
+                .help("Apply each tapped tempo at the next bar line instead of at once")
+                .action(ArgAction::SetTrue)
+                .requires("play")
+        )
+        .arg(
+            Arg::new("taps")
+                .long("taps")
+                .value_name("COUNT")
+                .help("Number of recent taps to average (default 8)")
+                .value_parser(clap::value_parser!(u32).range(2..=64))
+                .default_value("8")
+        )
+        .arg(
+            Arg::new("reset")
+                .long("reset")
+                .value_name("SECONDS")
+                .help("Pause after which tapping starts over (default 2)")
+                .value_parser(clap::value_parser!(f64))
+                .default_value("2")
+        )
+        .after_help("EXAMPLES:\n    \
+                     cli-metronome tap\n    \
+                     cli-metronome tap --taps 4 --reset 1.5\n    \
+                     cli-metronome tap --play 100 -t 3/4\n    \
+                     cli-metronome tap --play 100 --on-bar")
 }
 
 pub fn parse_args() -> Result<Option<CliArgs>> {
     let matches = build_cli().get_matches();
+    cli_args_from_matches(&matches)
+}
+
+/// Turn parsed command line matches into CLI arguments, or `None` for GUI mode
+pub fn cli_args_from_matches(matches: &ArgMatches) -> Result<Option<CliArgs>> {
+    let mut time_signature = matches.get_one::<TimeSignature>("time-signature")
+        .copied()
+        .unwrap_or_default();
+    if let Some(beat_unit) = matches.get_one::<BeatUnit>("beat-unit").copied() {
+        time_signature = time_signature.with_beat_unit(beat_unit)?;
+    }
+    let subdivision = matches.get_one::<Subdivision>("subdivision")
+        .copied()
+        .unwrap_or_default();
+    
+    #[cfg(feature = "gui")]
+    if let Some(path) = matches.get_one::<PathBuf>("setlist") {
+        let setlist = Setlist::load_from_file(path, &TempoRange::default())?;
+        let first_song = setlist.current_song().cloned().ok_or_else(|| {
+            CliError::InvalidArgument(format!("Setlist {} has no songs", path.display()))
+        })?;
+        let cli_args = CliArgs::new(first_song.bpm)
+            .with_time_signature(first_song.time_signature)
+            .with_subdivision(subdivision)
+            .with_setlist(setlist);
+        return with_practice_options(cli_args, matches).map(Some);
+    }
+    
+    if let Some(trainer_matches) = matches.subcommand_matches("trainer") {
+        let speed_trainer = speed_trainer_from_matches(trainer_matches)?;
+        let cli_args = CliArgs::new(speed_trainer.start_bpm)
+            .with_time_signature(time_signature)
+            .with_subdivision(subdivision)
+            .with_speed_trainer(speed_trainer);
+        return with_practice_options(cli_args, matches).map(Some);
+    }
+    
+    if let Some(tap_matches) = matches.subcommand_matches("tap") {
+        let tap_tempo = tap_tempo_from_matches(tap_matches)?;
+        let play_bpm = tap_matches.get_one::<Tempo>("play").copied();
+        let cli_args = CliArgs::new(play_bpm.unwrap_or_default())
+            .with_time_signature(time_signature)
+            .with_subdivision(subdivision)
+            .with_tap_tempo(tap_tempo, play_bpm.is_some());
+        let cli_args = if tap_matches.get_flag("on-bar") {
+            cli_args.with_change_timing(ChangeTiming::NextBar)
+        } else {
+            cli_args
+        };
+        return with_practice_options(cli_args, matches).map(Some);
+    }
     
     // Check if BPM argument was provided
-    if let Some(bpm) = matches.get_one::<u32>("bpm").copied() {
-        // BPM validation
-        if bpm < 60 || bpm > 200 {
-            return Err(CliError::InvalidArgument(
-                format!("BPM must be between 60 and 200, got {}", bpm)
-            ).into());
-        }
+    // The tempo range is checked while parsing
+    if let Some(bpm) = matches.get_one::<Tempo>("bpm").copied() {
+        let mut cli_args = CliArgs::new(bpm)
+            .with_time_signature(time_signature)
+            .with_subdivision(subdivision);
         
-        Ok(Some(CliArgs::new(bpm)))
+        if let Some(target_bpm) = matches.get_one::<Tempo>("ramp-to").copied() {
+            let bars = matches.get_one::<u32>("ramp-bars").copied().unwrap_or(8);
+            let curve = matches.get_one::<TempoCurve>("ramp-curve").copied().unwrap_or_default();
+            cli_args = cli_args.with_tempo_ramp(TempoRamp::new(bpm, target_bpm, bars)?.with_curve(curve));
+        }
+        with_practice_options(cli_args, matches).map(Some)
     } else {
         // No BPM provided - GUI mode
         Ok(None)
     }
 }
 
+/// Add the swing, gap click, dropout, count-in and session limit options shared by every way of playing
+fn with_practice_options(mut cli_args: CliArgs, matches: &ArgMatches) -> Result<CliArgs> {
+    if let Some(percent) = matches.get_one::<u32>("swing").copied() {
+        cli_args = cli_args.with_swing(percent as f32 / 100.0);
+    }
+    if let Some(gap_click) = matches.get_one::<GapClick>("gap").copied() {
+        cli_args = cli_args.with_gap_click(gap_click);
+    }
+    if let Some(dropout) = dropout_from_matches(matches)? {
+        cli_args = cli_args.with_dropout(dropout);
+    }
+    if let Some(count_in) = count_in_from_matches(matches)? {
+        cli_args = cli_args.with_count_in(count_in);
+    }
+    if let Some(session_limit) = matches.get_one::<SessionLimit>("duration").copied() {
+        cli_args = cli_args.with_session_limit(session_limit);
+    }
+    if let Some(bars) = matches.get_one::<u32>("session-bars").copied() {
+        cli_args = cli_args.with_session_limit(SessionLimit::bars(bars)?);
+    }
+    if let Some(quality) = matches.get_one::<ResampleQuality>("resample-quality").copied() {
+        cli_args = cli_args.with_resample_quality(quality);
+    }
+    Ok(cli_args)
+}
+
+fn count_in_from_matches(matches: &ArgMatches) -> Result<Option<CountIn>> {
+    let Some(bars) = matches.get_one::<u32>("count-in").copied() else {
+        return Ok(None);
+    };
+    let sound = match matches.get_one::<String>("count-in-sound").map(String::as_str) {
+        Some("click") => SoundType::BuiltinClick,
+        Some("wood") => SoundType::BuiltinWood,
+        _ => SoundType::BuiltinBeep,
+    };
+    Ok(Some(CountIn::new(bars)?.with_sound(sound)))
+}
+
+fn dropout_from_matches(matches: &ArgMatches) -> Result<Option<BeatDropout>> {
+    let Some(percent) = matches.get_one::<u32>("dropout").copied() else {
+        return Ok(None);
+    };
+    let mut dropout = BeatDropout::new(percent as f32 / 100.0)?
+        .with_keep_downbeat(!matches.get_flag("dropout-all-beats"));
+    if let Some(seed) = matches.get_one::<u64>("dropout-seed").copied() {
+        dropout = dropout.with_seed(seed);
+    }
+    Ok(Some(dropout))
+}
+
+fn speed_trainer_from_matches(matches: &ArgMatches) -> Result<SpeedTrainer> {
+    let start_bpm = matches.get_one::<Tempo>("start").copied().unwrap_or_default();
+    let target_bpm = matches.get_one::<Tempo>("target").copied().unwrap_or_default();
+    let increment = matches.get_one::<u32>("increment").copied().unwrap_or(5);
+    let bars = matches.get_one::<u32>("bars").copied().unwrap_or(4);
+    let end = match matches.get_one::<String>("end").map(String::as_str) {
+        Some("loop") => TrainerEnd::Loop,
+        Some("drop") => TrainerEnd::DropBack(matches.get_one::<u32>("drop-by").copied().unwrap_or(10)),
+        _ => TrainerEnd::Hold,
+    };
+    SpeedTrainer::new(start_bpm, target_bpm, increment, bars)?.with_end(end)
+}
+
+fn tap_tempo_from_matches(matches: &ArgMatches) -> Result<TapTempo> {
+    let taps = matches.get_one::<u32>("taps").copied().unwrap_or(8);
+    let reset = matches.get_one::<f64>("reset").copied().unwrap_or(2.0);
+    let reset_after = Duration::try_from_secs_f64(reset).map_err(|_| {
+        CliError::InvalidArgument(format!("Invalid tap reset pause: {} seconds", reset))
+    })?;
+    TapTempo::new().with_max_taps(taps as usize)?.with_reset_after(reset_after)
+}
+
 
 pub fn show_help() {
     let mut cmd = build_cli();
@@ -75,10 +742,11 @@ pub fn show_help() {
     println!();
 }
 
-pub fn validate_bpm(bpm: u32) -> Result<()> {
-    if bpm < 60 || bpm > 200 {
+pub fn validate_bpm(bpm: impl Into<Tempo>) -> Result<()> {
+    let bpm = bpm.into();
+    if !TempoRange::default().contains(bpm) {
         return Err(CliError::InvalidArgument(
-            format!("Invalid BPM value: {}. Must be between 60 and 200", bpm)
+            format!("Invalid BPM value: {}. Must be between {} and {}", bpm, MIN_BPM, MAX_BPM)
         ).into());
     }
     Ok(())
@@ -98,12 +766,24 @@ mod tests {
         assert!(validate_bpm(60).is_ok());
         assert!(validate_bpm(120).is_ok());
         assert!(validate_bpm(200).is_ok());
+        assert!(validate_bpm(20).is_ok());
+        assert!(validate_bpm(400).is_ok());
+        assert!(validate_bpm(72.5).is_ok());
+    }
+    
+    #[test]
+    fn test_fractional_bpm_argument() {
+        let matches = build_cli().try_get_matches_from(["cli-metronome", "72.5"]).unwrap();
+        assert_eq!(matches.get_one::<Tempo>("bpm"), Some(&Tempo::from(72.5)));
+        
+        assert!(build_cli().try_get_matches_from(["cli-metronome", "19.5"]).is_err());
+        assert!(build_cli().try_get_matches_from(["cli-metronome", "fast"]).is_err());
     }
     
     #[test]
     fn test_bpm_validation_invalid() {
-        assert!(validate_bpm(59).is_err());
-        assert!(validate_bpm(201).is_err());
+        assert!(validate_bpm(19).is_err());
+        assert!(validate_bpm(401).is_err());
         assert!(validate_bpm(0).is_err());
     }
     
@@ -135,7 +815,284 @@ mod tests {
         
         // Test BPM validation still works
         assert!(validate_bpm(120).is_ok());
-        assert!(validate_bpm(59).is_err());
-        assert!(validate_bpm(201).is_err());
+        assert!(validate_bpm(19).is_err());
+        assert!(validate_bpm(401).is_err());
+    }
+    
+    #[test]
+    fn test_time_signature_argument() {
+        let matches = build_cli()
+            .try_get_matches_from(["cli-metronome", "140", "--time-signature", "11/8"])
+            .unwrap();
+        assert_eq!(matches.get_one::<TimeSignature>("time-signature"), Some(&TimeSignature::new(11, 8)));
+        
+        let matches = build_cli()
+            .try_get_matches_from(["cli-metronome", "140", "-t", "15/16"])
+            .unwrap();
+        assert_eq!(matches.get_one::<TimeSignature>("time-signature"), Some(&TimeSignature::new(15, 16)));
+        
+        let matches = build_cli()
+            .try_get_matches_from(["cli-metronome", "140", "-t", "2+2+3/8"])
+            .unwrap();
+        let time_signature = matches.get_one::<TimeSignature>("time-signature").unwrap();
+        assert_eq!(time_signature.groups(), vec![2, 2, 3]);
+        
+        let matches = build_cli()
+            .try_get_matches_from(["cli-metronome", "60", "-t", "6/8", "--beat-unit", "dotted-quarter"])
+            .unwrap();
+        let cli_args = cli_args_from_matches(&matches).unwrap().unwrap();
+        assert_eq!(cli_args.time_signature.beat_unit(), Some(BeatUnit::DottedQuarter));
+        assert_eq!(cli_args.time_signature.beats_per_measure(), 2);
+        
+        // 7/8 cannot be counted in dotted quarters
+        let matches = build_cli()
+            .try_get_matches_from(["cli-metronome", "60", "-t", "7/8", "--beat-unit", "dotted-quarter"])
+            .unwrap();
+        assert!(cli_args_from_matches(&matches).is_err());
+        assert!(build_cli().try_get_matches_from(["cli-metronome", "60", "--beat-unit", "whole"]).is_err());
+        
+        assert!(build_cli().try_get_matches_from(["cli-metronome", "140", "-t", "7/6"]).is_err());
+        assert!(build_cli().try_get_matches_from(["cli-metronome", "140", "-t", "seven"]).is_err());
+    }
+    
+    #[test]
+    fn test_tempo_ramp_arguments() {
+        let matches = build_cli()
+            .try_get_matches_from(["cli-metronome", "90", "--ramp-to", "140", "--ramp-bars", "32", "--ramp-curve", "exponential"])
+            .unwrap();
+        assert_eq!(matches.get_one::<Tempo>("ramp-to"), Some(&Tempo::from(140)));
+        assert_eq!(matches.get_one::<u32>("ramp-bars"), Some(&32));
+        assert_eq!(matches.get_one::<TempoCurve>("ramp-curve"), Some(&TempoCurve::Exponential));
+        
+        // Ramp length and curve only make sense with a target
+        assert!(build_cli().try_get_matches_from(["cli-metronome", "90", "--ramp-bars", "32"]).is_err());
+        assert!(build_cli().try_get_matches_from(["cli-metronome", "90", "--ramp-to", "140", "--ramp-bars", "0"]).is_err());
+        assert!(build_cli().try_get_matches_from(["cli-metronome", "90", "--ramp-to", "140", "--ramp-curve", "cubic"]).is_err());
+    }
+    
+    #[test]
+    fn test_trainer_subcommand() {
+        let matches = build_cli()
+            .try_get_matches_from(["cli-metronome", "trainer", "--start", "80", "--target", "120", "-i", "10", "-b", "2", "-t", "6/8"])
+            .unwrap();
+        let args = cli_args_from_matches(&matches).unwrap().unwrap();
+        assert_eq!(args.bpm, 80);
+        assert_eq!(args.time_signature, TimeSignature::new(6, 8));
+        let trainer = args.speed_trainer.unwrap();
+        assert_eq!((trainer.start_bpm, trainer.target_bpm), (Tempo::from(80), Tempo::from(120)));
+        assert_eq!((trainer.increment, trainer.bars_per_step), (10, 2));
+        assert_eq!(trainer.end, TrainerEnd::Hold);
+        
+        let matches = build_cli()
+            .try_get_matches_from(["cli-metronome", "trainer", "--start", "90", "--target", "130", "--end", "drop", "--drop-by", "15"])
+            .unwrap();
+        let trainer = cli_args_from_matches(&matches).unwrap().unwrap().speed_trainer.unwrap();
+        assert_eq!((trainer.increment, trainer.bars_per_step), (5, 4));
+        assert_eq!(trainer.end, TrainerEnd::DropBack(15));
+        
+        // Both tempos are required and the target must be above the start
+        assert!(build_cli().try_get_matches_from(["cli-metronome", "trainer", "--start", "80"]).is_err());
+        assert!(build_cli().try_get_matches_from(["cli-metronome", "trainer", "--start", "80", "--target", "120", "--end", "stop"]).is_err());
+        let matches = build_cli()
+            .try_get_matches_from(["cli-metronome", "trainer", "--start", "120", "--target", "80"])
+            .unwrap();
+        assert!(cli_args_from_matches(&matches).is_err());
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_gap_argument() {
+        let matches = build_cli()
+            .try_get_matches_from(["cli-metronome", "100", "--gap", "4:2"])
+            .unwrap();
+        let args = cli_args_from_matches(&matches).unwrap().unwrap();
+        assert_eq!(args.gap_click, Some(GapClick::new(4, 2).unwrap()));
+        
+        // Gap clicks also work with the speed trainer
+        let matches = build_cli()
+            .try_get_matches_from(["cli-metronome", "trainer", "--start", "80", "--target", "100", "--gap", "2:1"])
+            .unwrap();
+        let args = cli_args_from_matches(&matches).unwrap().unwrap();
+        assert_eq!(args.gap_click, Some(GapClick::new(2, 1).unwrap()));
+        
+        assert!(build_cli().try_get_matches_from(["cli-metronome", "100", "--gap", "4"]).is_err());
+        assert!(build_cli().try_get_matches_from(["cli-metronome", "100", "--gap", "4:0"]).is_err());
+    }
+    
+    #[test]
+    fn test_dropout_arguments() {
+        let matches = build_cli()
+            .try_get_matches_from(["cli-metronome", "100", "--dropout", "25", "--dropout-seed", "7"])
+            .unwrap();
+        let dropout = cli_args_from_matches(&matches).unwrap().unwrap().dropout.unwrap();
+        assert_eq!(dropout.probability, 0.25);
+        assert!(dropout.keep_downbeat);
+        assert_eq!(dropout.seed, Some(7));
+        
+        let matches = build_cli()
+            .try_get_matches_from(["cli-metronome", "100", "--dropout", "50", "--dropout-all-beats"])
+            .unwrap();
+        let dropout = cli_args_from_matches(&matches).unwrap().unwrap().dropout.unwrap();
+        assert!(!dropout.keep_downbeat);
+        assert_eq!(dropout.seed, None);
+        
+        assert!(build_cli().try_get_matches_from(["cli-metronome", "100", "--dropout", "101"]).is_err());
+        assert!(build_cli().try_get_matches_from(["cli-metronome", "100", "--dropout-seed", "7"]).is_err());
+    }
+    
+    #[test]
+    fn test_swing_argument() {
+        let matches = build_cli()
+            .try_get_matches_from(["cli-metronome", "120", "-s", "eighths", "--swing", "67"])
+            .unwrap();
+        assert_eq!(cli_args_from_matches(&matches).unwrap().unwrap().swing, 0.67);
+        
+        let matches = build_cli().try_get_matches_from(["cli-metronome", "120", "-s", "eighths"]).unwrap();
+        assert_eq!(cli_args_from_matches(&matches).unwrap().unwrap().swing, 0.5);
+        
+        assert!(build_cli().try_get_matches_from(["cli-metronome", "120", "-s", "eighths", "--swing", "80"]).is_err());
+        assert!(build_cli().try_get_matches_from(["cli-metronome", "120", "--swing", "60"]).is_err());
+    }
+    
+    #[test]
+    fn test_count_in_arguments() {
+        let matches = build_cli()
+            .try_get_matches_from(["cli-metronome", "100", "--count-in", "2", "--count-in-sound", "wood"])
+            .unwrap();
+        let count_in = cli_args_from_matches(&matches).unwrap().unwrap().count_in.unwrap();
+        assert_eq!(count_in.bars, 2);
+        assert_eq!(count_in.sound, SoundType::BuiltinWood);
+        
+        let matches = build_cli().try_get_matches_from(["cli-metronome", "100", "--count-in", "1"]).unwrap();
+        let count_in = cli_args_from_matches(&matches).unwrap().unwrap().count_in.unwrap();
+        assert_eq!(count_in.sound, SoundType::BuiltinBeep);
+        
+        assert!(build_cli().try_get_matches_from(["cli-metronome", "100", "--count-in", "5"]).is_err());
+        assert!(build_cli().try_get_matches_from(["cli-metronome", "100", "--count-in-sound", "wood"]).is_err());
+    }
+    
+    #[test]
+    fn test_session_limit_arguments() {
+        let matches = build_cli().try_get_matches_from(["cli-metronome", "100", "--duration", "10m"]).unwrap();
+        let cli_args = cli_args_from_matches(&matches).unwrap().unwrap();
+        assert_eq!(cli_args.session_limit, Some(SessionLimit::Duration(Duration::from_secs(600))));
+        
+        let matches = build_cli().try_get_matches_from(["cli-metronome", "100", "--bars", "64"]).unwrap();
+        let cli_args = cli_args_from_matches(&matches).unwrap().unwrap();
+        assert_eq!(cli_args.session_limit, Some(SessionLimit::Bars(64)));
+        
+        // In the trainer --bars keeps meaning bars per step, and --duration limits the session
+        let matches = build_cli()
+            .try_get_matches_from(["cli-metronome", "trainer", "--start", "80", "--target", "120", "--bars", "2", "--duration", "5m"])
+            .unwrap();
+        let cli_args = cli_args_from_matches(&matches).unwrap().unwrap();
+        assert_eq!(cli_args.speed_trainer.unwrap().bars_per_step, 2);
+        assert_eq!(cli_args.session_limit, Some(SessionLimit::Duration(Duration::from_secs(300))));
+        
+        assert!(build_cli().try_get_matches_from(["cli-metronome", "100", "--duration", "10"]).is_err());
+        assert!(build_cli().try_get_matches_from(["cli-metronome", "100", "--bars", "0"]).is_err());
+        assert!(build_cli().try_get_matches_from(["cli-metronome", "100", "--bars", "8", "--duration", "1m"]).is_err());
+    }
+    
+    #[cfg(feature = "gui")]
+    #[test]
+    fn test_setlist_argument() {
+        let matches = build_cli()
+            .try_get_matches_from(["cli-metronome", "--setlist", "gig.json", "-s", "eighths"])
+            .unwrap();
+        assert_eq!(matches.get_one::<PathBuf>("setlist"), Some(&PathBuf::from("gig.json")));
+        
+        // A setlist sets its own tempos
+        assert!(build_cli().try_get_matches_from(["cli-metronome", "120", "--setlist", "gig.json"]).is_err());
+        assert!(build_cli().try_get_matches_from(["cli-metronome", "--setlist", "gig.json", "--ramp-to", "140"]).is_err());
+        
+        let matches = build_cli()
+            .try_get_matches_from(["cli-metronome", "--setlist", "/nonexistent/gig.json"])
+            .unwrap();
+        assert!(cli_args_from_matches(&matches).is_err());
+    }
+    
+    #[cfg(not(feature = "gui"))]
+    #[test]
+    fn test_setlist_argument_needs_gui_feature() {
+        // Setlist files are JSON, so builds without the gui feature reject the option up front
+        assert!(build_cli().try_get_matches_from(["cli-metronome", "--setlist", "gig.json"]).is_err());
+    }
+    
+    #[test]
+    fn test_tap_subcommand() {
+        let matches = build_cli().try_get_matches_from(["cli-metronome", "tap"]).unwrap();
+        let args = cli_args_from_matches(&matches).unwrap().unwrap();
+        assert!(args.tap_only);
+        let tap_tempo = args.tap_tempo.unwrap();
+        assert_eq!(tap_tempo.max_taps, 8);
+        assert_eq!(tap_tempo.reset_after, Duration::from_secs(2));
+        
+        let matches = build_cli()
+            .try_get_matches_from(["cli-metronome", "tap", "--play", "90", "--taps", "4", "--reset", "1.5", "-t", "3/4"])
+            .unwrap();
+        let args = cli_args_from_matches(&matches).unwrap().unwrap();
+        assert!(!args.tap_only);
+        assert_eq!(args.bpm, 90);
+        assert_eq!(args.time_signature, TimeSignature::new(3, 4));
+        assert_eq!(args.tap_tempo.as_ref().unwrap().max_taps, 4);
+        assert_eq!(args.tap_tempo.unwrap().reset_after, Duration::from_millis(1500));
+        assert_eq!(args.change_timing, ChangeTiming::Immediate);
+        
+        let matches = build_cli().try_get_matches_from(["cli-metronome", "tap", "--play", "90", "--on-bar"]).unwrap();
+        let args = cli_args_from_matches(&matches).unwrap().unwrap();
+        assert_eq!(args.change_timing, ChangeTiming::NextBar);
+        assert!(build_cli().try_get_matches_from(["cli-metronome", "tap", "--on-bar"]).is_err());
+        
+        assert!(build_cli().try_get_matches_from(["cli-metronome", "tap", "--play", "450"]).is_err());
+        assert!(build_cli().try_get_matches_from(["cli-metronome", "tap", "--taps", "1"]).is_err());
+        let matches = build_cli().try_get_matches_from(["cli-metronome", "tap", "--reset", "0"]).unwrap();
+        assert!(cli_args_from_matches(&matches).is_err());
+    }
+    
+    #[test]
+    fn test_resample_quality_argument() {
+        let matches = build_cli().try_get_matches_from(["cli-metronome", "120"]).unwrap();
+        let args = cli_args_from_matches(&matches).unwrap().unwrap();
+        assert_eq!(args.resample_quality, ResampleQuality::Balanced);
+        
+        let matches = build_cli()
+            .try_get_matches_from(["cli-metronome", "120", "--resample-quality", "HIGH"])
+            .unwrap();
+        let args = cli_args_from_matches(&matches).unwrap().unwrap();
+        assert_eq!(args.resample_quality, ResampleQuality::High);
+        
+        let matches = build_cli()
+            .try_get_matches_from(["cli-metronome", "tap", "--play", "90", "--resample-quality", "fast"])
+            .unwrap();
+        let args = cli_args_from_matches(&matches).unwrap().unwrap();
+        assert_eq!(args.resample_quality, ResampleQuality::Fast);
+        
+        assert!(build_cli().try_get_matches_from(["cli-metronome", "120", "--resample-quality", "best"]).is_err());
+    }
+    
+    #[test]
+    fn test_tap_commands() {
+        assert!(matches!(TapCommand::parse(""), Some(TapCommand::Tap(_))));
+        assert_eq!(TapCommand::parse(" R \n"), Some(TapCommand::Reset));
+        assert_eq!(TapCommand::parse("quit"), Some(TapCommand::Quit));
+        assert_eq!(TapCommand::parse("s"), Some(TapCommand::TogglePause));
+        assert_eq!(TapCommand::parse("x"), None);
+    }
+    
+    #[test]
+    fn test_setlist_commands() {
+        assert_eq!(SetlistCommand::parse(""), Some(SetlistCommand::Next));
+        assert_eq!(SetlistCommand::parse(" n \n"), Some(SetlistCommand::Next));
+        assert_eq!(SetlistCommand::parse("P"), Some(SetlistCommand::Previous));
+        assert_eq!(SetlistCommand::parse("s"), Some(SetlistCommand::TogglePause));
+        assert_eq!(SetlistCommand::parse("x"), None);
+    }
+    
+    #[test]
+    fn test_playback_commands() {
+        assert_eq!(PlaybackCommand::parse(""), Some(PlaybackCommand::TogglePause));
+        assert_eq!(PlaybackCommand::parse(" S \n"), Some(PlaybackCommand::TogglePause));
+        assert_eq!(PlaybackCommand::parse("resume"), Some(PlaybackCommand::TogglePause));
+        assert_eq!(PlaybackCommand::parse("x"), None);
+    }
+}
@@ -1,7 +1,21 @@
 use std::io::{self, Write};
 use std::time::{Duration, Instant};
 use crate::audio::AudioStatus;
-use crate::models::{TimeSignature, Beat};
+use crate::models::{TimeSignature, Beat, BeatDropout, CountIn, GapClick, MetronomeState, SessionLimit, Setlist, Song, SongSection, Subdivision, SpeedTrainer, TapTempo, Tempo, TempoRamp, TempoRange, TrainerEnd, MAX_BPM, MIN_BPM, STRAIGHT_SWING};
+
+/// Format a number of bars, e.g. "1 bar" or "4 bars"
+fn bar_count(bars: u32) -> String {
+    if bars == 1 { "1 bar".to_string() } else { format!("{} bars", bars) }
+}
+
+/// Summarize a song's tempo and meter, e.g. "120 BPM, 4/4" or "3 sections, 26 bars"
+fn song_summary(song: &Song) -> String {
+    match song.sections.len() {
+        0 => format!("{} BPM, {}", song.bpm, song.time_signature),
+        1 => format!("1 section, {}", bar_count(song.total_bars())),
+        sections => format!("{} sections, {}", sections, bar_count(song.total_bars())),
+    }
+}
 
 pub struct DisplayEngine {
     start_time: Option<Instant>,
@@ -18,19 +32,231 @@ impl DisplayEngine {
         self.start_time = Some(start_time);
     }
     
-    pub fn show_startup_info(&self, bpm: u32, time_signature: TimeSignature, audio_status: &AudioStatus) {
+    pub fn show_startup_info(&self, bpm: impl Into<Tempo>, time_signature: TimeSignature, audio_status: &AudioStatus) {
         println!("CLI Metronome v0.1.0");
         println!("==================");
-        println!("BPM: {}", bpm);
-        println!("Time Signature: {}", time_signature.as_str());
+        println!("BPM: {}", time_signature.tempo_with_unit(bpm));
+        println!("Time Signature: {}", time_signature);
         println!("Audio Status: {}", audio_status);
-        println!("Press Ctrl+C to stop");
+        println!("Press Ctrl+C to stop, type s and Enter to pause and resume");
         println!();
         self.show_time_signature_legend(time_signature);
         println!();
     }
     
-    pub fn show_status(&self, bpm: u32, beat_count: u64, elapsed: Duration, time_signature: TimeSignature, current_beat_in_measure: u32) {
+    /// Show the active subdivision and its swing below the startup information
+    pub fn show_subdivision_info(&self, subdivision: Subdivision, swing: f32) {
+        if subdivision == Subdivision::None {
+            return;
+        }
+        println!("Subdivision: {} ({} clicks per beat)", subdivision.as_str(), subdivision.pulses_per_beat());
+        if subdivision.can_swing() && swing > STRAIGHT_SWING {
+            println!("Swing: {:.0}%", swing * 100.0);
+        }
+        println!("  \x1b[36m·\x1b[0m = Subdivision click");
+        println!();
+    }
+    
+    /// Show the configured tempo ramp below the startup information
+    pub fn show_ramp_info(&self, ramp: &TempoRamp) {
+        let direction = if ramp.target_bpm >= ramp.start_bpm { "Accelerando" } else { "Ritardando" };
+        println!("Tempo Ramp: {} → {} BPM over {} bars ({}, {})",
+            ramp.start_bpm, ramp.target_bpm, ramp.bars, ramp.curve.as_str().to_lowercase(), direction);
+        println!();
+    }
+    
+    /// Show the configured speed trainer below the startup information
+    pub fn show_trainer_info(&self, trainer: &SpeedTrainer) {
+        let end = match trainer.end {
+            TrainerEnd::Hold => "hold the target".to_string(),
+            TrainerEnd::Loop => format!("loop back to {} BPM", trainer.start_bpm),
+            TrainerEnd::DropBack(amount) => format!("drop back {} BPM and climb again", amount),
+        };
+        let step = if trainer.bars_per_step == 1 { "bar".to_string() } else { bar_count(trainer.bars_per_step) };
+        println!("Speed Trainer: {} → {} BPM, +{} BPM every {}, then {}",
+            trainer.start_bpm, trainer.target_bpm, trainer.increment, step, end);
+        println!();
+    }
+    
+    /// Announce a speed trainer tempo change on its own line
+    pub fn show_trainer_step(&self, step: u32, previous_bpm: Tempo, bpm: Tempo, trainer: &SpeedTrainer) {
+        let message = if bpm < previous_bpm {
+            match trainer.end {
+                TrainerEnd::Loop => format!("Looping back to {} BPM", bpm),
+                _ => format!("Dropping back to {} BPM", bpm),
+            }
+        } else if bpm >= trainer.target_bpm {
+            format!("Target reached: {} BPM", bpm)
+        } else {
+            format!("Step up to {} BPM (target {})", bpm, trainer.target_bpm)
+        };
+        println!("\r\x1B[2K\x1b[33mTrainer step {}:\x1b[0m {}", step, message);
+    }
+    
+    /// Show the gap click training below the startup information
+    pub fn show_gap_info(&self, gap_click: &GapClick) {
+        println!("Gap Click: {} with the click, then {} silent", bar_count(gap_click.play_bars), bar_count(gap_click.mute_bars));
+        println!("  \x1b[2m◌\x1b[0m = Silent beat (keep counting)");
+        println!();
+    }
+    
+    /// Show the random beat dropout below the startup information
+    pub fn show_dropout_info(&self, dropout: &BeatDropout) {
+        let downbeat = if dropout.keep_downbeat { ", beat 1 always plays" } else { "" };
+        print!("Random Dropout: {:.0}% of beats silent{}", dropout.probability * 100.0, downbeat);
+        match dropout.seed {
+            Some(seed) => println!(" (seed {})", seed),
+            None => println!(),
+        }
+        println!();
+    }
+    
+    /// Show the count-in below the startup information
+    pub fn show_count_in_info(&self, count_in: &CountIn) {
+        println!("Count-in: {} with the {} sound", bar_count(count_in.bars), count_in.sound.as_str().to_lowercase());
+        println!();
+    }
+    
+    /// Show when the session will stop on its own
+    pub fn show_session_limit_info(&self, session_limit: &SessionLimit) {
+        match session_limit {
+            SessionLimit::Duration(_) => println!("Session: stops after {}", session_limit),
+            SessionLimit::Bars(_) => println!("Session: stops after {}, not counting the count-in", session_limit),
+        }
+        println!();
+    }
+    
+    /// Show the count so far, e.g. "Count-in 1 2 3", keeping each finished bar on its own line
+    pub fn show_count_in(&self, beat: &Beat) {
+        let counts: Vec<String> = (1..=beat.beat_in_measure).map(|count| count.to_string()).collect();
+        print!("\r\x1B[2K\x1b[1;34mCount-in\x1b[0m {}", counts.join(" "));
+        if beat.beat_in_measure == beat.time_signature.beats_per_measure() {
+            println!();
+        }
+        io::stdout().flush().unwrap();
+    }
+    
+    /// Show that playback paused, with the position it will resume from, or that it resumed
+    pub fn show_pause(&self, paused: bool, state: &MetronomeState) {
+        if paused {
+            let elapsed_secs = state.get_elapsed_time().as_secs();
+            println!(
+                "\r\x1B[2K\x1b[1;33m⏸ Paused\x1b[0m on beat {}/{} (beat {}, {:02}:{:02}) - type s and Enter to resume",
+                state.current_beat_in_measure, state.time_signature.beats_per_measure(),
+                state.beat_count, elapsed_secs / 60, elapsed_secs % 60
+            );
+        } else {
+            println!("\r\x1B[2K\x1b[1;32m▶ Resumed\x1b[0m");
+        }
+    }
+    
+    /// Show the songs of a setlist and how to step through them
+    pub fn show_setlist_info(&self, setlist: &Setlist) {
+        println!("Setlist: {} songs", setlist.len());
+        for (index, song) in setlist.songs.iter().enumerate() {
+            let marker = if index == setlist.current_index() { "▶" } else { " " };
+            println!("  {} {}. {} - {}", marker, index + 1, song.name, song_summary(song));
+        }
+        println!("Press Enter for the next song, type p and Enter for the previous one, s and Enter to pause");
+        println!();
+    }
+    
+    /// Announce the song that is now playing on its own line
+    pub fn show_song(&self, position: usize, total: usize, song: &Song) {
+        println!("\r\x1B[2K\x1b[1;35mSong {}/{}:\x1b[0m {} - {}",
+            position, total, song.name, song_summary(song));
+    }
+    
+    /// Announce the song section that starts on this bar on its own line
+    pub fn show_section_change(&self, index: usize, total: usize, section: &SongSection) {
+        println!("\r\x1B[2K\x1b[35mSection {}/{}:\x1b[0m {} - {} of {} at {} BPM",
+            index + 1, total, section.display_name(index), bar_count(section.bars), section.time_signature, section.bpm);
+    }
+    
+    /// Show the song section and the bar within it in the status line
+    pub fn show_section_status(&self, section_name: &str, bar_in_section: u32, bars: u32) {
+        print!("\x1b[35m{}\x1b[0m bar {}/{} | ", section_name, bar_in_section, bars);
+        io::stdout().flush().unwrap();
+    }
+    
+    /// Tell the user there is no song further in the requested direction
+    pub fn show_setlist_boundary(&self, at_end: bool) {
+        let message = if at_end { "Already at the last song" } else { "Already at the first song" };
+        println!("\r\x1B[2K\x1b[2m{}\x1b[0m", message);
+    }
+    
+    /// Explain how to tap a tempo on the terminal
+    pub fn show_tap_info(&self, tap_tempo: &TapTempo) {
+        println!("Tap Tempo: press Enter in time, averaging the last {} taps; a {:.1} s pause starts over",
+            tap_tempo.max_taps, tap_tempo.reset_after.as_secs_f64());
+        println!("Type r and Enter to start over, q and Enter to finish");
+        println!();
+    }
+    
+    /// Show the tempo detected after a tap on its own line
+    pub fn show_tap(&self, taps: usize, tempo: Option<f64>) {
+        match tempo {
+            Some(tempo) => println!("\r\x1B[2K\x1b[36mTap {}:\x1b[0m {:.1} BPM", taps, tempo),
+            None => println!("\r\x1B[2K\x1b[36mTap {}:\x1b[0m keep tapping", taps),
+        }
+    }
+    
+    /// Confirm that the taps were cleared
+    pub fn show_tap_reset(&self) {
+        println!("\r\x1B[2K\x1b[2mTaps cleared, start tapping again\x1b[0m");
+    }
+    
+    /// Show the final tapped tempo and how to play it
+    pub fn show_tap_result(&self, bpm: Option<Tempo>) {
+        println!();
+        match bpm {
+            Some(bpm) => {
+                println!("Tapped tempo: \x1b[1m{}\x1b[0m BPM", bpm);
+                if TempoRange::default().contains(bpm) {
+                    println!("Play it with: cli-metronome {}", bpm);
+                } else {
+                    println!("This is outside the playable range of {} to {} BPM", MIN_BPM, MAX_BPM);
+                }
+            }
+            None => println!("Not enough taps to find a tempo"),
+        }
+    }
+    
+    /// Show whether the current bar has the click or is silent in the status line
+    pub fn show_gap_status(&self, gap_click: &GapClick, silent: bool, bar_in_section: u32) {
+        if silent {
+            print!("\x1b[7m SILENT {}/{} \x1b[0m | ", bar_in_section, gap_click.mute_bars);
+        } else {
+            print!("Click {}/{} | ", bar_in_section, gap_click.play_bars);
+        }
+        io::stdout().flush().unwrap();
+    }
+    
+    /// Show the time or bars left before the session limit in the status line
+    pub fn show_session_status(&self, remaining: &SessionLimit) {
+        print!("Left: \x1b[1m{}\x1b[0m | ", remaining);
+        io::stdout().flush().unwrap();
+    }
+    
+    /// Summarize a session that stopped at its limit
+    pub fn show_session_complete(&self, elapsed: Duration, bars_played: u64, beat_count: u64) {
+        let elapsed_secs = elapsed.as_secs();
+        println!(
+            "\n\x1b[1;32mSession complete:\x1b[0m {} ({} beats) in {:02}:{:02}",
+            bar_count(bars_played as u32), beat_count, elapsed_secs / 60, elapsed_secs % 60
+        );
+    }
+    
+    /// Show the current and target tempo of a running ramp in the status line
+    pub fn show_ramp_status(&self, current_tempo: f64, target_bpm: Tempo, progress: f64) {
+        print!(
+            "Ramp: \x1b[1m{:.1}\x1b[0m → \x1b[1m{}\x1b[0m BPM ({:3.0}%) | ",
+            current_tempo, target_bpm, progress * 100.0
+        );
+        io::stdout().flush().unwrap();
+    }
+    
+    pub fn show_status(&self, bpm: impl Into<Tempo>, beat_count: u64, elapsed: Duration, time_signature: TimeSignature, current_beat_in_measure: u32) {
         let elapsed_secs = elapsed.as_secs();
         let elapsed_mins = elapsed_secs / 60;
         let elapsed_secs = elapsed_secs % 60;
@@ -41,7 +267,7 @@ impl DisplayEngine {
         // Show enhanced status information with time signature and beat position
         print!(
             "BPM: \x1b[1m{:3}\x1b[0m | Beat: \x1b[1m{:4}\x1b[0m | Time: \x1b[32m{:02}:{:02}\x1b[0m | \x1b[36m{}\x1b[0m: ",
-            bpm, beat_count, elapsed_mins, elapsed_secs, time_signature.as_str()
+            time_signature.tempo_with_unit(bpm), beat_count, elapsed_mins, elapsed_secs, time_signature
         );
         
         // Show visual beat position within measure with enhanced indicators
@@ -113,8 +339,36 @@ impl DisplayEngine {
         io::stdout().flush().unwrap();
     }
     
+    /// Show the position of the current click within its beat, e.g. `[● · ·]` for triplets
+    pub fn show_subdivision_position(&self, beat: &Beat) {
+        let pulses = beat.subdivision.pulses_per_beat();
+        if pulses <= 1 {
+            return;
+        }
+        
+        print!("{} [", beat.subdivision.as_str());
+        for i in 0..pulses {
+            let symbol = if i == 0 { "●" } else { "·" };
+            if i == beat.subdivision_index {
+                print!("\x1b[1;36m{}\x1b[0m", symbol); // Bold cyan for the current click
+            } else {
+                print!("\x1b[2m{}\x1b[0m", symbol); // Dim for the other clicks
+            }
+            
+            if i + 1 < pulses {
+                print!(" ");
+            }
+        }
+        print!("] | ");
+        io::stdout().flush().unwrap();
+    }
+    
     /// Get the appropriate beat indicator symbol based on beat strength
     fn get_beat_indicator_symbol(&self, beat: &Beat) -> &'static str {
+        if beat.is_subdivision() {
+            return "·"; // Subdivision click between beats
+        }
+        
         let strength = beat.get_accent_strength();
         
         if strength >= 1.0 {
@@ -128,6 +382,13 @@ impl DisplayEngine {
     
     /// Get the appropriate visual beat symbol based on beat strength
     fn get_visual_beat_symbol(&self, beat: &Beat) -> &'static str {
+        if beat.in_gap {
+            return if beat.is_subdivision() { " " } else { "◌" };    // Silent gap bar (dotted circle)
+        }
+        if beat.is_subdivision() {
+            return "·";    // Subdivision click (small dot)
+        }
+        
         let strength = beat.get_accent_strength();
         
         if strength >= 1.0 {
@@ -147,62 +408,40 @@ impl DisplayEngine {
         println!("  \x1b[2m○\x1b[0m = Weak beat (no accent)");
         println!();
         
-        match time_signature {
-            TimeSignature::One => {
-                println!("  \x1b[36m1/4 Time Signature:\x1b[0m");
-                println!("    Beat positions: 1 2");
-                println!("    Pattern: \x1b[2m○\x1b[0m");
-                println!("    Description: weak");
-            }
-            TimeSignature::Two => {
-                println!("  \x1b[36m2/4 Time Signature:\x1b[0m");
-                println!("    Beat positions: 1 2");
-                println!("    Pattern: \x1b[1;31m●\x1b[0m \x1b[2m○\x1b[0m");
-                println!("    Description: Strong-weak");
-            }
-            TimeSignature::Three => {
-                println!("  \x1b[36m3/4 Time Signature:\x1b[0m");
-                println!("    Beat positions: 1 2 3");
-                println!("    Pattern: \x1b[1;31m●\x1b[0m \x1b[2m○\x1b[0m \x1b[2m○\x1b[0m");
-                println!("    Description: Strong-weak-weak (waltz time)");
-            }
-            TimeSignature::Four => {
-                println!("  \x1b[36m4/4 Time Signature:\x1b[0m");
-                println!("    Beat positions: 1 2 3 4");
-                println!("    Pattern: \x1b[1;31m●\x1b[0m \x1b[2m○\x1b[0m \x1b[1;33m◐\x1b[0m \x1b[2m○\x1b[0m");
-                println!("    Description: Strong-weak-medium-weak (common time)");
-            }
-            TimeSignature::Five => {
-                println!("  \x1b[36m5/8 Time Signature:\x1b[0m");
-                println!("    Beat positions: 1 2 3 4 5");
-                println!("    Pattern: \x1b[1;31m●\x1b[0m \x1b[2m○\x1b[0m \x1b[2m○\x1b[0m \x1b[2m○\x1b[0m \x1b[2m○\x1b[0m");
-                println!("    Description: Strong-weak-weak-weak-weak");
-            }
-            TimeSignature::Six => {
-                println!("  \x1b[36m6/8 Time Signature:\x1b[0m");
-                println!("    Beat positions: 1 2 3 4 5 6");
-                println!("    Pattern: \x1b[1;31m●\x1b[0m \x1b[2m○\x1b[0m \x1b[2m○\x1b[0m \x1b[1;33m◐\x1b[0m \x1b[2m○\x1b[0m \x1b[2m○\x1b[0m");
-                println!("    Description: Strong-weak-weak-medium-weak-weak (compound time)");
-            }
-            TimeSignature::Seven => {
-                println!("  \x1b[36m7/8 Time Signature:\x1b[0m");
-                println!("    Beat positions: 1 2 3 4 5 6 7");
-                println!("    Pattern: \x1b[1;31m●\x1b[0m \x1b[2m○\x1b[0m \x1b[2m○\x1b[0m \x1b[2m○\x1b[0m \x1b[2m○\x1b[0m \x1b[2m○\x1b[0m \x1b[2m○\x1b[0m");
-                println!("    Description: Strong-weak-weak-weak-weak-weak-weak");
+        let beats_per_measure = time_signature.beats_per_measure();
+        let positions: Vec<String> = (1..=beats_per_measure).map(|i| i.to_string()).collect();
+        let mut pattern = String::new();
+        for i in 1..=beats_per_measure {
+            // Separate the beat groups so additive meters such as 2+2+3 are visible
+            if i > 1 {
+                pattern.push_str(if time_signature.is_group_start(i) { " | " } else { " " });
             }
-            TimeSignature::Eight => {
-                println!("  \x1b[36m8/8 Time Signature:\x1b[0m");
-                println!("    Beat positions: 1 2 3 4 5 6 7 8");
-                println!("    Pattern: \x1b[1;31m●\x1b[0m \x1b[2m○\x1b[0m \x1b[2m○\x1b[0m \x1b[2m○\x1b[0m \x1b[1;33m◐\x1b[0m \x1b[2m○\x1b[0m \x1b[2m○\x1b[0m \x1b[2m○\x1b[0m");
-                println!("    Description: Strong-weak-weak-medium-weak-weak-weak (compound time)");
+            let temp_beat = crate::models::Beat::new(i as u64, time_signature, 120);
+            if temp_beat.is_strong_beat() {
+                pattern.push_str("\x1b[1;31m●\x1b[0m");
+            } else if temp_beat.is_medium_beat() {
+                pattern.push_str("\x1b[1;33m◐\x1b[0m");
+            } else {
+                pattern.push_str("\x1b[2m○\x1b[0m");
             }
         }
+        
+        println!("  \x1b[36m{} Time Signature:\x1b[0m", time_signature);
+        println!("    Beat positions: {}", positions.join(" "));
+        println!("    Pattern: {}", pattern);
+        let groups: Vec<String> = time_signature.groups().iter().map(|g| g.to_string()).collect();
+        println!("    Grouping: {}", groups.join("+"));
+        if time_signature.is_compound() && !time_signature.has_custom_grouping() {
+            println!("    Description: {} (compound time)", time_signature.describe_pattern());
+        } else {
+            println!("    Description: {}", time_signature.describe_pattern());
+        }
         println!();
     }
     
     /// Show enhanced time signature information with current beat context
     pub fn show_enhanced_time_signature_info(&self, time_signature: TimeSignature, current_beat: u32) {
-        let time_sig_str = time_signature.as_str();
+        let time_sig_str = time_signature.to_string();
         let beats_per_measure = time_signature.beats_per_measure();
         
         print!("\x1b[36m{}\x1b[0m time | Beat \x1b[1m{}\x1b[0m of \x1b[1m{}\x1b[0m | ", 
@@ -298,16 +537,7 @@ impl DisplayEngine {
     
     /// Get time signature display with current beat emphasis
     fn get_time_signature_display(&self, time_signature: TimeSignature) -> String {
-        match time_signature {
-            TimeSignature::One => format!("\x1b[36m1/4\x1b[0m"),     // Cyan
-            TimeSignature::Two => format!("\x1b[36m2/4\x1b[0m"),     // Cyan
-            TimeSignature::Three => format!("\x1b[36m3/4\x1b[0m"),   // Cyan
-            TimeSignature::Four => format!("\x1b[36m4/4\x1b[0m"),    // Cyan
-            TimeSignature::Five => format!("\x1b[36m5/8\x1b[0m"),   // Cyan
-            TimeSignature::Six => format!("\x1b[36m6/8\x1b[0m"),     // Cyan
-            TimeSignature::Seven => format!("\x1b[36m7/8\x1b[0m"),     // Cyan
-            TimeSignature::Eight => format!("\x1b[36m8/8\x1b[0m"),     // Cyan
-        }
+        format!("\x1b[36m{}\x1b[0m", time_signature) // Cyan
     }
     
     /// Get beat position display with visual emphasis
@@ -388,7 +618,7 @@ impl DisplayEngine {
         println!("    cli-metronome [BPM]");
         println!();
         println!("\x1b[1mARGUMENTS:\x1b[0m");
-        println!("    <BPM>    Beats per minute (60-200) - enables CLI mode");
+        println!("    <BPM>    Beats per minute (20-400, e.g. 72.5) - enables CLI mode");
         println!();
         println!("\x1b[1mOPTIONS:\x1b[0m");
         println!("    -h, --help    Show this help message");
@@ -420,7 +650,9 @@ impl DisplayEngine {
         println!("\x1b[1mNOTES:\x1b[0m");
         println!("    - The metronome supports both audio and visual indicators");
         println!("    - If audio is not available, visual-only mode will be used");
-        println!("    - BPM must be between 60 and 200");
+        println!("    - BPM must be between 20 and 400 and may have decimals");
+        println!("    - BPM counts the time signature's note value unless --beat-unit sets another");
+        println!("    - --duration or --bars stops the metronome on its own, with exit code 3");
         println!("    - Visual indicators use colors when terminal supports them");
         println!("    - Time signatures follow standard musical conventions");
     }
@@ -467,7 +699,7 @@ impl DisplayEngine {
         print!("\r\x1B[2K"); // Clear entire line
         
         // Show time signature
-        print!("\x1b[36m{}\x1b[0m ", beat.time_signature.as_str());
+        print!("\x1b[36m{}\x1b[0m ", beat.time_signature);
         
         // Show beat sequence with current beat highlighted
         let beats_per_measure = beat.time_signature.beats_per_measure();
@@ -542,7 +774,7 @@ impl DisplayEngine {
     
     /// Show beat pattern demonstration for a given time signature
     pub fn demonstrate_beat_pattern(&self, time_signature: TimeSignature) {
-        println!("\x1b[1mDemonstrating {} beat pattern:\x1b[0m", time_signature.as_str());
+        println!("\x1b[1mDemonstrating {} beat pattern:\x1b[0m", time_signature);
         
         let beats_per_measure = time_signature.beats_per_measure();
         
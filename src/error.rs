@@ -2,12 +2,48 @@ use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum MetronomeError {
-    #[error("Invalid BPM value: {0}. Must be between 60 and 200")]
-    InvalidBpm(u32),
+    #[error("Invalid BPM value: {0}. Must be within the tempo range, 20 to 400 by default")]
+    InvalidBpm(f64),
+    
+    #[error("Invalid tempo: {0}")]
+    InvalidTempo(String),
     
     #[error("Invalid volume value: {0}. Must be between 0.0 and 1.0")]
     InvalidVolume(f32),
     
+    #[error("Invalid swing value: {0}. Must be between 0.5 and 0.75")]
+    InvalidSwing(f32),
+    
+    #[error("Invalid time signature {0}")]
+    InvalidTimeSignature(String),
+    
+    #[error("Invalid accent pattern: {0}")]
+    InvalidAccentPattern(String),
+    
+    #[error("Invalid tempo ramp: {0}")]
+    InvalidTempoRamp(String),
+    
+    #[error("Invalid speed trainer: {0}")]
+    InvalidSpeedTrainer(String),
+    
+    #[error("Invalid gap click: {0}")]
+    InvalidGapClick(String),
+    
+    #[error("Invalid beat dropout: {0}")]
+    InvalidDropout(String),
+    
+    #[error("Invalid setlist: {0}")]
+    InvalidSetlist(String),
+    
+    #[error("Invalid tap tempo: {0}")]
+    InvalidTapTempo(String),
+    
+    #[error("Invalid count-in: {0}")]
+    InvalidCountIn(String),
+    
+    #[error("Invalid session limit: {0}")]
+    InvalidSessionLimit(String),
+    
     #[error("Audio system error: {0}")]
     AudioError(#[from] AudioError),
     
@@ -1,17 +1,27 @@
 // GUI application implementation using egui/eframe
 
+use std::sync::mpsc::Receiver;
 use std::sync::{Arc, Mutex};
-use std::time::Instant;
 
 use crate::metronome::Metronome;
-use crate::models::{GuiState, TimeSignature, SoundType};
+use crate::models::{AccentLevel, AccentPattern, Beat, BeatUnit, ChangeTiming, GuiState, ResampleQuality, SessionLimit, Setlist, Tempo, TempoCurve, TempoRange, TimeSignature, TrainerEnd, SoundType, Subdivision, MAX_SWING, STRAIGHT_SWING};
+
+/// Space in the beat indicator for one straight subdivision step
+const SUBDIVISION_SPACING: f32 = 4.0;
+
+/// Change requested from the setlist panel
+enum SetlistAction {
+    Select(usize),
+    Move(usize, usize),
+    Remove(usize),
+}
 
 /// Main GUI application structure
 pub struct MetronomeApp {
     metronome: Arc<Mutex<Metronome>>,
     gui_state: GuiState,
     audio_engine: Option<Arc<crate::audio::CrossPlatformAudio>>,
-    last_beat_time: Option<Instant>,
+    beat_receiver: Option<Receiver<Beat>>,
 }
 
 impl MetronomeApp {
@@ -23,24 +33,95 @@ impl MetronomeApp {
         if let Ok(config) = Self::load_config() {
             gui_state.volume = config.volume;
             gui_state.selected_time_signature = config.time_signature;
+            gui_state.grouping_input = GuiState::grouping_text(config.time_signature);
             gui_state.selected_beat_sound = config.beat_sound.clone();
             gui_state.selected_accent_sound = config.accent_sound.clone();
             gui_state.accent_enabled = config.accent_enabled;
             gui_state.bpm_input = config.bpm.to_string();
+            gui_state.tempo_range = config.tempo_range;
+            gui_state.selected_subdivision = config.subdivision;
+            gui_state.selected_subdivision_sound = config.subdivision_sound.clone();
+            gui_state.subdivision_volume = config.subdivision_volume;
+            gui_state.swing = config.swing;
+            gui_state.accent_pattern = config.accent_pattern.clone();
+            if let Some(ramp) = config.tempo_ramp {
+                gui_state.ramp_enabled = true;
+                gui_state.ramp_target_bpm = ramp.target_bpm.bpm();
+                gui_state.ramp_bars = ramp.bars;
+                gui_state.ramp_curve = ramp.curve;
+            }
+            if let Some(trainer) = config.speed_trainer {
+                gui_state.trainer_enabled = true;
+                gui_state.trainer_target_bpm = trainer.target_bpm.bpm();
+                gui_state.trainer_increment = trainer.increment;
+                gui_state.trainer_bars = trainer.bars_per_step;
+                gui_state.trainer_end = trainer.end;
+                if let TrainerEnd::DropBack(amount) = trainer.end {
+                    gui_state.trainer_drop_bpm = amount;
+                }
+            }
+            if let Some(gap_click) = config.gap_click {
+                gui_state.gap_enabled = true;
+                gui_state.gap_play_bars = gap_click.play_bars;
+                gui_state.gap_mute_bars = gap_click.mute_bars;
+            }
+            if let Some(dropout) = config.dropout {
+                gui_state.dropout_probability = dropout.probability;
+                gui_state.dropout_keep_downbeat = dropout.keep_downbeat;
+            }
+            if let Some(count_in) = &config.count_in {
+                gui_state.count_in_bars = count_in.bars;
+                gui_state.count_in_sound = count_in.sound.clone();
+            }
+            match config.session_limit {
+                Some(SessionLimit::Duration(duration)) => {
+                    gui_state.session_limit_enabled = true;
+                    gui_state.session_limit_by_bars = false;
+                    gui_state.session_minutes = (duration.as_secs() / 60).max(1) as u32;
+                }
+                Some(SessionLimit::Bars(bars)) => {
+                    gui_state.session_limit_enabled = true;
+                    gui_state.session_limit_by_bars = true;
+                    gui_state.session_bars = bars;
+                }
+                None => {}
+            }
+            gui_state.change_timing = config.change_timing;
+            gui_state.resample_quality = config.resample_quality;
             
             // Apply loaded settings to metronome
             if let Ok(metronome) = metronome.lock() {
+                let _ = metronome.set_tempo_range(config.tempo_range);
                 let _ = metronome.set_bpm(config.bpm);
                 metronome.set_time_signature(config.time_signature);
                 metronome.set_sounds(config.beat_sound, config.accent_sound);
                 metronome.set_accent_enabled(config.accent_enabled);
                 let _ = metronome.set_volume(config.volume);
+                metronome.set_subdivision(config.subdivision);
+                let _ = metronome.set_subdivision_sound(config.subdivision_sound, config.subdivision_volume);
+                let _ = metronome.set_swing(config.swing);
+                let _ = metronome.set_accent_pattern(config.accent_pattern);
+                metronome.set_tempo_ramp(config.tempo_ramp);
+                if config.speed_trainer.is_some() {
+                    metronome.set_speed_trainer(config.speed_trainer);
+                }
+                metronome.set_gap_click(config.gap_click);
+                metronome.set_dropout(config.dropout);
+                metronome.set_count_in(config.count_in);
+                metronome.set_session_limit(config.session_limit);
+                metronome.set_change_timing(config.change_timing);
             }
         }
         
+        // Load the saved setlist
+        if let Ok(setlist) = Self::load_setlist(&gui_state.tempo_range) {
+            gui_state.setlist = setlist;
+        }
+        
         // Initialize audio engine
         let audio_engine = {
-            let mut audio = crate::audio::CrossPlatformAudio::new();
+            let mut audio = crate::audio::CrossPlatformAudio::new()
+                .with_resample_quality(gui_state.resample_quality);
             match audio.initialize() {
                 Ok(()) => {
                     Some(Arc::new(audio))
@@ -56,15 +137,159 @@ impl MetronomeApp {
             metronome,
             gui_state,
             audio_engine,
-            last_beat_time: None,
+            beat_receiver: None,
+        }
+    }
+    
+    /// Convert the loaded sounds again with a different resampling quality
+    fn set_resample_quality(&mut self, quality: ResampleQuality) {
+        // The app holds the only reference to the engine, so it can be borrowed mutably
+        if let Some(audio_engine) = self.audio_engine.as_mut().and_then(Arc::get_mut) {
+            if let Err(e) = audio_engine.set_resample_quality(quality) {
+                self.gui_state.set_error(e.to_string());
+            }
+        }
+    }
+    
+    /// Switch to a setlist song and show its settings
+    fn select_song(&mut self, index: usize) {
+        let Some(song) = self.gui_state.setlist.select(index).cloned() else {
+            return;
+        };
+        self.gui_state.load_song(&song);
+        if let Ok(metronome) = self.metronome.lock() {
+            if let Err(e) = metronome.apply_song(&song) {
+                self.gui_state.set_error(e.to_string());
+            }
+        }
+    }
+    
+    /// Show the setlist with next/previous controls, reordering and editing
+    fn show_setlist_panel(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Setlist");
+        
+        let current = self.gui_state.setlist.current_index();
+        let song_count = self.gui_state.setlist.len();
+        ui.horizontal(|ui| {
+            if ui.add_enabled(current > 0, egui::Button::new("◀ Previous")).clicked() {
+                self.select_song(current - 1);
+            }
+            if ui.add_enabled(current + 1 < song_count, egui::Button::new("Next ▶")).clicked() {
+                self.select_song(current + 1);
+            }
+        });
+        
+        ui.separator();
+        
+        // Song list: click a song to load it, use the arrows to reorder
+        let mut action = None;
+        egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+            for (index, song) in self.gui_state.setlist.songs.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    let details = if song.sections.is_empty() {
+                        format!("{} BPM, {}", song.bpm, song.time_signature)
+                    } else {
+                        song.sections.iter().enumerate()
+                            .map(|(index, section)| format!("{}: {} × {} at {} BPM",
+                                section.display_name(index), section.bars, section.time_signature, section.bpm))
+                            .collect::<Vec<_>>()
+                            .join("\n")
+                    };
+                    let label = ui.selectable_label(index == current, format!("{}. {}", index + 1, song.name))
+                        .on_hover_text(details);
+                    if label.clicked() {
+                        action = Some(SetlistAction::Select(index));
+                    }
+                    if ui.add_enabled(index > 0, egui::Button::new("▲").small()).clicked() {
+                        action = Some(SetlistAction::Move(index, index - 1));
+                    }
+                    if ui.add_enabled(index + 1 < song_count, egui::Button::new("▼").small()).clicked() {
+                        action = Some(SetlistAction::Move(index, index + 1));
+                    }
+                    if ui.small_button("×").on_hover_text("Remove song").clicked() {
+                        action = Some(SetlistAction::Remove(index));
+                    }
+                });
+            }
+            if song_count == 0 {
+                ui.colored_label(egui::Color32::GRAY, "No songs yet");
+            }
+        });
+        
+        let mut changed = match action {
+            Some(SetlistAction::Select(index)) => {
+                self.select_song(index);
+                false
+            }
+            Some(SetlistAction::Move(from, to)) => {
+                self.gui_state.setlist.move_song(from, to);
+                true
+            }
+            Some(SetlistAction::Remove(index)) => {
+                self.gui_state.setlist.remove_song(index);
+                true
+            }
+            None => false,
+        };
+        
+        ui.separator();
+        
+        // Add the current settings as a new song, or store them in the selected one
+        ui.add(egui::TextEdit::singleline(&mut self.gui_state.song_name_input).hint_text("Song name"));
+        ui.horizontal(|ui| {
+            if ui.button("Add current").clicked() {
+                match self.gui_state.song_from_inputs(&self.gui_state.song_name_input) {
+                    Ok(song) => {
+                        self.gui_state.setlist.add_song(song);
+                        self.gui_state.setlist.select(self.gui_state.setlist.len() - 1);
+                        self.gui_state.song_name_input.clear();
+                        changed = true;
+                    }
+                    Err(e) => self.gui_state.set_error(e.to_string()),
+                }
+            }
+            
+            let selected_name = self.gui_state.setlist.current_song().map(|song| song.name.clone());
+            if let Some(name) = selected_name {
+                if ui.button("Update selected").on_hover_text("Store the current settings in the selected song").clicked() {
+                    match self.gui_state.song_from_inputs(&name) {
+                        Ok(song) => {
+                            // The song's sections are kept; they are edited in the setlist file
+                            self.gui_state.setlist.update_song(current, song);
+                            changed = true;
+                        }
+                        Err(e) => self.gui_state.set_error(e.to_string()),
+                    }
+                }
+            }
+        });
+        
+        if changed {
+            if let Err(e) = self.save_setlist() {
+                self.gui_state.set_error(format!("Failed to save setlist: {}", e));
+            }
+        }
+    }
+    
+    /// Tap the tempo and apply it once enough taps have been made
+    fn tap_tempo(&mut self) {
+        if self.gui_state.register_tap(std::time::Instant::now()).is_none() {
+            return;
+        }
+        if let Ok(metronome) = self.metronome.lock() {
+            match metronome.apply_tap_tempo(&self.gui_state.tap_tempo) {
+                Ok(_) => self.gui_state.clear_error(),
+                Err(e) => self.gui_state.set_error(e.to_string()),
+            }
         }
     }
     
     /// Adjust BPM by the given delta and update the input field
     fn adjust_bpm(&mut self, delta: i32) {
         if let Ok(metronome) = self.metronome.lock() {
-            let current_bpm = metronome.get_bpm() as i32;
-            let new_bpm = (current_bpm + delta).max(60).min(200) as u32;
+            // Step on from a tempo still waiting for the bar line
+            let current_bpm = metronome.get_pending_changes().0.unwrap_or(metronome.get_bpm()).bpm();
+            let new_bpm = self.gui_state.tempo_range.clamp(Tempo::from(current_bpm + delta as f64));
             
             // Update the input field
             self.gui_state.bpm_input = new_bpm.to_string();
@@ -105,13 +330,45 @@ impl MetronomeApp {
     
     /// Start the metronome
     fn start_metronome(&mut self) {
+        // A finished ramp is cleared, so set it again to replay it from the start
+        let tempo_ramp = match self.gui_state.get_tempo_ramp() {
+            Ok(tempo_ramp) => tempo_ramp,
+            Err(e) => {
+                self.gui_state.set_error(format!("Invalid tempo ramp: {}", e));
+                return;
+            }
+        };
+        let speed_trainer = match self.gui_state.get_speed_trainer() {
+            Ok(speed_trainer) => speed_trainer,
+            Err(e) => {
+                self.gui_state.set_error(format!("Invalid speed trainer: {}", e));
+                return;
+            }
+        };
+        
         if let Ok(metronome) = self.metronome.lock() {
-            if let Err(e) = metronome.start() {
-                self.gui_state.set_error(format!("Failed to start metronome: {}", e));
-            } else {
-                self.gui_state.clear_error();
-                self.gui_state.is_running = true;
-                self.last_beat_time = Some(Instant::now());
+            if tempo_ramp.is_some() {
+                metronome.set_tempo_ramp(tempo_ramp);
+            }
+            if speed_trainer.is_some() {
+                metronome.set_speed_trainer(speed_trainer);
+            }
+            let started = metronome.start_scheduler()
+                .and_then(|receiver| metronome.start().map(|()| receiver));
+            
+            match started {
+                Ok(receiver) => {
+                    if let Some(audio_engine) = &self.audio_engine {
+                        audio_engine.reset_click_timing();
+                    }
+                    self.gui_state.clear_error();
+                    self.gui_state.is_running = true;
+                    self.gui_state.session_finished = false;
+                    self.beat_receiver = Some(receiver);
+                }
+                Err(e) => {
+                    self.gui_state.set_error(format!("Failed to start metronome: {}", e));
+                }
             }
         }
     }
@@ -120,8 +377,28 @@ impl MetronomeApp {
     fn stop_metronome(&mut self) {
         if let Ok(metronome) = self.metronome.lock() {
             metronome.stop();
+            metronome.stop_scheduler();
             self.gui_state.is_running = false;
-            self.last_beat_time = None;
+            self.beat_receiver = None;
+        }
+    }
+    
+    /// Count the selected time signature in another beat unit
+    fn set_beat_unit(&mut self, beat_unit: BeatUnit) {
+        let result = self.gui_state.selected_time_signature.with_beat_unit(beat_unit);
+        if let Some(time_sig) = self.gui_state.handle_result(result) {
+            self.gui_state.selected_time_signature = time_sig;
+            if let Ok(metronome) = self.metronome.lock() {
+                metronome.set_time_signature(time_sig);
+            }
+        }
+    }
+    
+    /// Pause the running metronome or resume the paused one
+    fn toggle_pause(&mut self) {
+        if let Ok(metronome) = self.metronome.lock() {
+            let paused = metronome.toggle_pause();
+            self.gui_state.is_running = !paused;
         }
     }
     
@@ -129,43 +406,49 @@ impl MetronomeApp {
     fn reset_metronome(&mut self) {
         if let Ok(metronome) = self.metronome.lock() {
             metronome.stop();
+            metronome.stop_scheduler();
             metronome.reset_beat_position();
             self.gui_state.is_running = false;
-            self.last_beat_time = None;
+            self.beat_receiver = None;
             self.gui_state.clear_error();
         }
     }
     
-    /// Handle metronome beat timing and audio playback
+    /// Handle beats delivered by the scheduler thread and play their audio
     fn handle_metronome_beats(&mut self) {
-        if let Ok(metronome) = self.metronome.lock() {
-            if !metronome.is_running() {
-                return;
-            }
-            
-            if let Some(last_beat) = self.last_beat_time {
-                if metronome.should_play_beat(last_beat) {
-                    // Play the beat
-                    let beat = metronome.increment_beat();
-                    // Use accent sound for strong beats (strength >= 1.0) only, and only if accents are enabled
-                    let sound_type = if self.gui_state.accent_enabled && beat.get_accent_strength() >= 1.0 {
-                        &self.gui_state.selected_accent_sound
-                    } else {
-                        &self.gui_state.selected_beat_sound
-                    };
-                    
-                    // Play audio if available
-                    if let Some(audio_engine) = &self.audio_engine {
-                        if let Err(e) = audio_engine.play_sound_with_volume(sound_type, self.gui_state.volume) {
-                            eprintln!("Audio playback error: {}", e);
-                        }
+        let beats: Vec<Beat> = match &self.beat_receiver {
+            Some(receiver) => receiver.try_iter().collect(),
+            None => return,
+        };
+        
+        // No beats follow the session limit, so stop once the last one has been handled
+        let session_complete = beats.is_empty()
+            && self.metronome.lock().is_ok_and(|metronome| metronome.is_session_complete());
+        if session_complete {
+            self.stop_metronome();
+            self.gui_state.session_finished = true;
+            return;
+        }
+        
+        for beat in beats {
+            // Muted, gap and dropped beats only update the indicator
+            if !beat.is_muted() {
+                // Accent sound for strong beats, subdivision sound between beats, beat sound otherwise
+                let (sound_type, volume) = match self.metronome.lock() {
+                    Ok(metronome) => metronome.get_sound_for_beat(&beat),
+                    Err(_) => continue,
+                };
+                
+                // Schedule audio at the beat's grid time, however late this frame is drawn
+                if let Some(audio_engine) = &self.audio_engine {
+                    if let Err(e) = audio_engine.schedule_beat(&beat, &sound_type, volume) {
+                        eprintln!("Audio playback error: {}", e);
                     }
-                    
-                    // Update visual beat indicator
-                    self.gui_state.update_beat_visual();
-                    self.last_beat_time = Some(Instant::now());
                 }
             }
+            
+            // Update visual beat indicator
+            self.gui_state.update_beat_visual();
         }
     }
     
@@ -189,8 +472,14 @@ impl MetronomeApp {
                 .map_err(|e| crate::error::ConfigError::WriteError(format!("Failed to create config directory: {}", e)))?;
         }
         
+        // A BPM field that is not a tempo in the range saves the tempo that is playing
+        let bpm = match self.gui_state.get_input_bpm() {
+            Ok(bpm) => bpm,
+            Err(_) => self.metronome.lock().unwrap_or_else(|e| e.into_inner()).get_bpm(),
+        };
+        
         let config = crate::models::MetronomeConfig {
-            bpm: self.gui_state.bpm_input.parse().unwrap_or(120),
+            bpm,
             time_signature: self.gui_state.selected_time_signature,
             beat_sound: self.gui_state.selected_beat_sound.clone(),
             accent_sound: self.gui_state.selected_accent_sound.clone(),
@@ -198,6 +487,21 @@ impl MetronomeApp {
             visual_enabled: true,
             accent_enabled: self.gui_state.accent_enabled,
             volume: self.gui_state.volume,
+            subdivision: self.gui_state.selected_subdivision,
+            subdivision_sound: self.gui_state.selected_subdivision_sound.clone(),
+            subdivision_volume: self.gui_state.subdivision_volume,
+            swing: self.gui_state.swing,
+            accent_pattern: self.gui_state.accent_pattern.clone()
+                .filter(|pattern| pattern.fits(self.gui_state.selected_time_signature)),
+            tempo_ramp: self.gui_state.get_tempo_ramp().ok().flatten(),
+            speed_trainer: self.gui_state.get_speed_trainer().ok().flatten(),
+            gap_click: self.gui_state.get_gap_click().ok().flatten(),
+            dropout: self.gui_state.get_dropout().ok().flatten(),
+            count_in: self.gui_state.get_count_in().ok().flatten(),
+            tempo_range: self.gui_state.tempo_range,
+            session_limit: self.gui_state.get_session_limit().ok().flatten(),
+            change_timing: self.gui_state.change_timing,
+            resample_quality: self.gui_state.resample_quality,
         };
         
         config.save_to_file(&config_path)
@@ -205,17 +509,47 @@ impl MetronomeApp {
     
     /// Get the configuration file path
     fn get_config_path() -> crate::error::Result<std::path::PathBuf> {
+        Ok(Self::get_config_dir().join("config.json"))
+    }
+    
+    /// Get the setlist file path, next to the configuration file
+    fn get_setlist_path() -> crate::error::Result<std::path::PathBuf> {
+        Ok(Self::get_config_dir().join("setlist.json"))
+    }
+    
+    /// Get the directory holding the configuration and setlist files
+    fn get_config_dir() -> std::path::PathBuf {
         use std::path::PathBuf;
         
         // Try to get user config directory
-        let config_dir = if let Some(config_dir) = dirs::config_dir() {
+        if let Some(config_dir) = dirs::config_dir() {
             config_dir.join("cli-metronome")
         } else {
             // Fallback to current directory
             PathBuf::from(".")
-        };
+        }
+    }
+    
+    /// Load the setlist from file, or start with an empty one
+    fn load_setlist(tempo_range: &TempoRange) -> crate::error::Result<Setlist> {
+        let setlist_path = Self::get_setlist_path()?;
+        if setlist_path.exists() {
+            Setlist::load_from_file(&setlist_path, tempo_range)
+        } else {
+            Ok(Setlist::new())
+        }
+    }
+    
+    /// Save the setlist to file
+    fn save_setlist(&self) -> crate::error::Result<()> {
+        let setlist_path = Self::get_setlist_path()?;
+        
+        if let Some(parent) = setlist_path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| crate::error::ConfigError::WriteError(format!("Failed to create config directory: {}", e)))?;
+        }
         
-        Ok(config_dir.join("config.json"))
+        self.gui_state.setlist.save_to_file(&setlist_path)
     }
 }
 
@@ -225,6 +559,19 @@ impl eframe::App for MetronomeApp {
         // Handle metronome beats
         self.handle_metronome_beats();
         
+        // Space taps the tempo unless a text field or button has keyboard focus
+        let space_pressed = ctx.input(|input| input.key_pressed(egui::Key::Space));
+        if space_pressed && ctx.memory(|memory| memory.focus().is_none()) {
+            self.tap_tempo();
+        }
+        
+        // Setlist panel beside the main controls
+        egui::SidePanel::right("setlist_panel")
+            .default_width(220.0)
+            .show(ctx, |ui| {
+                self.show_setlist_panel(ui);
+            });
+        
         // Set up the main window
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.heading("Metronome");
@@ -246,6 +593,19 @@ impl eframe::App for MetronomeApp {
                         let _ = self.gui_state.validate_bpm(&input_clone);
                     }
                     
+                    // Beat unit the tempo counts, e.g. dotted quarters in 6/8
+                    ui.label("per");
+                    let current_unit = self.gui_state.selected_time_signature.beat_unit();
+                    egui::ComboBox::from_id_source("beat_unit")
+                        .selected_text(current_unit.map_or("Note", |unit| unit.as_str()))
+                        .show_ui(ui, |ui| {
+                            for &beat_unit in BeatUnit::all() {
+                                if ui.selectable_label(current_unit == Some(beat_unit), beat_unit.as_str()).clicked() {
+                                    self.set_beat_unit(beat_unit);
+                                }
+                            }
+                        });
+                    
                     // Apply BPM button
                     if ui.button("Apply").clicked() {
                         let input_clone = self.gui_state.bpm_input.clone();
@@ -271,7 +631,7 @@ impl eframe::App for MetronomeApp {
                     
                     // Current BPM display
                     if let Ok(metronome) = self.metronome.lock() {
-                        ui.label(format!("Current: {}", metronome.get_bpm()));
+                        ui.label(format!("Current: {}", metronome.get_time_signature().tempo_with_unit(metronome.get_bpm())));
                     }
                     
                     // BPM increase buttons
@@ -281,11 +641,132 @@ impl eframe::App for MetronomeApp {
                     if ui.button("+10").clicked() {
                         self.adjust_bpm(10);
                     }
+                    
+                    ui.separator();
+                    
+                    // Tap tempo: averages the recent taps and starts over after a pause
+                    if ui.button("Tap").on_hover_text("Tap in time to set the tempo, or press Space").clicked() {
+                        self.tap_tempo();
+                    }
+                    match (self.gui_state.tap_tempo.tap_count(), self.gui_state.tap_tempo.tempo()) {
+                        (0, _) => {}
+                        (taps, Some(tempo)) => {
+                            ui.label(format!("{} taps: {:.1} BPM", taps, tempo));
+                        }
+                        (_, None) => {
+                            ui.colored_label(egui::Color32::GRAY, "Keep tapping");
+                        }
+                    }
+                });
+                
+                // Tempo ramp controls: from the entered BPM to a target over a number of bars
+                ui.horizontal(|ui| {
+                    let mut ramp_changed = ui.checkbox(&mut self.gui_state.ramp_enabled, "Tempo Ramp").changed();
+                    if ramp_changed && self.gui_state.ramp_enabled {
+                        // The ramp replaces the speed trainer
+                        self.gui_state.trainer_enabled = false;
+                    }
+                    
+                    ui.add_enabled_ui(self.gui_state.ramp_enabled, |ui| {
+                        ui.label("to");
+                        ramp_changed |= ui.add(egui::DragValue::new(&mut self.gui_state.ramp_target_bpm)
+                            .clamp_range(self.gui_state.tempo_range.min..=self.gui_state.tempo_range.max)
+                            .max_decimals(2)
+                            .suffix(" BPM")).changed();
+                        ui.label("over");
+                        ramp_changed |= ui.add(egui::DragValue::new(&mut self.gui_state.ramp_bars)
+                            .clamp_range(1..=999)
+                            .suffix(" bars")).changed();
+                        
+                        egui::ComboBox::from_id_source("ramp_curve")
+                            .selected_text(self.gui_state.ramp_curve.as_str())
+                            .show_ui(ui, |ui| {
+                                for &curve in TempoCurve::all() {
+                                    ramp_changed |= ui.selectable_value(&mut self.gui_state.ramp_curve, curve, curve.as_str()).changed();
+                                }
+                            });
+                    });
+                    
+                    // Start the ramp over with the new settings, or cancel it
+                    if ramp_changed {
+                        match self.gui_state.get_tempo_ramp() {
+                            Ok(tempo_ramp) => {
+                                if let Ok(metronome) = self.metronome.lock() {
+                                    metronome.set_tempo_ramp(tempo_ramp);
+                                }
+                            }
+                            Err(e) => self.gui_state.set_error(format!("Invalid tempo ramp: {}", e)),
+                        }
+                    }
                 });
                 
+                // Speed trainer controls: step up from the entered BPM every few bars
+                let mut trainer_changed = false;
+                ui.horizontal(|ui| {
+                    trainer_changed |= ui.checkbox(&mut self.gui_state.trainer_enabled, "Speed Trainer").changed();
+                    if trainer_changed && self.gui_state.trainer_enabled {
+                        // The trainer replaces the tempo ramp
+                        self.gui_state.ramp_enabled = false;
+                    }
+                    
+                    ui.add_enabled_ui(self.gui_state.trainer_enabled, |ui| {
+                        ui.label("to");
+                        trainer_changed |= ui.add(egui::DragValue::new(&mut self.gui_state.trainer_target_bpm)
+                            .clamp_range(self.gui_state.tempo_range.min..=self.gui_state.tempo_range.max)
+                            .max_decimals(2)
+                            .suffix(" BPM")).changed();
+                        ui.label("by");
+                        trainer_changed |= ui.add(egui::DragValue::new(&mut self.gui_state.trainer_increment)
+                            .clamp_range(1..=50)
+                            .prefix("+")
+                            .suffix(" BPM")).changed();
+                        ui.label("every");
+                        trainer_changed |= ui.add(egui::DragValue::new(&mut self.gui_state.trainer_bars)
+                            .clamp_range(1..=64)
+                            .suffix(" bars")).changed();
+                    });
+                });
+                ui.horizontal(|ui| {
+                    ui.add_enabled_ui(self.gui_state.trainer_enabled, |ui| {
+                        ui.label("At target:");
+                        let ends = [TrainerEnd::Hold, TrainerEnd::Loop, TrainerEnd::DropBack(self.gui_state.trainer_drop_bpm)];
+                        egui::ComboBox::from_id_source("trainer_end")
+                            .selected_text(self.gui_state.trainer_end.as_str())
+                            .show_ui(ui, |ui| {
+                                for end in ends {
+                                    trainer_changed |= ui.selectable_value(&mut self.gui_state.trainer_end, end, end.as_str()).changed();
+                                }
+                            });
+                        
+                        if matches!(self.gui_state.trainer_end, TrainerEnd::DropBack(_)) {
+                            ui.label("by");
+                            let drop_changed = ui.add(egui::DragValue::new(&mut self.gui_state.trainer_drop_bpm)
+                                .clamp_range(1..=140)
+                                .suffix(" BPM")).changed();
+                            if drop_changed {
+                                self.gui_state.trainer_end = TrainerEnd::DropBack(self.gui_state.trainer_drop_bpm);
+                                trainer_changed = true;
+                            }
+                        }
+                    });
+                });
+                
+                // Start the trainer over with the new settings, or stop it
+                if trainer_changed {
+                    match self.gui_state.get_speed_trainer() {
+                        Ok(speed_trainer) => {
+                            if let Ok(metronome) = self.metronome.lock() {
+                                metronome.set_speed_trainer(speed_trainer);
+                            }
+                        }
+                        Err(e) => self.gui_state.set_error(format!("Invalid speed trainer: {}", e)),
+                    }
+                }
+                
                 // BPM validation indicator
                 if !self.gui_state.bpm_valid {
-                    ui.colored_label(egui::Color32::RED, "Invalid BPM (must be 60-200)");
+                    let range = self.gui_state.tempo_range;
+                    ui.colored_label(egui::Color32::RED, format!("Invalid BPM (must be {}-{})", range.min, range.max));
                 }
             });
             
@@ -300,21 +781,21 @@ impl eframe::App for MetronomeApp {
                     
                     // Time signature dropdown
                     egui::ComboBox::from_label("")
-                        .selected_text(self.gui_state.selected_time_signature.as_str())
+                        .selected_text(self.gui_state.selected_time_signature.to_string())
                         .show_ui(ui, |ui| {
-                            for &time_sig in TimeSignature::all() {
+                            for &preset in TimeSignature::all() {
+                                let time_sig = self.gui_state.with_selected_beat_unit(preset);
                                 let selected = ui.selectable_value(
                                     &mut self.gui_state.selected_time_signature,
                                     time_sig,
-                                    time_sig.as_str()
+                                    preset.to_string()
                                 );
                                 
                                 // Apply time signature change immediately
                                 if selected.clicked() {
+                                    self.gui_state.grouping_input.clear();
                                     if let Ok(metronome) = self.metronome.lock() {
                                         metronome.set_time_signature(time_sig);
-                                        // Reset beat position when changing time signature
-                                        metronome.reset_beat_position();
                                     }
                                 }
                             }
@@ -323,10 +804,140 @@ impl eframe::App for MetronomeApp {
                     // Current time signature display
                     if let Ok(metronome) = self.metronome.lock() {
                         let current_time_sig = metronome.get_time_signature();
-                        ui.label(format!("Current: {}", current_time_sig.as_str()));
+                        ui.label(format!("Current: {}", current_time_sig));
                         ui.label(format!("({} beats per measure)", current_time_sig.beats_per_measure()));
                     }
                 });
+                
+                // Custom time signature: any numerator over a power-of-two note value
+                ui.horizontal(|ui| {
+                    ui.label("Custom:");
+                    
+                    let current = self.gui_state.selected_time_signature;
+                    let mut numerator = current.numerator();
+                    let mut denominator = current.denominator();
+                    
+                    ui.add(egui::DragValue::new(&mut numerator)
+                        .clamp_range(1..=TimeSignature::MAX_NUMERATOR)
+                        .speed(0.1));
+                    ui.label("/");
+                    egui::ComboBox::from_id_source("time_signature_denominator")
+                        .selected_text(denominator.to_string())
+                        .width(50.0)
+                        .show_ui(ui, |ui| {
+                            for value in TimeSignature::DENOMINATORS {
+                                ui.selectable_value(&mut denominator, value, value.to_string());
+                            }
+                        });
+                    
+                    if numerator != current.numerator() || denominator != current.denominator() {
+                        // Keep the grouping when only the note value changes
+                        let result = TimeSignature::try_new(numerator, denominator).and_then(|time_sig| {
+                            if numerator == current.numerator() && current.has_custom_grouping() {
+                                time_sig.with_grouping(&current.groups())
+                            } else {
+                                Ok(time_sig)
+                            }
+                        });
+                        match result {
+                            Ok(time_sig) => {
+                                let time_sig = self.gui_state.with_selected_beat_unit(time_sig);
+                                self.gui_state.selected_time_signature = time_sig;
+                                self.gui_state.grouping_input = GuiState::grouping_text(time_sig);
+                                if let Ok(metronome) = self.metronome.lock() {
+                                    metronome.set_time_signature(time_sig);
+                                }
+                            }
+                            Err(e) => self.gui_state.set_error(e.to_string()),
+                        }
+                    }
+                });
+                
+                // Beat grouping for additive meters; the first beat of each group is accented
+                ui.horizontal(|ui| {
+                    ui.label("Grouping:");
+                    
+                    let response = ui.add(egui::TextEdit::singleline(&mut self.gui_state.grouping_input)
+                        .desired_width(80.0)
+                        .hint_text("e.g. 2+2+3"));
+                    
+                    if response.lost_focus() {
+                        let input = self.gui_state.grouping_input.clone();
+                        if let Ok(time_sig) = self.gui_state.validate_grouping(&input) {
+                            self.gui_state.grouping_input = GuiState::grouping_text(time_sig);
+                            if let Ok(metronome) = self.metronome.lock() {
+                                metronome.set_time_signature(time_sig);
+                            }
+                        }
+                    }
+                    
+                    let groups: Vec<String> = self.gui_state.selected_time_signature.groups()
+                        .iter()
+                        .map(|g| g.to_string())
+                        .collect();
+                    ui.label(format!("({})", groups.join("+")));
+                });
+                
+                // Tempo and meter changes can wait for the bar line so the phrase is played out
+                ui.horizontal(|ui| {
+                    ui.label("Apply changes:");
+                    for &timing in ChangeTiming::all() {
+                        if ui.radio_value(&mut self.gui_state.change_timing, timing, timing.as_str()).changed() {
+                            if let Ok(metronome) = self.metronome.lock() {
+                                metronome.set_change_timing(timing);
+                            }
+                        }
+                    }
+                    
+                    if let Ok(metronome) = self.metronome.lock() {
+                        let (pending_bpm, pending_time_sig) = metronome.get_pending_changes();
+                        if let Some(text) = GuiState::pending_change_text(pending_bpm, pending_time_sig) {
+                            ui.colored_label(egui::Color32::YELLOW, text);
+                        }
+                    }
+                });
+                
+                ui.horizontal(|ui| {
+                    ui.label("Subdivision:");
+                    
+                    // Subdivision dropdown
+                    egui::ComboBox::from_label("subdivision")
+                        .selected_text(self.gui_state.selected_subdivision.as_str())
+                        .show_ui(ui, |ui| {
+                            for &subdivision in Subdivision::all() {
+                                let selected = ui.selectable_value(
+                                    &mut self.gui_state.selected_subdivision,
+                                    subdivision,
+                                    subdivision.as_str()
+                                );
+                                
+                                // Apply subdivision change immediately
+                                if selected.clicked() {
+                                    if let Ok(metronome) = self.metronome.lock() {
+                                        metronome.set_subdivision(subdivision);
+                                    }
+                                }
+                            }
+                        });
+                    
+                    // Swing delays every second click, so it needs an even subdivision
+                    ui.add_enabled_ui(self.gui_state.selected_subdivision.can_swing(), |ui| {
+                        ui.label("Swing:");
+                        let swing_changed = ui.add(
+                            egui::Slider::new(&mut self.gui_state.swing, STRAIGHT_SWING..=MAX_SWING)
+                                .show_value(false)
+                        ).on_hover_text("50% is straight, 67% triplet swing, 75% hard shuffle").changed();
+                        ui.label(format!("{}%", (self.gui_state.swing * 100.0).round() as u32));
+                        
+                        if swing_changed {
+                            if let Ok(metronome) = self.metronome.lock() {
+                                if let Err(e) = metronome.set_swing(self.gui_state.swing) {
+                                    self.gui_state.set_error(e.to_string());
+                                }
+                            }
+                        }
+                    });
+                });
             });
             
             ui.separator();
@@ -399,6 +1010,47 @@ impl eframe::App for MetronomeApp {
                     }
                 });
                 
+                ui.horizontal(|ui| {
+                    ui.label("Subdivision Sound:");
+                    
+                    // Subdivision sound dropdown
+                    let mut subdivision_sound_changed = false;
+                    egui::ComboBox::from_label("subdivision_sound")
+                        .selected_text(self.gui_state.selected_subdivision_sound.as_str())
+                        .show_ui(ui, |ui| {
+                            for sound_type in SoundType::builtin_sounds() {
+                                subdivision_sound_changed |= ui.selectable_value(
+                                    &mut self.gui_state.selected_subdivision_sound,
+                                    sound_type.clone(),
+                                    sound_type.as_str()
+                                ).clicked();
+                            }
+                        });
+                    
+                    // Subdivision level relative to the main volume
+                    subdivision_sound_changed |= ui.add(
+                        egui::Slider::new(&mut self.gui_state.subdivision_volume, 0.0..=1.0)
+                            .text("Level")
+                    ).changed();
+                    
+                    if subdivision_sound_changed {
+                        if let Ok(metronome) = self.metronome.lock() {
+                            if let Err(e) = metronome.set_subdivision_sound(
+                                self.gui_state.selected_subdivision_sound.clone(),
+                                self.gui_state.subdivision_volume
+                            ) {
+                                self.gui_state.set_error(e.to_string());
+                            }
+                        }
+                    }
+                    
+                    // Test subdivision sound button
+                    if ui.button("Test").clicked() {
+                        let volume = self.gui_state.volume * self.gui_state.subdivision_volume;
+                        self.test_sound_with_volume(&self.gui_state.selected_subdivision_sound.clone(), volume);
+                    }
+                });
+                
                 // Accent enabled toggle
                 ui.horizontal(|ui| {
                     ui.label("Enable Accents:");
@@ -412,6 +1064,108 @@ impl eframe::App for MetronomeApp {
                     }
                 });
                 
+                // Accent pattern editor: click a beat to cycle strong, medium, weak and mute
+                ui.horizontal(|ui| {
+                    ui.label("Accent Pattern:");
+                    
+                    // The metronome drops a custom pattern when the measure length changes
+                    let time_sig = self.gui_state.selected_time_signature;
+                    if self.gui_state.accent_pattern.as_ref().is_some_and(|pattern| !pattern.fits(time_sig)) {
+                        self.gui_state.accent_pattern = None;
+                    }
+                    let mut pattern = self.gui_state.accent_pattern.clone()
+                        .unwrap_or_else(|| AccentPattern::default_for(time_sig));
+                    let mut changed = false;
+                    
+                    for beat_num in 1..=time_sig.beats_per_measure() {
+                        if beat_num > 1 && time_sig.is_group_start(beat_num) {
+                            ui.colored_label(egui::Color32::DARK_GRAY, "|");
+                        }
+                        
+                        let level = pattern.level(beat_num);
+                        let (color, symbol) = match level {
+                            AccentLevel::Strong => (egui::Color32::RED, "●"),
+                            AccentLevel::Medium => (egui::Color32::from_rgb(255, 165, 0), "◐"),
+                            AccentLevel::Weak => (egui::Color32::GRAY, "○"),
+                            AccentLevel::Mute => (egui::Color32::DARK_GRAY, "×"),
+                        };
+                        let cell = ui.add(egui::Button::new(egui::RichText::new(symbol).color(color)).min_size(egui::vec2(22.0, 22.0)))
+                            .on_hover_text(format!("Beat {}: {}", beat_num, level.as_str()));
+                        if cell.clicked() {
+                            pattern.cycle_level(beat_num);
+                            changed = true;
+                        }
+                    }
+                    
+                    let reset = ui.button("Reset").on_hover_text("Use the time signature's accents");
+                    let new_pattern = if reset.clicked() {
+                        Some(None)
+                    } else if changed {
+                        Some(Some(pattern))
+                    } else {
+                        None
+                    };
+                    
+                    if let Some(new_pattern) = new_pattern {
+                        self.gui_state.accent_pattern = new_pattern.clone();
+                        if let Ok(metronome) = self.metronome.lock() {
+                            if let Err(e) = metronome.set_accent_pattern(new_pattern) {
+                                self.gui_state.set_error(e.to_string());
+                            }
+                        }
+                    }
+                });
+                
+                // Gap click training: play some bars, then leave some bars silent
+                ui.horizontal(|ui| {
+                    let mut gap_changed = ui.checkbox(&mut self.gui_state.gap_enabled, "Gap Click").changed();
+                    
+                    ui.add_enabled_ui(self.gui_state.gap_enabled, |ui| {
+                        ui.label("play");
+                        gap_changed |= ui.add(egui::DragValue::new(&mut self.gui_state.gap_play_bars)
+                            .clamp_range(1..=64)
+                            .suffix(" bars")).changed();
+                        ui.label("then mute");
+                        gap_changed |= ui.add(egui::DragValue::new(&mut self.gui_state.gap_mute_bars)
+                            .clamp_range(1..=64)
+                            .suffix(" bars")).changed();
+                    });
+                    
+                    // The new cycle starts with audible bars from the next downbeat
+                    if gap_changed {
+                        match self.gui_state.get_gap_click() {
+                            Ok(gap_click) => {
+                                if let Ok(metronome) = self.metronome.lock() {
+                                    metronome.set_gap_click(gap_click);
+                                }
+                            }
+                            Err(e) => self.gui_state.set_error(e.to_string()),
+                        }
+                    }
+                });
+                
+                // Random dropout: silence beats at random while the indicator keeps going
+                ui.horizontal(|ui| {
+                    ui.label("Random Dropout:");
+                    let mut dropout_changed = ui.add(
+                        egui::Slider::new(&mut self.gui_state.dropout_probability, 0.0..=1.0)
+                            .show_value(false)
+                    ).on_hover_text("Chance of each beat being silent").changed();
+                    ui.label(format!("{}%", (self.gui_state.dropout_probability * 100.0).round() as u32));
+                    dropout_changed |= ui.checkbox(&mut self.gui_state.dropout_keep_downbeat, "Keep beat 1").changed();
+                    
+                    if dropout_changed {
+                        match self.gui_state.get_dropout() {
+                            Ok(dropout) => {
+                                if let Ok(metronome) = self.metronome.lock() {
+                                    metronome.set_dropout(dropout);
+                                }
+                            }
+                            Err(e) => self.gui_state.set_error(e.to_string()),
+                        }
+                    }
+                });
+                
                 // Custom sound file selection (placeholder for now)
                 ui.horizontal(|ui| {
                     ui.label("Custom Sound:");
@@ -422,9 +1176,42 @@ impl eframe::App for MetronomeApp {
                     }
                 });
                 
+                // Interpolation used when a sound's sample rate differs from the device's
+                ui.horizontal(|ui| {
+                    ui.label("Resampling:");
+                    let mut quality_changed = false;
+                    egui::ComboBox::from_label("resample_quality")
+                        .selected_text(self.gui_state.resample_quality.as_str())
+                        .show_ui(ui, |ui| {
+                            for &quality in ResampleQuality::all() {
+                                quality_changed |= ui.selectable_value(
+                                    &mut self.gui_state.resample_quality,
+                                    quality,
+                                    quality.as_str()
+                                ).clicked();
+                            }
+                        });
+                    
+                    if quality_changed {
+                        self.set_resample_quality(self.gui_state.resample_quality);
+                    }
+                });
+                
                 // Audio status display
-                if let Some(_audio_engine) = &self.audio_engine {
-                    ui.label("Audio Status: Available");
+                if let Some(audio_engine) = &self.audio_engine {
+                    match audio_engine.output_format() {
+                        Some(format) => ui.label(format!("Audio Status: Available ({})", format)),
+                        None => ui.label("Audio Status: Available"),
+                    };
+                    
+                    // How closely the clicks of this run hit their scheduled frames
+                    if let Some(timing) = audio_engine.click_timing().filter(|timing| timing.clicks > 0) {
+                        if timing.is_sample_accurate() {
+                            ui.label(format!("Click timing: {}", timing));
+                        } else {
+                            ui.colored_label(egui::Color32::YELLOW, format!("Click timing: {}", timing));
+                        }
+                    }
                 } else {
                     ui.colored_label(egui::Color32::YELLOW, "Audio Status: Visual-only mode");
                 }
@@ -478,10 +1265,10 @@ impl eframe::App for MetronomeApp {
                 ui.label("Metronome Control");
                 
                 ui.horizontal(|ui| {
-                    let is_running = if let Ok(metronome) = self.metronome.lock() {
-                        metronome.is_running()
+                    let (is_running, is_paused) = if let Ok(metronome) = self.metronome.lock() {
+                        (metronome.is_running(), metronome.is_paused())
                     } else {
-                        false
+                        (false, false)
                     };
                     
                     if is_running {
@@ -490,8 +1277,25 @@ impl eframe::App for MetronomeApp {
                             self.stop_metronome();
                         }
                         
+                        // Pause keeps the bar and beat position
+                        if ui.button("⏸ Pause").clicked() {
+                            self.toggle_pause();
+                        }
+                        
                         // Visual status indicator
                         ui.colored_label(egui::Color32::GREEN, "● Running");
+                    } else if is_paused {
+                        // Resume continues from the next beat, Stop starts over next time
+                        if ui.button("▶ Resume").clicked() {
+                            self.toggle_pause();
+                        }
+                        
+                        if ui.button("⏹ Stop").clicked() {
+                            self.stop_metronome();
+                        }
+                        
+                        // Visual status indicator
+                        ui.colored_label(egui::Color32::YELLOW, "● Paused");
                     } else {
                         // Start button
                         if ui.button("▶ Start").clicked() {
@@ -503,6 +1307,73 @@ impl eframe::App for MetronomeApp {
                     }
                 });
                 
+                // Count-in played before the first beat on start
+                ui.horizontal(|ui| {
+                    ui.label("Count-in:");
+                    let mut count_in_changed = false;
+                    egui::ComboBox::from_id_source("count_in_bars")
+                        .selected_text(match self.gui_state.count_in_bars {
+                            0 => "Off".to_string(),
+                            1 => "1 bar".to_string(),
+                            bars => format!("{} bars", bars),
+                        })
+                        .width(70.0)
+                        .show_ui(ui, |ui| {
+                            for (bars, label) in [(0, "Off"), (1, "1 bar"), (2, "2 bars"), (4, "4 bars")] {
+                                count_in_changed |= ui.selectable_value(&mut self.gui_state.count_in_bars, bars, label).changed();
+                            }
+                        });
+                    
+                    ui.add_enabled_ui(self.gui_state.count_in_bars > 0, |ui| {
+                        egui::ComboBox::from_id_source("count_in_sound")
+                            .selected_text(self.gui_state.count_in_sound.as_str())
+                            .width(70.0)
+                            .show_ui(ui, |ui| {
+                                for sound in SoundType::builtin_sounds() {
+                                    count_in_changed |= ui.selectable_value(&mut self.gui_state.count_in_sound, sound.clone(), sound.as_str()).changed();
+                                }
+                            });
+                    });
+                    
+                    if count_in_changed {
+                        match self.gui_state.get_count_in() {
+                            Ok(count_in) => {
+                                if let Ok(metronome) = self.metronome.lock() {
+                                    metronome.set_count_in(count_in);
+                                }
+                            }
+                            Err(e) => self.gui_state.set_error(e.to_string()),
+                        }
+                    }
+                });
+                
+                // Practice session that stops on its own after a time or number of bars
+                ui.horizontal(|ui| {
+                    let mut session_changed = ui.checkbox(&mut self.gui_state.session_limit_enabled, "Stop after").changed();
+                    ui.add_enabled_ui(self.gui_state.session_limit_enabled, |ui| {
+                        if self.gui_state.session_limit_by_bars {
+                            session_changed |= ui.add(egui::DragValue::new(&mut self.gui_state.session_bars)
+                                .clamp_range(1..=9999)).changed();
+                        } else {
+                            session_changed |= ui.add(egui::DragValue::new(&mut self.gui_state.session_minutes)
+                                .clamp_range(1..=24 * 60)).changed();
+                        }
+                        session_changed |= ui.radio_value(&mut self.gui_state.session_limit_by_bars, false, "minutes").changed();
+                        session_changed |= ui.radio_value(&mut self.gui_state.session_limit_by_bars, true, "bars").changed();
+                    });
+                    
+                    if session_changed {
+                        match self.gui_state.get_session_limit() {
+                            Ok(session_limit) => {
+                                if let Ok(metronome) = self.metronome.lock() {
+                                    metronome.set_session_limit(session_limit);
+                                }
+                            }
+                            Err(e) => self.gui_state.set_error(e.to_string()),
+                        }
+                    }
+                });
+                
                 // Reset button
                 if ui.button("🔄 Reset").clicked() {
                     self.reset_metronome();
@@ -520,13 +1391,90 @@ impl eframe::App for MetronomeApp {
                     
                     // Current settings display
                     ui.horizontal(|ui| {
-                        ui.label(format!("BPM: {}", state.bpm));
+                        ui.label(format!("BPM: {}", state.time_signature.tempo_with_unit(state.bpm)));
                         ui.separator();
-                        ui.label(format!("Time Signature: {}", state.time_signature.as_str()));
+                        ui.label(format!("Time Signature: {}", state.time_signature));
                         ui.separator();
-                        ui.label(format!("Status: {}", if state.is_running { "Running" } else { "Stopped" }));
+                        let status = if state.is_running {
+                            "Running"
+                        } else if state.is_paused() {
+                            "Paused"
+                        } else {
+                            "Stopped"
+                        };
+                        ui.label(format!("Status: {}", status));
                     });
                     
+                    // Count-in beats so far, before the real first beat
+                    if let Some(count_in_beat) = state.get_count_in_beat() {
+                        let counts: Vec<String> = (1..=count_in_beat).map(|beat| beat.to_string()).collect();
+                        ui.label(egui::RichText::new(format!("Count-in {}", counts.join(" ")))
+                            .size(20.0)
+                            .color(egui::Color32::LIGHT_BLUE));
+                    }
+                    
+                    // Countdown to the session limit, or a note that the session has ended
+                    if let (true, Some(session_limit), Some(remaining)) = (state.is_running || state.is_paused(), state.session_limit, state.get_session_remaining()) {
+                        ui.horizontal(|ui| {
+                            ui.label(format!("Left: {}", remaining));
+                            let progress = match (session_limit, remaining) {
+                                (SessionLimit::Duration(total), SessionLimit::Duration(left)) => 1.0 - left.as_secs_f32() / total.as_secs_f32(),
+                                (SessionLimit::Bars(total), SessionLimit::Bars(left)) => 1.0 - left as f32 / total as f32,
+                                _ => 0.0,
+                            };
+                            ui.add(egui::ProgressBar::new(progress).desired_width(150.0));
+                        });
+                    } else if self.gui_state.session_finished {
+                        ui.colored_label(egui::Color32::GREEN, "Session complete");
+                    }
+                    
+                    // Current and target tempo while a ramp runs
+                    if let (Some(target_bpm), Some(progress)) = (state.get_target_bpm(), state.get_ramp_progress()) {
+                        ui.horizontal(|ui| {
+                            ui.label(format!("Ramp: {:.1} → {} BPM", state.get_current_tempo(), target_bpm));
+                            ui.add(egui::ProgressBar::new(progress as f32)
+                                .desired_width(150.0)
+                                .show_percentage());
+                        });
+                    }
+                    
+                    // Section and bar within it while a song with sections plays
+                    if let (Some(section), Some((index, bar))) = (state.get_current_section(), state.get_section_position()) {
+                        ui.horizontal(|ui| {
+                            ui.label(format!("Section {}/{}: {}", index + 1, state.sections.len(), section.display_name(index)));
+                            ui.separator();
+                            ui.label(format!("Bar {}/{}", bar, section.bars));
+                            ui.add(egui::ProgressBar::new(bar as f32 / section.bars as f32)
+                                .desired_width(150.0));
+                        });
+                    }
+                    
+                    // Trainer step and tempo while the speed trainer runs
+                    if let Some(trainer) = state.speed_trainer {
+                        ui.horizontal(|ui| {
+                            let holding = state.bpm >= trainer.target_bpm && trainer.end == TrainerEnd::Hold;
+                            ui.label(format!("Trainer step {}: {} BPM", state.trainer_step, state.bpm));
+                            ui.separator();
+                            if holding {
+                                ui.colored_label(egui::Color32::GREEN, "Target reached");
+                            } else {
+                                ui.label(format!("Target: {} BPM (+{} every {} bars)",
+                                    trainer.target_bpm, trainer.increment, trainer.bars_per_step));
+                            }
+                        });
+                    }
+                    
+                    // Click or silent section of gap click training
+                    if let (Some(gap_click), Some((silent, bar))) = (state.gap_click, state.get_gap_position()) {
+                        ui.horizontal(|ui| {
+                            if silent {
+                                ui.colored_label(egui::Color32::LIGHT_BLUE, format!("Silent bar {}/{} - keep counting", bar, gap_click.mute_bars));
+                            } else {
+                                ui.label(format!("Click bar {}/{}", bar, gap_click.play_bars));
+                            }
+                        });
+                    }
+                    
                     // Beat count and timing display
                     ui.horizontal(|ui| {
                         ui.label(format!("Total Beats: {}", state.beat_count));
@@ -548,17 +1496,32 @@ impl eframe::App for MetronomeApp {
                         ui.label("Beat Indicator:");
                         
                         // Show beat pattern for current time signature
+                        let in_gap = state.is_in_gap();
                         let beats_per_measure = state.time_signature.beats_per_measure();
                         for beat_num in 1..=beats_per_measure {
+                            // Mark where each beat group begins
+                            if beat_num > 1 && state.time_signature.is_group_start(beat_num) {
+                                ui.colored_label(egui::Color32::DARK_GRAY, "|");
+                            }
+                            
                             let is_current_beat = beat_num == state.current_beat_in_measure;
                             // Create a temporary beat to check accent strength
-                            let temp_beat = crate::models::Beat::new_with_accent_setting(beat_num as u64, state.time_signature, state.bpm, self.gui_state.accent_enabled);
+                            let temp_beat = crate::models::Beat::new_with_accent_setting(beat_num as u64, state.time_signature, state.bpm, self.gui_state.accent_enabled)
+                                .with_accent_level(state.get_accent_level(beat_num));
                             let is_strong_accent = temp_beat.get_accent_strength() >= 1.0;
                             let is_medium_accent = temp_beat.get_accent_strength() > 0.0 && temp_beat.get_accent_strength() < 1.0;
                             
                             // Visual beat indicator with different colors
-                            let (color, symbol) = if is_current_beat {
-                                if self.gui_state.should_show_beat_visual(std::time::Duration::from_millis(100)) {
+                            let (color, symbol) = if temp_beat.is_muted() {
+                                (egui::Color32::DARK_GRAY, "×") // Muted beat
+                            } else if in_gap {
+                                if is_current_beat && state.current_subdivision == 0 && self.gui_state.should_show_beat_visual(std::time::Duration::from_millis(100)) {
+                                    (egui::Color32::LIGHT_BLUE, "◌") // Current beat of a silent bar - light blue
+                                } else {
+                                    (egui::Color32::DARK_GRAY, "◌") // Silent bar position - dark gray
+                                }
+                            } else if is_current_beat {
+                                if state.current_subdivision == 0 && self.gui_state.should_show_beat_visual(std::time::Duration::from_millis(100)) {
                                     if is_strong_accent {
                                         (egui::Color32::RED, "●")  // Current strong accent beat - red
                                     } else if is_medium_accent {
@@ -582,6 +1545,26 @@ impl eframe::App for MetronomeApp {
                             };
                             
                             ui.colored_label(color, symbol);
+                            
+                            // Subdivision clicks between this beat and the next, spaced to show the swing
+                            let pulses = state.get_click_subdivision().pulses_per_beat();
+                            for sub_index in 1..pulses {
+                                let gap = state.get_click_offset(sub_index) - state.get_click_offset(sub_index - 1);
+                                ui.add_space(gap as f32 * pulses as f32 * SUBDIVISION_SPACING);
+                                let is_current_click = is_current_beat
+                                    && state.current_subdivision == sub_index
+                                    && self.gui_state.should_show_beat_visual(std::time::Duration::from_millis(100));
+                                let color = if is_current_click {
+                                    egui::Color32::LIGHT_BLUE // Current subdivision click - light blue
+                                } else {
+                                    egui::Color32::DARK_GRAY // Subdivision position - dark gray
+                                };
+                                ui.colored_label(color, "·");
+                            }
+                            if pulses > 1 {
+                                let gap = 1.0 - state.get_click_offset(pulses - 1);
+                                ui.add_space(gap as f32 * pulses as f32 * SUBDIVISION_SPACING);
+                            }
                         }
                         
                         // Beat strength indicator
@@ -591,11 +1574,15 @@ impl eframe::App for MetronomeApp {
                                 state.time_signature, 
                                 state.bpm,
                                 self.gui_state.accent_enabled
-                            );
+                            ).with_accent_level(state.get_accent_level(state.current_beat_in_measure));
                             let strength = current_beat.get_accent_strength();
                             
                             ui.separator();
-                            if strength >= 1.0 {
+                            if in_gap {
+                                ui.colored_label(egui::Color32::LIGHT_BLUE, "Silent Bar");
+                            } else if current_beat.is_muted() {
+                                ui.colored_label(egui::Color32::DARK_GRAY, "Muted Beat");
+                            } else if strength >= 1.0 {
                                 ui.colored_label(egui::Color32::RED, "Strong Beat");
                             } else if strength > 0.0 {
                                 ui.colored_label(egui::Color32::YELLOW, "Medium Beat");
@@ -618,9 +1605,14 @@ impl eframe::App for MetronomeApp {
                             if beat_visual_active {
                                 // Flash effect for beat
                                 // Create a temporary beat to check accent strength
-                                let temp_beat = crate::models::Beat::new_with_accent_setting(state.current_beat_in_measure as u64, state.time_signature, state.bpm, self.gui_state.accent_enabled);
+                                let temp_beat = crate::models::Beat::new_with_accent_setting(state.current_beat_in_measure as u64, state.time_signature, state.bpm, self.gui_state.accent_enabled)
+                                    .with_accent_level(state.get_accent_level(state.current_beat_in_measure));
                                 let accent_strength = temp_beat.get_accent_strength();
-                                let color = if accent_strength >= 1.0 {
+                                let color = if state.is_in_gap() {
+                                    egui::Color32::LIGHT_BLUE // Silent gap bar
+                                } else if temp_beat.is_muted() {
+                                    egui::Color32::DARK_GRAY // Muted beat
+                                } else if accent_strength >= 1.0 {
                                     egui::Color32::from_rgb(255, 100, 100) // Light red for strong accent
                                 } else if accent_strength > 0.0 {
                                     egui::Color32::from_rgb(255, 165, 100) // Light orange for medium accent
@@ -634,7 +1626,7 @@ impl eframe::App for MetronomeApp {
                             }
                             
                             // BPM indicator bars
-                            let interval_ms = (60000.0 / state.bpm as f32) as u64;
+                            let interval_ms = state.bpm.beat_interval().as_millis().max(1) as u64;
                             let progress = if let Some(start_time) = state.start_time {
                                 let elapsed_ms = start_time.elapsed().as_millis() as u64;
                                 (elapsed_ms % interval_ms) as f32 / interval_ms as f32
@@ -1,34 +1,40 @@
 use cli_metronome::{
-    app::{determine_mode, launch_app},
+    app::{determine_mode, launch_app, AppExit},
+    models::{MAX_BPM, MIN_BPM},
     MetronomeError,
 };
 
 fn main() {
     // Setup cleanup on exit
     let result = std::panic::catch_unwind(|| {
-        if let Err(e) = run() {
-            handle_application_error(&e);
-            std::process::exit(1);
+        match run() {
+            Ok(exit) => exit,
+            Err(e) => {
+                handle_application_error(&e);
+                std::process::exit(1);
+            }
         }
     });
     
     // Ensure cleanup happens even if panic occurs
     cleanup_resources();
     
-    if result.is_err() {
-        eprintln!("Application panicked. Exiting...");
-        std::process::exit(1);
+    match result {
+        Ok(AppExit::Stopped) => {}
+        Ok(exit) => std::process::exit(exit.exit_code()),
+        Err(_) => {
+            eprintln!("Application panicked. Exiting...");
+            std::process::exit(1);
+        }
     }
 }
 
-fn run() -> Result<(), MetronomeError> {
+fn run() -> Result<AppExit, MetronomeError> {
     // Determine application mode based on command line arguments
     let mode = determine_mode()?;
     
     // Launch application in appropriate mode
-    launch_app(mode)?;
-    
-    Ok(())
+    launch_app(mode)
 }
 
 // Cleanup resources before exit
@@ -46,7 +52,10 @@ fn cleanup_resources() {
 fn handle_application_error(error: &MetronomeError) {
     match error {
         MetronomeError::InvalidBpm(bpm) => {
-            eprintln!("Invalid BPM value: {}. Please use a value between 60 and 200.", bpm);
+            eprintln!("Invalid BPM value: {}. Please use a value between {} and {}.", bpm, MIN_BPM, MAX_BPM);
+        }
+        MetronomeError::InvalidTempo(details) => {
+            eprintln!("Invalid tempo: {}.", details);
         }
         MetronomeError::AudioError(audio_err) => {
             eprintln!("Audio system error: {}", audio_err);
@@ -69,5 +78,38 @@ fn handle_application_error(error: &MetronomeError) {
         MetronomeError::InvalidVolume(volume) => {
             eprintln!("Invalid volume value: {}. Please use a value between 0.0 and 1.0.", volume);
         }
+        MetronomeError::InvalidSwing(swing) => {
+            eprintln!("Invalid swing value: {}. Please use a value between 0.5 (straight) and 0.75 (hard shuffle).", swing);
+        }
+        MetronomeError::InvalidTimeSignature(details) => {
+            eprintln!("Invalid time signature {}. Please use a value such as 4/4 or 11/8.", details);
+        }
+        MetronomeError::InvalidAccentPattern(details) => {
+            eprintln!("Invalid accent pattern: {}. Please give one level per beat.", details);
+        }
+        MetronomeError::InvalidTempoRamp(details) => {
+            eprintln!("Invalid tempo ramp: {}.", details);
+        }
+        MetronomeError::InvalidSpeedTrainer(details) => {
+            eprintln!("Invalid speed trainer: {}.", details);
+        }
+        MetronomeError::InvalidGapClick(details) => {
+            eprintln!("Invalid gap click: {}.", details);
+        }
+        MetronomeError::InvalidDropout(details) => {
+            eprintln!("Invalid beat dropout: {}.", details);
+        }
+        MetronomeError::InvalidSetlist(details) => {
+            eprintln!("Invalid setlist: {}.", details);
+        }
+        MetronomeError::InvalidTapTempo(details) => {
+            eprintln!("Invalid tap tempo: {}.", details);
+        }
+        MetronomeError::InvalidCountIn(details) => {
+            eprintln!("Invalid count-in: {}.", details);
+        }
+        MetronomeError::InvalidSessionLimit(details) => {
+            eprintln!("Invalid session limit: {}.", details);
+        }
     }
 }
\ No newline at end of file
@@ -1,11 +1,20 @@
+pub mod scheduler;
+
+use std::sync::mpsc::Receiver;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use crate::error::{MetronomeError, Result};
-use crate::models::{Beat, MetronomeConfig, MetronomeState};
+use crate::models::{Beat, MetronomeConfig, MetronomeState, Tempo};
+
+pub use scheduler::BeatScheduler;
 
 #[derive(Debug)]
 pub struct Metronome {
     state: Arc<Mutex<MetronomeState>>,
+    scheduler: Mutex<Option<BeatScheduler>>,
+    /// Wakes `run_async` after a change that may move the next deadline
+    #[cfg(feature = "gui")]
+    changed: tokio::sync::Notify,
 }
 
 impl Metronome {
@@ -15,6 +24,9 @@ impl Metronome {
         
         Self {
             state: Arc::new(Mutex::new(state)),
+            scheduler: Mutex::new(None),
+            #[cfg(feature = "gui")]
+            changed: tokio::sync::Notify::new(),
         }
     }
     
@@ -24,23 +36,63 @@ impl Metronome {
         
         Ok(Self {
             state: Arc::new(Mutex::new(state)),
+            scheduler: Mutex::new(None),
+            #[cfg(feature = "gui")]
+            changed: tokio::sync::Notify::new(),
         })
     }
     
-    pub fn with_bpm(bpm: u32) -> Result<Self> {
+    pub fn with_bpm(bpm: impl Into<Tempo>) -> Result<Self> {
         let config = MetronomeConfig::new(bpm);
         config.validate()?;
         Self::from_config(config)
     }
     
-    pub fn set_bpm(&self, bpm: u32) -> Result<()> {
-        let mut state = self.state.lock().unwrap();
-        state.update_bpm(bpm)
+    /// Set the tempo, at once or at the next downbeat depending on the change timing
+    pub fn set_bpm(&self, bpm: impl Into<Tempo>) -> Result<()> {
+        let result = self.state.lock().unwrap().change_bpm(bpm);
+        self.notify_scheduler();
+        result
+    }
+    
+    /// Limit the tempos that can be set, bringing the current tempo into the range
+    pub fn set_tempo_range(&self, tempo_range: crate::models::TempoRange) -> Result<()> {
+        let result = self.state.lock().unwrap().update_tempo_range(tempo_range);
+        self.notify_scheduler();
+        result
+    }
+    
+    /// Set the tempo detected by a tap tempo, if it has found one yet
+    ///
+    /// A tapped tempo outside the tempo range is an error and leaves the tempo unchanged.
+    pub fn apply_tap_tempo(&self, tap_tempo: &crate::models::TapTempo) -> Result<Option<Tempo>> {
+        let Some(bpm) = tap_tempo.bpm() else {
+            return Ok(None);
+        };
+        self.set_bpm(bpm)?;
+        Ok(Some(bpm))
     }
     
+    /// Set the time signature, at once or at the next downbeat depending on the change timing
     pub fn set_time_signature(&self, time_signature: crate::models::TimeSignature) {
-        let mut state = self.state.lock().unwrap();
-        state.update_time_signature(time_signature);
+        self.state.lock().unwrap().change_time_signature(time_signature);
+        self.notify_scheduler();
+    }
+    
+    /// Choose whether tempo and meter changes apply at once or wait for the next bar line
+    pub fn set_change_timing(&self, change_timing: crate::models::ChangeTiming) {
+        self.state.lock().unwrap().update_change_timing(change_timing);
+    }
+    
+    pub fn get_change_timing(&self) -> crate::models::ChangeTiming {
+        let state = self.state.lock().unwrap();
+        state.change_timing
+    }
+    
+    /// Get the tempo and time signature waiting for the next downbeat, if any
+    pub fn get_pending_changes(&self) -> (Option<Tempo>, Option<crate::models::TimeSignature>) {
+        let state = self.state.lock().unwrap();
+        (state.pending_bpm, state.pending_time_signature)
     }
     
     pub fn set_sounds(&self, beat_sound: crate::models::SoundType, accent_sound: crate::models::SoundType) {
@@ -53,6 +105,149 @@ impl Metronome {
         state.update_accent_enabled(accent_enabled);
     }
     
+    /// Start a tempo ramp from the next beat (or from the start), or cancel it with `None`
+    pub fn set_tempo_ramp(&self, tempo_ramp: Option<crate::models::TempoRamp>) {
+        self.state.lock().unwrap().update_tempo_ramp(tempo_ramp);
+        self.notify_scheduler();
+    }
+    
+    /// Get the tempo ramp in progress, if any
+    pub fn get_tempo_ramp(&self) -> Option<crate::models::TempoRamp> {
+        let state = self.state.lock().unwrap();
+        state.tempo_ramp
+    }
+    
+    /// Start a speed trainer from the next beat (or from the start), or stop it with `None`
+    pub fn set_speed_trainer(&self, speed_trainer: Option<crate::models::SpeedTrainer>) {
+        self.state.lock().unwrap().update_speed_trainer(speed_trainer);
+        self.notify_scheduler();
+    }
+    
+    /// Get the speed trainer in progress, if any
+    pub fn get_speed_trainer(&self) -> Option<crate::models::SpeedTrainer> {
+        let state = self.state.lock().unwrap();
+        state.speed_trainer
+    }
+    
+    /// Start gap click training from the next downbeat, or end it with `None`
+    pub fn set_gap_click(&self, gap_click: Option<crate::models::GapClick>) {
+        let mut state = self.state.lock().unwrap();
+        state.update_gap_click(gap_click);
+    }
+    
+    /// Get the gap click training in effect, if any
+    pub fn get_gap_click(&self) -> Option<crate::models::GapClick> {
+        let state = self.state.lock().unwrap();
+        state.gap_click
+    }
+    
+    /// Set random beat dropout, or turn it off with `None`
+    pub fn set_dropout(&self, dropout: Option<crate::models::BeatDropout>) {
+        let mut state = self.state.lock().unwrap();
+        state.update_dropout(dropout);
+    }
+    
+    /// Get the random beat dropout in effect, if any
+    pub fn get_dropout(&self) -> Option<crate::models::BeatDropout> {
+        let state = self.state.lock().unwrap();
+        state.dropout
+    }
+    
+    /// Set the count-in played each time the metronome starts, or none with `None`
+    pub fn set_count_in(&self, count_in: Option<crate::models::CountIn>) {
+        let mut state = self.state.lock().unwrap();
+        state.update_count_in(count_in);
+    }
+    
+    /// Get the count-in played at start, if any
+    pub fn get_count_in(&self) -> Option<crate::models::CountIn> {
+        let state = self.state.lock().unwrap();
+        state.count_in.clone()
+    }
+    
+    /// Stop scheduling beats after a time or number of bars, or remove the limit with `None`
+    pub fn set_session_limit(&self, session_limit: Option<crate::models::SessionLimit>) {
+        self.state.lock().unwrap().update_session_limit(session_limit);
+        self.notify_scheduler();
+    }
+    
+    /// Get the session limit in effect, if any
+    pub fn get_session_limit(&self) -> Option<crate::models::SessionLimit> {
+        let state = self.state.lock().unwrap();
+        state.session_limit
+    }
+    
+    /// Get the time or bars left before the session limit, if one is set
+    pub fn get_session_remaining(&self) -> Option<crate::models::SessionLimit> {
+        let state = self.state.lock().unwrap();
+        state.get_session_remaining()
+    }
+    
+    /// Check whether the session limit has been reached
+    pub fn is_session_complete(&self) -> bool {
+        let state = self.state.lock().unwrap();
+        state.is_session_complete()
+    }
+    
+    /// Switch to a setlist song, restarting the count from beat 1 if running
+    pub fn apply_song(&self, song: &crate::models::Song) -> Result<()> {
+        let result = self.state.lock().unwrap().apply_song(song);
+        self.notify_scheduler();
+        result
+    }
+    
+    /// Get the exact tempo of the current beat, which is fractional while ramping
+    pub fn get_current_tempo(&self) -> f64 {
+        let state = self.state.lock().unwrap();
+        state.get_current_tempo()
+    }
+    
+    /// Set a custom per-beat accent pattern, or follow the time signature with `None`
+    pub fn set_accent_pattern(&self, accent_pattern: Option<crate::models::AccentPattern>) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        state.update_accent_pattern(accent_pattern)
+    }
+    
+    /// Get the per-beat accent levels currently in effect
+    pub fn get_accent_pattern(&self) -> crate::models::AccentPattern {
+        let state = self.state.lock().unwrap();
+        state.get_effective_accent_pattern()
+    }
+    
+    pub fn set_subdivision(&self, subdivision: crate::models::Subdivision) {
+        self.state.lock().unwrap().update_subdivision(subdivision);
+        self.notify_scheduler();
+    }
+    
+    pub fn get_subdivision(&self) -> crate::models::Subdivision {
+        let state = self.state.lock().unwrap();
+        state.subdivision
+    }
+    
+    /// Set the swing of subdivision clicks, from 0.5 (straight) to 0.75 (hard shuffle)
+    pub fn set_swing(&self, swing: f32) -> Result<()> {
+        self.state.lock().unwrap().update_swing(swing)?;
+        self.notify_scheduler();
+        Ok(())
+    }
+    
+    pub fn get_swing(&self) -> f32 {
+        let state = self.state.lock().unwrap();
+        state.swing
+    }
+    
+    /// Set the sound and relative level used for subdivision clicks
+    pub fn set_subdivision_sound(&self, sound: crate::models::SoundType, volume: f32) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        state.update_subdivision_sound(sound, volume)
+    }
+    
+    /// Get the sound and volume to play for the given beat
+    pub fn get_sound_for_beat(&self, beat: &Beat) -> (crate::models::SoundType, f32) {
+        let state = self.state.lock().unwrap();
+        state.get_sound_for_beat(beat)
+    }
+    
     pub fn set_volume(&self, volume: f32) -> Result<()> {
         let mut state = self.state.lock().unwrap();
         state.update_volume(volume)
@@ -103,20 +298,20 @@ impl Metronome {
     }
     
     /// Update multiple settings atomically
-    pub fn update_settings(&self, bpm: Option<u32>, time_signature: Option<crate::models::TimeSignature>, 
+    ///
+    /// Tempo and time signature follow the change timing: with `ChangeTiming::NextBar`
+    /// they wait for the next downbeat while playing, otherwise they apply at once.
+    pub fn update_settings(&self, bpm: Option<Tempo>, time_signature: Option<crate::models::TimeSignature>, 
                           beat_sound: Option<crate::models::SoundType>, accent_sound: Option<crate::models::SoundType>,
                           accent_enabled: Option<bool>, volume: Option<f32>) -> Result<()> {
         let mut state = self.state.lock().unwrap();
         
         if let Some(bpm) = bpm {
-            if bpm < 60 || bpm > 200 {
-                return Err(MetronomeError::InvalidBpm(bpm));
-            }
-            state.bpm = bpm;
+            state.change_bpm(bpm)?;
         }
         
         if let Some(time_sig) = time_signature {
-            state.update_time_signature(time_sig);
+            state.change_time_signature(time_sig);
         }
         
         if let Some(beat) = beat_sound {
@@ -135,6 +330,8 @@ impl Metronome {
             state.update_volume(vol)?;
         }
         
+        drop(state);
+        self.notify_scheduler();
         Ok(())
     }
     
@@ -147,6 +344,67 @@ impl Metronome {
         last_beat_time.elapsed() >= state.get_interval()
     }
     
+    /// Produce the next beat if its scheduled time has been reached
+    ///
+    /// Beats are scheduled from the start time plus the accumulated intervals, so a
+    /// late poll delays only the beat it produces and never shifts the ones after it.
+    /// The returned beat carries both its scheduled time and the time it was produced.
+    pub fn poll_beat(&self, now: Instant) -> Option<Beat> {
+        let mut state = self.state.lock().unwrap();
+        if state.is_beat_due(now) {
+            Some(state.increment_beat_at(now))
+        } else {
+            None
+        }
+    }
+    
+    /// Get the scheduled time of the next beat, if the metronome is running
+    pub fn get_next_beat_time(&self) -> Option<Instant> {
+        let state = self.state.lock().unwrap();
+        if state.is_running {
+            state.get_next_beat_time()
+        } else {
+            None
+        }
+    }
+    
+    /// Start the background timer thread and return the channel its beats arrive on
+    ///
+    /// The thread sleeps until each beat is due instead of polling the state, so
+    /// consumers only need to wait on the returned receiver. Any previously started
+    /// scheduler is shut down first.
+    pub fn start_scheduler(&self) -> Result<Receiver<Beat>> {
+        let mut scheduler = self.scheduler.lock().unwrap();
+        if let Some(mut previous) = scheduler.take() {
+            previous.shutdown();
+        }
+        
+        let (new_scheduler, receiver) = BeatScheduler::spawn(Arc::clone(&self.state))?;
+        *scheduler = Some(new_scheduler);
+        Ok(receiver)
+    }
+    
+    /// Shut down the background timer thread, if one is running
+    pub fn stop_scheduler(&self) {
+        if let Some(mut scheduler) = self.scheduler.lock().unwrap().take() {
+            scheduler.shutdown();
+        }
+    }
+    
+    /// Check whether the background timer thread is running
+    pub fn is_scheduler_active(&self) -> bool {
+        self.scheduler.lock().unwrap().as_ref().is_some_and(|scheduler| scheduler.is_active())
+    }
+    
+    /// Wake the timer thread and `run_async` after a change that may move the next deadline
+    fn notify_scheduler(&self) {
+        if let Some(scheduler) = self.scheduler.lock().unwrap().as_ref() {
+            scheduler.notify();
+        }
+        #[cfg(feature = "gui")]
+        self.changed.notify_one();
+    }
+    
     /// Get current sound type for the current beat position (thread-safe)
     pub fn get_current_sound_type(&self) -> crate::models::SoundType {
         let state = self.state.lock().unwrap();
@@ -158,14 +416,14 @@ impl Metronome {
     }
     
     pub fn start(&self) -> Result<()> {
-        let mut state = self.state.lock().unwrap();
-        state.start();
+        self.state.lock().unwrap().start();
+        self.notify_scheduler();
         Ok(())
     }
     
     pub fn stop(&self) {
-        let mut state = self.state.lock().unwrap();
-        state.stop();
+        self.state.lock().unwrap().stop();
+        self.notify_scheduler();
     }
     
     pub fn is_running(&self) -> bool {
@@ -173,7 +431,38 @@ impl Metronome {
         state.is_running
     }
     
-    pub fn get_bpm(&self) -> u32 {
+    /// Pause playback, keeping the bar and beat position and the elapsed time
+    pub fn pause(&self) {
+        self.state.lock().unwrap().pause();
+        self.notify_scheduler();
+    }
+    
+    /// Resume after a pause, continuing from the next beat
+    pub fn resume(&self) {
+        self.state.lock().unwrap().resume();
+        self.notify_scheduler();
+    }
+    
+    /// Pause a running metronome or resume a paused one, returning whether it is now paused
+    pub fn toggle_pause(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if state.is_paused() {
+            state.resume();
+        } else {
+            state.pause();
+        }
+        let paused = state.is_paused();
+        drop(state);
+        self.notify_scheduler();
+        paused
+    }
+    
+    pub fn is_paused(&self) -> bool {
+        let state = self.state.lock().unwrap();
+        state.is_paused()
+    }
+    
+    pub fn get_bpm(&self) -> Tempo {
         let state = self.state.lock().unwrap();
         state.bpm
     }
@@ -213,28 +502,50 @@ impl Metronome {
     where
         F: FnMut(crate::models::Beat, crate::models::SoundType) + Send + 'static,
     {
-        use tokio::time::{sleep, Duration as TokioDuration};
+        use std::future::Future;
+        use std::task::Poll;
+        use tokio::time::{sleep_until, Instant as TokioInstant};
         
-        let mut last_beat_time = Instant::now();
-        
-        while self.is_running() {
-            if self.should_play_beat_safe(last_beat_time) {
-                let beat = self.increment_beat();
-                let sound_type = self.get_current_sound_type();
-                
-                beat_callback(beat, sound_type);
-                last_beat_time = Instant::now();
+        loop {
+            // A pause waits for the resume or stop instead of ending the loop
+            if self.is_paused() {
+                self.changed.notified().await;
+                continue;
+            }
+            if !self.is_running() {
+                break;
             }
             
-            // Small async sleep to prevent excessive CPU usage
-            sleep(TokioDuration::from_millis(1)).await;
+            // Sleep until the next scheduled beat instead of polling the state;
+            // there is none once the session limit has been reached
+            let Some(deadline) = self.get_next_beat_time() else {
+                break;
+            };
+            
+            // Stopping or a tempo change cuts the sleep short so the deadline is re-read
+            let mut sleep = std::pin::pin!(sleep_until(TokioInstant::from_std(deadline)));
+            let mut changed = std::pin::pin!(self.changed.notified());
+            let woken = std::future::poll_fn(|cx| {
+                if changed.as_mut().poll(cx).is_ready() {
+                    return Poll::Ready(true);
+                }
+                sleep.as_mut().poll(cx).map(|()| false)
+            }).await;
+            if woken {
+                continue;
+            }
+            
+            if let Some(beat) = self.poll_beat(Instant::now()) {
+                let (sound_type, _volume) = self.get_sound_for_beat(&beat);
+                beat_callback(beat, sound_type);
+            }
         }
         
         Ok(())
     }
     
     /// Get a snapshot of the current state for display purposes
-    pub fn get_display_state(&self) -> (u32, u64, Duration, crate::models::TimeSignature, u32, bool) {
+    pub fn get_display_state(&self) -> (Tempo, u64, Duration, crate::models::TimeSignature, u32, bool) {
         let state = self.state.lock().unwrap();
         (
             state.bpm,
@@ -255,7 +566,7 @@ pub struct MetronomeController {
 }
 
 impl MetronomeController {
-    pub fn new(bpm: u32) -> Result<Self> {
+    pub fn new(bpm: impl Into<Tempo>) -> Result<Self> {
         let metronome = Metronome::with_bpm(bpm)?;
         let running = Arc::new(AtomicBool::new(false));
         
@@ -325,7 +636,7 @@ impl MetronomeController {
     }
     
     /// Update metronome settings atomically
-    pub fn update_metronome_settings(&self, bpm: Option<u32>, time_signature: Option<crate::models::TimeSignature>, 
+    pub fn update_metronome_settings(&self, bpm: Option<Tempo>, time_signature: Option<crate::models::TimeSignature>, 
                                    beat_sound: Option<crate::models::SoundType>, accent_sound: Option<crate::models::SoundType>,
                                    accent_enabled: Option<bool>, volume: Option<f32>) -> Result<()> {
         self.metronome.update_settings(bpm, time_signature, beat_sound, accent_sound, accent_enabled, volume)
@@ -346,8 +657,8 @@ mod tests {
     
     #[test]
     fn test_metronome_invalid_bpm() {
-        assert!(Metronome::with_bpm(59).is_err());
-        assert!(Metronome::with_bpm(201).is_err());
+        assert!(Metronome::with_bpm(19).is_err());
+        assert!(Metronome::with_bpm(401).is_err());
     }
     
     #[test]
@@ -371,6 +682,58 @@ mod tests {
         assert!(!metronome.is_running());
     }
     
+    #[test]
+    fn test_metronome_pause_resume() {
+        let metronome = Metronome::with_bpm(120).unwrap();
+        
+        // Nothing to pause while stopped
+        assert!(!metronome.toggle_pause());
+        
+        metronome.start().unwrap();
+        metronome.increment_beat();
+        metronome.increment_beat();
+        assert!(metronome.toggle_pause());
+        assert!(metronome.is_paused());
+        assert!(!metronome.is_running());
+        assert_eq!(metronome.get_next_beat_time(), None);
+        
+        metronome.resume();
+        assert!(metronome.is_running());
+        assert_eq!(metronome.get_beat_count(), 2);
+        assert_eq!(metronome.increment_beat().beat_in_measure, 3);
+        
+        metronome.pause();
+        metronome.stop();
+        assert!(!metronome.is_paused());
+    }
+    
+    #[test]
+    fn test_metronome_settings_at_next_bar() {
+        use crate::models::{ChangeTiming, TimeSignature};
+        
+        let metronome = Metronome::with_bpm(120).unwrap();
+        metronome.set_change_timing(ChangeTiming::NextBar);
+        metronome.start().unwrap();
+        metronome.increment_beat();
+        metronome.increment_beat();
+        
+        metronome.update_settings(Some(Tempo::from(90)), Some(TimeSignature::new(3, 4)), None, None, None, None).unwrap();
+        assert_eq!(metronome.get_pending_changes(), (Some(Tempo::from(90)), Some(TimeSignature::new(3, 4))));
+        assert_eq!(metronome.get_bpm(), 120);
+        assert_eq!(metronome.increment_beat().beat_in_measure, 3);
+        assert_eq!(metronome.increment_beat().beat_in_measure, 4);
+        
+        let beat = metronome.increment_beat();
+        assert_eq!((beat.beat_in_measure, beat.time_signature), (1, TimeSignature::new(3, 4)));
+        assert_eq!(metronome.get_bpm(), 90);
+        assert_eq!(metronome.get_pending_changes(), (None, None));
+        
+        // Immediate changes still apply from the next click
+        metronome.set_change_timing(ChangeTiming::Immediate);
+        metronome.set_bpm(100).unwrap();
+        assert_eq!(metronome.get_bpm(), 100);
+    }
+    
     #[test]
     fn test_beat_increment() {
         let metronome = Metronome::with_bpm(120).unwrap();
@@ -396,6 +759,30 @@ mod tests {
         assert_eq!(controller.get_metronome().get_bpm(), 120);
     }
     
+    #[test]
+    fn test_apply_tap_tempo() {
+        use crate::models::TapTempo;
+        
+        let metronome = Metronome::with_bpm(120).unwrap();
+        let mut tap_tempo = TapTempo::new();
+        let start = Instant::now();
+        tap_tempo.tap_at(start);
+        assert_eq!(metronome.apply_tap_tempo(&tap_tempo).unwrap(), None);
+        assert_eq!(metronome.get_bpm(), 120);
+        
+        tap_tempo.tap_at(start + Duration::from_millis(600));
+        assert_eq!(metronome.apply_tap_tempo(&tap_tempo).unwrap(), Some(Tempo::from(100.0)));
+        assert_eq!(metronome.get_bpm(), 100);
+        
+        // Taps faster than a narrowed range allows are rejected
+        metronome.set_tempo_range(crate::models::TempoRange::new(40.0, 200.0).unwrap()).unwrap();
+        tap_tempo.reset();
+        tap_tempo.tap_at(start);
+        tap_tempo.tap_at(start + Duration::from_millis(250));
+        assert!(matches!(metronome.apply_tap_tempo(&tap_tempo), Err(MetronomeError::InvalidBpm(_))));
+        assert_eq!(metronome.get_bpm(), 100);
+    }
+    
     #[test]
     fn test_time_signature_functionality() {
         use crate::models::TimeSignature;
@@ -403,14 +790,14 @@ mod tests {
         let metronome = Metronome::with_bpm(120).unwrap();
         
         // Test default time signature (4/4)
-        assert_eq!(metronome.get_time_signature(), TimeSignature::Four);
+        assert_eq!(metronome.get_time_signature(), TimeSignature::new(4, 4));
         assert_eq!(metronome.get_beats_per_measure(), 4);
         assert_eq!(metronome.get_current_beat_in_measure(), 1);
         assert!(metronome.is_accent_beat());
         
         // Test changing time signature
-        metronome.set_time_signature(TimeSignature::Three);
-        assert_eq!(metronome.get_time_signature(), TimeSignature::Three);
+        metronome.set_time_signature(TimeSignature::new(3, 4));
+        assert_eq!(metronome.get_time_signature(), TimeSignature::new(3, 4));
         assert_eq!(metronome.get_beats_per_measure(), 3);
     }
     
@@ -419,7 +806,7 @@ mod tests {
         use crate::models::TimeSignature;
         
         let metronome = Metronome::with_bpm(120).unwrap();
-        metronome.set_time_signature(TimeSignature::Three); // 3/4 time
+        metronome.set_time_signature(TimeSignature::new(3, 4)); // 3/4 time
         metronome.start().unwrap();
         
         // First beat - accent
@@ -453,7 +840,7 @@ mod tests {
         
         let metronome = Metronome::with_bpm(120).unwrap();
         metronome.set_sounds(SoundType::BuiltinClick, SoundType::BuiltinWood);
-        metronome.set_time_signature(TimeSignature::Four);
+        metronome.set_time_signature(TimeSignature::new(4, 4));
         metronome.start().unwrap();
         
         // First beat should use accent sound
@@ -492,8 +879,8 @@ mod tests {
         
         // Update multiple settings atomically
         let result = metronome.update_settings(
-            Some(140),
-            Some(TimeSignature::Three),
+            Some(Tempo::from(140)),
+            Some(TimeSignature::new(3, 4)),
             Some(SoundType::BuiltinWood),
             Some(SoundType::BuiltinBeep),
             None,
@@ -502,10 +889,10 @@ mod tests {
         
         assert!(result.is_ok());
         assert_eq!(metronome.get_bpm(), 140);
-        assert_eq!(metronome.get_time_signature(), TimeSignature::Three);
+        assert_eq!(metronome.get_time_signature(), TimeSignature::new(3, 4));
         
         // Test invalid BPM in atomic update
-        let result = metronome.update_settings(Some(300), None, None, None, None, None);
+        let result = metronome.update_settings(Some(Tempo::from(500)), None, None, None, None, None);
         assert!(result.is_err());
         // BPM should remain unchanged after failed update
         assert_eq!(metronome.get_bpm(), 140);
@@ -534,14 +921,14 @@ mod tests {
         use crate::models::TimeSignature;
         
         let metronome = Metronome::with_bpm(120).unwrap();
-        metronome.set_time_signature(TimeSignature::Three);
+        metronome.set_time_signature(TimeSignature::new(3, 4));
         metronome.start().unwrap();
         
         let (bpm, beat_count, _elapsed, time_sig, current_beat, is_running) = metronome.get_display_state();
         
         assert_eq!(bpm, 120);
         assert_eq!(beat_count, 0);
-        assert_eq!(time_sig, TimeSignature::Three);
+        assert_eq!(time_sig, TimeSignature::new(3, 4));
         assert_eq!(current_beat, 1);
         assert!(is_running);
     }
@@ -561,8 +948,8 @@ mod tests {
         
         // Test atomic settings update through controller
         let result = controller.update_metronome_settings(
-            Some(140), 
-            Some(crate::models::TimeSignature::Four), 
+            Some(Tempo::from(140)), 
+            Some(crate::models::TimeSignature::new(4, 4)), 
             None, 
             None,
             None,
@@ -598,4 +985,164 @@ mod tests {
         assert!(result.is_err());
         assert_eq!(metronome.get_volume(), 0.8); // Should remain unchanged after failed update
     }
+    
+    #[test]
+    fn test_scheduler_has_no_cumulative_drift() {
+        let metronome = Metronome::with_bpm(120).unwrap();
+        metronome.start().unwrap();
+        let start_time = metronome.get_state().start_time.unwrap();
+        
+        // One hour at 120 BPM, with every poll arriving up to 12 ms late
+        for n in 1..=7200u64 {
+            let expected = start_time + Duration::from_millis(500) * n as u32;
+            assert_eq!(metronome.get_next_beat_time(), Some(expected));
+            assert!(metronome.poll_beat(expected - Duration::from_millis(1)).is_none());
+            
+            let latency = Duration::from_millis(n % 13);
+            let beat = metronome.poll_beat(expected + latency).unwrap();
+            assert_eq!(beat.sequence_number, n);
+            assert_eq!(beat.scheduled_time, expected);
+            assert_eq!(beat.lateness(), latency);
+        }
+        
+        assert_eq!(
+            metronome.get_next_beat_time(),
+            Some(start_time + Duration::from_secs(3600) + Duration::from_millis(500))
+        );
+    }
+    
+    #[test]
+    fn test_scheduler_tempo_change_continues_from_last_beat() {
+        let metronome = Metronome::with_bpm(120).unwrap();
+        metronome.start().unwrap();
+        let start_time = metronome.get_state().start_time.unwrap();
+        
+        for n in 1..=4u32 {
+            let due = start_time + Duration::from_millis(500) * n;
+            assert!(metronome.poll_beat(due + Duration::from_millis(3)).is_some());
+        }
+        
+        // The new tempo applies from the last scheduled beat, not from the late poll
+        metronome.set_bpm(60).unwrap();
+        let last_beat_time = start_time + Duration::from_secs(2);
+        assert_eq!(metronome.get_next_beat_time(), Some(last_beat_time + Duration::from_secs(1)));
+        
+        let beat = metronome.poll_beat(last_beat_time + Duration::from_secs(1)).unwrap();
+        assert_eq!(beat.sequence_number, 5);
+        assert_eq!(beat.lateness(), Duration::ZERO);
+        assert_eq!(metronome.get_next_beat_time(), Some(last_beat_time + Duration::from_secs(2)));
+    }
+    
+    #[test]
+    fn test_scheduler_thread_delivers_beats() {
+        let metronome = Metronome::with_bpm(200).unwrap();
+        let beats = metronome.start_scheduler().unwrap();
+        assert!(metronome.is_scheduler_active());
+        metronome.start().unwrap();
+        
+        for n in 1..=3 {
+            let beat = beats.recv_timeout(Duration::from_secs(2)).unwrap();
+            assert_eq!(beat.sequence_number, n);
+            assert!(beat.lateness() < Duration::from_millis(50));
+        }
+        
+        metronome.stop_scheduler();
+        assert!(!metronome.is_scheduler_active());
+    }
+    
+    #[test]
+    fn test_scheduler_thread_wakes_on_tempo_change() {
+        let metronome = Metronome::with_bpm(60).unwrap();
+        let beats = metronome.start_scheduler().unwrap();
+        metronome.start().unwrap();
+        
+        // The thread is asleep waiting for a 1 s deadline; the faster tempo must wake it
+        std::thread::sleep(Duration::from_millis(20));
+        metronome.set_bpm(200).unwrap();
+        
+        let beat = beats.recv_timeout(Duration::from_millis(700)).unwrap();
+        assert_eq!(beat.sequence_number, 1);
+        assert_eq!(beat.bpm, 200);
+    }
+    
+    #[test]
+    #[cfg(feature = "gui")]
+    fn test_run_async_stops_without_waiting_out_the_interval() {
+        let metronome = Arc::new(Metronome::with_bpm(20).unwrap());
+        metronome.start().unwrap();
+        
+        // At 20 BPM the runner sleeps for 3 s between beats; stopping must wake it
+        let stopper = Arc::clone(&metronome);
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(100));
+            stopper.stop();
+        });
+        
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let started = Instant::now();
+        runtime.block_on(metronome.run_async(|_, _| {})).unwrap();
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+    
+    #[test]
+    #[cfg(feature = "gui")]
+    fn test_run_async_wakes_on_tempo_change() {
+        let metronome = Arc::new(Metronome::with_bpm(20).unwrap());
+        metronome.start().unwrap();
+        
+        let changer = Arc::clone(&metronome);
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            changer.set_bpm(200).unwrap();
+        });
+        
+        // Stop after the first beat at the new tempo
+        let stopper = Arc::clone(&metronome);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let started = Instant::now();
+        runtime.block_on(metronome.run_async(move |beat, _| {
+            if beat.bpm == 200 {
+                stopper.stop();
+            }
+            let _ = sender.send(beat.bpm);
+        })).unwrap();
+        
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert_eq!(receiver.try_iter().last(), Some(Tempo::from(200)));
+    }
+    
+    #[test]
+    #[cfg(feature = "gui")]
+    fn test_run_async_survives_pause() {
+        let metronome = Arc::new(Metronome::with_bpm(300).unwrap());
+        metronome.start().unwrap();
+        
+        let pauser = Arc::clone(&metronome);
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            pauser.pause();
+            std::thread::sleep(Duration::from_millis(300));
+            pauser.resume();
+        });
+        
+        // Stop on the third beat, which can only come after the resume
+        let stopper = Arc::clone(&metronome);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let started = Instant::now();
+        let mut beats = 0;
+        runtime.block_on(metronome.run_async(move |_, _| {
+            beats += 1;
+            if beats == 3 {
+                stopper.stop();
+            }
+            let _ = sender.send(Instant::now());
+        })).unwrap();
+        
+        let beat_times: Vec<_> = receiver.try_iter().collect();
+        assert_eq!(beat_times.len(), 3);
+        assert!(beat_times[2].duration_since(started) >= Duration::from_millis(350));
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,126 @@
+// Background beat scheduler that sleeps until each beat deadline
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::error::{MetronomeError, Result};
+use crate::models::{Beat, MetronomeState};
+
+/// How long before a deadline the thread stops sleeping and starts the precise wait
+const PRECISE_WAIT_WINDOW: Duration = Duration::from_millis(2);
+
+/// How long the thread sleeps when the metronome is stopped
+const IDLE_WAIT: Duration = Duration::from_millis(100);
+
+/// Timer thread that produces beats from a shared metronome state
+///
+/// The thread sleeps until shortly before the next scheduled beat, finishes with a
+/// short yielding wait and then sends the beat to the consumer. Setting changes wake
+/// the thread through [`BeatScheduler::notify`] so it can recompute its deadline.
+#[derive(Debug)]
+pub struct BeatScheduler {
+    handle: Option<JoinHandle<()>>,
+    shutdown: Arc<AtomicBool>,
+}
+
+impl BeatScheduler {
+    /// Spawn the timer thread and return it together with the receiving end of its beats
+    pub fn spawn(state: Arc<Mutex<MetronomeState>>) -> Result<(Self, Receiver<Beat>)> {
+        let (sender, receiver) = mpsc::channel();
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let thread_shutdown = Arc::clone(&shutdown);
+
+        let handle = thread::Builder::new()
+            .name("metronome-scheduler".to_string())
+            .spawn(move || run_scheduler(state, sender, thread_shutdown))
+            .map_err(|e| MetronomeError::SystemError(format!("Failed to spawn scheduler thread: {}", e)))?;
+
+        Ok((
+            Self {
+                handle: Some(handle),
+                shutdown,
+            },
+            receiver,
+        ))
+    }
+
+    /// Wake the timer thread so it re-reads the next deadline
+    pub fn notify(&self) {
+        if let Some(handle) = &self.handle {
+            handle.thread().unpark();
+        }
+    }
+
+    /// Check whether the timer thread is still running
+    pub fn is_active(&self) -> bool {
+        self.handle.as_ref().is_some_and(|handle| !handle.is_finished())
+    }
+
+    /// Stop the timer thread and wait for it to exit
+    pub fn shutdown(&mut self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.handle.take() {
+            handle.thread().unpark();
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for BeatScheduler {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+fn run_scheduler(state: Arc<Mutex<MetronomeState>>, sender: Sender<Beat>, shutdown: Arc<AtomicBool>) {
+    while !shutdown.load(Ordering::SeqCst) {
+        let deadline = {
+            let state = state.lock().unwrap();
+            if state.is_running {
+                state.get_next_beat_time()
+            } else {
+                None
+            }
+        };
+
+        let deadline = match deadline {
+            Some(deadline) => deadline,
+            None => {
+                thread::park_timeout(IDLE_WAIT);
+                continue;
+            }
+        };
+
+        // Coarse sleep, then re-read the deadline in case the settings changed meanwhile
+        let now = Instant::now();
+        if deadline > now + PRECISE_WAIT_WINDOW {
+            thread::park_timeout(deadline - now - PRECISE_WAIT_WINDOW);
+            continue;
+        }
+
+        // Precise wait for the last stretch without holding the state lock
+        while Instant::now() < deadline {
+            thread::yield_now();
+        }
+
+        let beat = {
+            let mut state = state.lock().unwrap();
+            let now = Instant::now();
+            if state.is_beat_due(now) {
+                Some(state.increment_beat_at(now))
+            } else {
+                None
+            }
+        };
+
+        if let Some(beat) = beat {
+            if sender.send(beat).is_err() {
+                // The consumer has gone away
+                break;
+            }
+        }
+    }
+}
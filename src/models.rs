@@ -1,3 +1,4 @@
+use std::collections::VecDeque;
 use std::time::{Duration, Instant};
 use std::path::PathBuf;
 use crate::error::{MetronomeError, Result};
@@ -5,1043 +6,5247 @@ use crate::error::{MetronomeError, Result};
 #[cfg(feature = "gui")]
 use serde::{Deserialize, Serialize};
 
-/// Time signature enumeration supporting common time signatures
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Time signature made up of a numerator (notes per measure) and a denominator (note value)
+///
+/// The notes of a measure are split into groups whose first notes are accented. Without an
+/// explicit grouping such as 2+2+3 a default one is derived from the meter. The beat
+/// that is counted and that the tempo refers to is one note of the denominator's value,
+/// unless another beat unit is set, such as a dotted quarter in 6/8.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "gui", derive(Serialize, Deserialize))]
-pub enum TimeSignature {
-    // no time signature
-    One,
-    /// 2/4 time signature
-    Two,
-    /// 3/4 time signature  
-    Three,
-    /// 4/4 time signature
-    Four,
-    /// 5/4 time signature
-    Five,
-    /// 6/8 time signature
-    Six,
-    /// 7/8 time signature
-    Seven,
-    /// 8/8 time signature
-    Eight,
+#[cfg_attr(feature = "gui", serde(try_from = "String", into = "String"))]
+pub struct TimeSignature {
+    numerator: u32,
+    denominator: u32,
+    /// Bit `i` is set when note `i + 1` starts a group; zero means the default grouping
+    group_starts: u32,
+    /// Note value counted as one beat; `None` counts notes of the denominator's value
+    beat_unit: Option<BeatUnit>,
 }
 
 impl TimeSignature {
-    /// Get the number of beats per measure for this time signature
-    pub fn beats_per_measure(&self) -> u32 {
-        match self {
-            TimeSignature::One => 1,
-            TimeSignature::Two => 2,
-            TimeSignature::Three => 3,
-            TimeSignature::Four => 4,
-            TimeSignature::Five => 5,
-            TimeSignature::Six => 6,
-            TimeSignature::Seven => 7,
-            TimeSignature::Eight => 8,
+    /// Largest supported number of beats per measure
+    pub const MAX_NUMERATOR: u32 = 32;
+    
+    /// Note values that can be used as the denominator
+    pub const DENOMINATORS: [u32; 6] = [1, 2, 4, 8, 16, 32];
+    
+    /// Create a time signature, panicking on values outside the supported range
+    ///
+    /// Use [`TimeSignature::try_new`] or `str::parse` for user-provided values.
+    pub const fn new(numerator: u32, denominator: u32) -> Self {
+        assert!(numerator >= 1 && numerator <= Self::MAX_NUMERATOR, "time signature numerator out of range");
+        assert!(denominator.is_power_of_two() && denominator <= 32, "time signature denominator must be a power of two up to 32");
+        Self { numerator, denominator, group_starts: 0, beat_unit: None }
+    }
+    
+    /// Create a time signature, validating both parts
+    pub fn try_new(numerator: u32, denominator: u32) -> Result<Self> {
+        if !(1..=Self::MAX_NUMERATOR).contains(&numerator) {
+            return Err(MetronomeError::InvalidTimeSignature(format!(
+                "{}/{}: numerator must be between 1 and {}", numerator, denominator, Self::MAX_NUMERATOR
+            )));
+        }
+        if !Self::DENOMINATORS.contains(&denominator) {
+            return Err(MetronomeError::InvalidTimeSignature(format!(
+                "{}/{}: denominator must be one of 1, 2, 4, 8, 16, 32", numerator, denominator
+            )));
         }
+        Ok(Self { numerator, denominator, group_starts: 0, beat_unit: None })
     }
     
-    /// Get a human-readable string representation
-    pub fn as_str(&self) -> &'static str {
-        match self {
-            TimeSignature::One => "None",
-            TimeSignature::Two => "2/4",
-            TimeSignature::Three => "3/4",
-            TimeSignature::Four => "4/4",
-            TimeSignature::Five => "5/8",
-            TimeSignature::Six => "6/8",
-            TimeSignature::Seven => "7/8",
-            TimeSignature::Eight => "8/8",
+    /// Split the measure into the given beat groups, e.g. `&[2, 2, 3]` for 7/8
+    ///
+    /// The groups must add up to the numerator. A single group clears the grouping.
+    pub fn with_grouping(mut self, groups: &[u32]) -> Result<Self> {
+        let total: u32 = groups.iter().sum();
+        if groups.contains(&0) || total != self.numerator {
+            let grouping: Vec<String> = groups.iter().map(|g| g.to_string()).collect();
+            return Err(MetronomeError::InvalidTimeSignature(format!(
+                "{}/{}: grouping {} must add up to {}", self.numerator, self.denominator, grouping.join("+"), self.numerator
+            )));
+        }
+        
+        self.group_starts = 0;
+        if groups.len() > 1 {
+            let mut start = 0;
+            for &group in groups {
+                self.group_starts |= 1 << start;
+                start += group;
+            }
         }
+        Ok(self)
     }
     
-    /// Get all available time signatures
-    pub fn all() -> &'static [TimeSignature] {
-        &[
-            TimeSignature::One,
-            TimeSignature::Two,
-            TimeSignature::Three,
-            TimeSignature::Four,
-            TimeSignature::Five,
-            TimeSignature::Six,
-            TimeSignature::Seven,
-            TimeSignature::Eight,
-        ]
+    /// Remove any explicit grouping, returning to the default one
+    pub fn without_grouping(mut self) -> Self {
+        self.group_starts = 0;
+        self
     }
-}
-
-impl Default for TimeSignature {
-    fn default() -> Self {
-        TimeSignature::Four
+    
+    /// Count the measure in the given beat unit, e.g. two dotted quarters in 6/8
+    ///
+    /// The measure must hold a whole number of beats. The denominator's own note value
+    /// is the default and clears the beat unit.
+    pub fn with_beat_unit(mut self, beat_unit: BeatUnit) -> Result<Self> {
+        let measure_length = self.numerator * self.note_length();
+        if !measure_length.is_multiple_of(beat_unit.length())
+            || measure_length / beat_unit.length() > Self::MAX_NUMERATOR
+        {
+            return Err(MetronomeError::InvalidTimeSignature(format!(
+                "{}: a measure does not hold a whole number of {} beats", self, beat_unit
+            )));
+        }
+        self.beat_unit = (beat_unit.length() != self.note_length()).then_some(beat_unit);
+        Ok(self)
     }
-}
-
-/// Sound type enumeration for different metronome sounds
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
-#[cfg_attr(feature = "gui", derive(Serialize, Deserialize))]
-pub enum SoundType {
-    /// Built-in click sound
-    BuiltinClick,
-    /// Built-in wood block sound
-    BuiltinWood,
-    /// Built-in beep sound
-    BuiltinBeep,
-    /// Custom sound from file
-    Custom(PathBuf),
-}
-
-impl SoundType {
-    /// Get a human-readable string representation
-    pub fn as_str(&self) -> String {
-        match self {
-            SoundType::BuiltinClick => "Click".to_string(),
-            SoundType::BuiltinWood => "Wood".to_string(),
-            SoundType::BuiltinBeep => "Beep".to_string(),
-            SoundType::Custom(path) => {
-                format!("Custom: {}", path.file_name()
-                    .and_then(|n| n.to_str())
-                    .unwrap_or("Unknown"))
-            }
+    
+    /// Count one beat per note of the denominator's value again
+    pub fn without_beat_unit(mut self) -> Self {
+        self.beat_unit = None;
+        self
+    }
+    
+    /// Check whether a beat unit other than the denominator's note value has been set
+    pub fn has_custom_beat_unit(&self) -> bool {
+        self.beat_unit.is_some()
+    }
+    
+    /// Get the note value counted as one beat, if it is one of the selectable units
+    ///
+    /// Without a custom beat unit this is the denominator's note value, so 2/2 counts
+    /// half notes; sixteenths and other note values have no unit to show.
+    pub fn beat_unit(&self) -> Option<BeatUnit> {
+        self.beat_unit.or_else(|| BeatUnit::from_denominator(self.denominator))
+    }
+    
+    /// Format a tempo with the note value it counts, e.g. "60 (dotted quarter)"
+    pub fn tempo_with_unit(&self, bpm: impl Into<Tempo>) -> String {
+        match self.beat_unit() {
+            Some(beat_unit) => format!("{} ({})", bpm.into(), beat_unit),
+            None => bpm.into().to_string(),
         }
     }
     
-    /// Get all built-in sound types
-    pub fn builtin_sounds() -> &'static [SoundType] {
-        &[
-            SoundType::BuiltinClick,
-            SoundType::BuiltinWood,
-            SoundType::BuiltinBeep,
-        ]
+    /// Get the subdivision formed by the notes within each beat
+    ///
+    /// A beat unit longer than the denominator's note value is filled with its notes, so
+    /// 6/8 counted in dotted quarters has eighth-note triplets.
+    pub fn note_subdivision(&self) -> Subdivision {
+        let beat_length = self.beat_length();
+        if beat_length <= self.note_length() || !beat_length.is_multiple_of(self.note_length()) {
+            return Subdivision::None;
+        }
+        Subdivision::from_pulses(beat_length / self.note_length()).unwrap_or_default()
     }
     
-    /// Check if this is a built-in sound
-    pub fn is_builtin(&self) -> bool {
-        matches!(self, SoundType::BuiltinClick | SoundType::BuiltinWood | SoundType::BuiltinBeep)
+    /// Length of one note of the denominator's value in thirty-second notes
+    fn note_length(&self) -> u32 {
+        32 / self.denominator
     }
-}
-
-impl Default for SoundType {
-    fn default() -> Self {
-        SoundType::BuiltinClick
+    
+    /// Length of one beat in thirty-second notes
+    fn beat_length(&self) -> u32 {
+        self.beat_unit.map_or(self.note_length(), |beat_unit| beat_unit.length())
     }
-}
-
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "gui", derive(Serialize, Deserialize))]
-pub struct MetronomeConfig {
-    pub bpm: u32,
-    pub time_signature: TimeSignature,
-    pub beat_sound: SoundType,
-    pub accent_sound: SoundType,
-    pub sound_enabled: bool,
-    pub visual_enabled: bool,
-    pub accent_enabled: bool,
-    pub volume: f32,
-}
-
-impl MetronomeConfig {
-    pub fn new(bpm: u32) -> Self {
-        Self {
-            bpm,
-            time_signature: TimeSignature::default(),
-            beat_sound: SoundType::default(),
-            accent_sound: SoundType::BuiltinWood, // Different sound for accent
-            sound_enabled: true,
-            visual_enabled: true,
-            accent_enabled: true, // Accents enabled by default
-            volume: 0.7, // Default volume at 70%
+    
+    /// Get the note (1-based) a beat starts on, if it starts on one
+    fn beat_note(&self, beat_in_measure: u32) -> Option<u32> {
+        let start = (beat_in_measure - 1) * self.beat_length();
+        start.is_multiple_of(self.note_length()).then(|| start / self.note_length() + 1)
+    }
+    
+    /// Check whether an explicit grouping has been set
+    pub fn has_custom_grouping(&self) -> bool {
+        self.group_starts != 0
+    }
+    
+    /// Get the beat groups of a measure, e.g. `[3, 3]` for 6/8
+    pub fn groups(&self) -> Vec<u32> {
+        let starts = self.effective_group_starts();
+        let mut groups = Vec::new();
+        let mut length = 0;
+        for beat in 0..self.numerator {
+            if beat > 0 && starts & (1 << beat) != 0 {
+                groups.push(length);
+                length = 0;
+            }
+            length += 1;
         }
+        groups.push(length);
+        groups
     }
     
-    pub fn default() -> Self {
-        Self::new(120)
+    /// Check whether a beat (1-based) starts a group
+    pub fn is_group_start(&self, beat_in_measure: u32) -> bool {
+        if !(1..=self.beats_per_measure()).contains(&beat_in_measure) {
+            return false;
+        }
+        self.beat_note(beat_in_measure)
+            .is_some_and(|note| self.effective_group_starts() & (1 << (note - 1)) != 0)
     }
     
-    pub fn validate(&self) -> Result<()> {
-        if self.bpm < 60 || self.bpm > 200 {
-            return Err(MetronomeError::InvalidBpm(self.bpm));
+    /// Get the group-start bitmask, deriving the default grouping when none is set
+    ///
+    /// Compound meters fall into groups of three, even simple meters of four or more beats
+    /// into two halves, and everything else into a single group.
+    fn effective_group_starts(&self) -> u32 {
+        if self.group_starts != 0 {
+            return self.group_starts;
+        }
+        
+        if self.is_compound() {
+            (0..self.numerator).step_by(3).fold(0, |mask, beat| mask | (1 << beat))
+        } else if self.numerator >= 4 && self.numerator.is_multiple_of(2) {
+            1 | (1 << (self.numerator / 2))
+        } else {
+            1
         }
-        Ok(())
     }
     
-    pub fn with_sound(mut self, enabled: bool) -> Self {
-        self.sound_enabled = enabled;
-        self
+    /// Get the number of notes in a measure (the top number)
+    pub fn numerator(&self) -> u32 {
+        self.numerator
     }
     
-    pub fn with_visual(mut self, enabled: bool) -> Self {
-        self.visual_enabled = enabled;
-        self
+    /// Get the note value of the notes counted by the numerator (the bottom number)
+    pub fn denominator(&self) -> u32 {
+        self.denominator
     }
     
-    pub fn with_time_signature(mut self, time_signature: TimeSignature) -> Self {
-        self.time_signature = time_signature;
-        self
+    /// Get the number of beats per measure, counted in the beat unit
+    pub fn beats_per_measure(&self) -> u32 {
+        self.numerator * self.note_length() / self.beat_length()
     }
     
-    pub fn with_sounds(mut self, beat_sound: SoundType, accent_sound: SoundType) -> Self {
-        self.beat_sound = beat_sound;
-        self.accent_sound = accent_sound;
-        self
+    /// Check whether this is a compound meter (6/8, 9/8, 12/8, ...) felt in groups of three
+    pub fn is_compound(&self) -> bool {
+        self.denominator >= 8 && self.numerator > 3 && self.numerator.is_multiple_of(3)
     }
     
-    pub fn with_accent_enabled(mut self, enabled: bool) -> Self {
-        self.accent_enabled = enabled;
-        self
+    /// Get the accent strength of a beat (1-based) from the grouping
+    ///
+    /// The downbeat is strong and the first beat of every other group is medium.
+    /// Single-beat measures carry no accent at all.
+    pub fn accent_strength(&self, beat_in_measure: u32) -> f32 {
+        if self.beats_per_measure() == 1 {
+            return 0.0;
+        }
+        if beat_in_measure == 1 {
+            1.0
+        } else if self.is_group_start(beat_in_measure) {
+            0.5
+        } else {
+            0.0
+        }
     }
     
-    pub fn with_volume(mut self, volume: f32) -> Self {
-        self.volume = volume.clamp(0.0, 1.0);
-        self
+    /// Get a short description of the accent pattern, e.g. "Strong-weak-medium-weak"
+    pub fn describe_pattern(&self) -> String {
+        let parts: Vec<&str> = (1..=self.beats_per_measure())
+            .map(|beat| match self.accent_strength(beat) {
+                s if s >= 1.0 => "strong",
+                s if s > 0.0 => "medium",
+                _ => "weak",
+            })
+            .collect();
+        let description = parts.join("-");
+        let mut chars = description.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => description,
+        }
     }
     
-    #[cfg(feature = "gui")]
-    pub fn save_to_file(&self, path: &std::path::Path) -> Result<()> {
-        use std::fs;
-        use crate::error::ConfigError;
-        
-        let json = serde_json::to_string_pretty(self)
-            .map_err(|e| ConfigError::ParseError(format!("Failed to serialize config: {}", e)))?;
-        fs::write(path, json)
-            .map_err(|e| ConfigError::WriteError(format!("Failed to write config file: {}", e)))?;
-        Ok(())
+    /// Get the common time signatures offered as presets
+    pub fn all() -> &'static [TimeSignature] {
+        &TIME_SIGNATURE_PRESETS
     }
     
-    #[cfg(feature = "gui")]
-    pub fn load_from_file(path: &std::path::Path) -> Result<Self> {
-        use std::fs;
-        use crate::error::ConfigError;
-        
-        let json = fs::read_to_string(path)
-            .map_err(|e| ConfigError::ReadError(format!("Failed to read config file: {}", e)))?;
-        let config = serde_json::from_str(&json)
-            .map_err(|e| ConfigError::ParseError(format!("Failed to parse config: {}", e)))?;
-        Ok(config)
+    /// Map the names stored by configs from before time signatures had a numerator and denominator
+    fn from_legacy_name(name: &str) -> Option<Self> {
+        let (numerator, denominator) = match name {
+            "One" => (1, 4),
+            "Two" => (2, 4),
+            "Three" => (3, 4),
+            "Four" => (4, 4),
+            "Five" => (5, 8),
+            "Six" => (6, 8),
+            "Seven" => (7, 8),
+            "Eight" => (8, 8),
+            _ => return None,
+        };
+        Some(Self::new(numerator, denominator))
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct Beat {
-    pub timestamp: Instant,
-    pub sequence_number: u64,
-    pub beat_in_measure: u32,
-    pub is_accent: bool,
-    pub bpm: u32,
-    pub time_signature: TimeSignature,
-    pub accent_enabled: bool,
+/// Time signatures listed in the GUI and help output
+const TIME_SIGNATURE_PRESETS: [TimeSignature; 12] = [
+    TimeSignature::new(2, 4),
+    TimeSignature::new(3, 4),
+    TimeSignature::new(4, 4),
+    TimeSignature::new(5, 4),
+    TimeSignature::new(6, 4),
+    TimeSignature::new(5, 8),
+    TimeSignature::new(6, 8),
+    TimeSignature::new(7, 8),
+    TimeSignature::new(9, 8),
+    TimeSignature::new(11, 8),
+    TimeSignature::new(12, 8),
+    TimeSignature::new(15, 16),
+];
+
+impl Default for TimeSignature {
+    fn default() -> Self {
+        TimeSignature::new(4, 4)
+    }
 }
 
-impl Beat {
-    pub fn new(sequence_number: u64, time_signature: TimeSignature, bpm: u32) -> Self {
-        Self::new_with_accent_setting(sequence_number, time_signature, bpm, true)
+impl std::fmt::Display for TimeSignature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.has_custom_grouping() {
+            let groups: Vec<String> = self.groups().iter().map(|g| g.to_string()).collect();
+            write!(f, "{}/{}", groups.join("+"), self.denominator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
     }
+}
+
+impl std::str::FromStr for TimeSignature {
+    type Err = MetronomeError;
     
-    pub fn new_with_accent_setting(sequence_number: u64, time_signature: TimeSignature, bpm: u32, accent_enabled: bool) -> Self {
-        let beats_per_measure = time_signature.beats_per_measure();
-        // Handle the case where sequence_number is 0 by treating it as beat 1
-        let effective_sequence = if sequence_number == 0 { 1 } else { sequence_number };
-        let beat_in_measure = ((effective_sequence - 1) % beats_per_measure as u64) as u32 + 1;
-        
-        // Create a temporary state to calculate accent
-        let temp_state = MetronomeState {
-            bpm,
-            time_signature,
-            beat_sound: SoundType::default(),
-            accent_sound: SoundType::default(),
-            is_running: true,
-            start_time: Some(Instant::now()),
-            beat_count: sequence_number,
-            current_beat_in_measure: beat_in_measure,
-            accent_enabled,
-            volume: 0.7,
-        };
+    /// Parse forms such as "7/8", "2+2+3/8" or "6/8 (dotted quarter)" with a beat unit
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if let Some(time_signature) = Self::from_legacy_name(s) {
+            return Ok(time_signature);
+        }
+        if let Some((meter, beat_unit)) = s.strip_suffix(')').and_then(|s| s.split_once('(')) {
+            let beat_unit = beat_unit.parse::<BeatUnit>().map_err(MetronomeError::InvalidTimeSignature)?;
+            return meter.parse::<Self>()?.with_beat_unit(beat_unit);
+        }
         
-        let is_accent = temp_state.is_accent_beat();
+        let invalid = || MetronomeError::InvalidTimeSignature(format!("'{}': expected the form 7/8 or 2+2+3/8", s));
+        let (numerator, denominator) = s.split_once('/').ok_or_else(invalid)?;
+        let groups = numerator
+            .split('+')
+            .map(|group| group.trim().parse::<u32>().map_err(|_| invalid()))
+            .collect::<Result<Vec<u32>>>()?;
+        let denominator = denominator.trim().parse().map_err(|_| invalid())?;
         
-        Self {
-            timestamp: Instant::now(),
-            sequence_number: effective_sequence,
-            beat_in_measure,
-            is_accent,
-            bpm,
-            time_signature,
-            accent_enabled,
+        let numerator = groups
+            .iter()
+            .try_fold(0u32, |total, &group| total.checked_add(group))
+            .ok_or_else(invalid)?;
+        let time_signature = Self::try_new(numerator, denominator)?;
+        if groups.len() > 1 {
+            time_signature.with_grouping(&groups)
+        } else {
+            Ok(time_signature)
         }
     }
+}
+
+impl TryFrom<String> for TimeSignature {
+    type Error = MetronomeError;
     
-    pub fn is_first_beat(&self) -> bool {
-        self.beat_in_measure == 1
+    fn try_from(value: String) -> Result<Self> {
+        value.parse()
     }
-    
-    pub fn get_accent_strength(&self) -> f32 {
-        if !self.accent_enabled {
-            return 0.0; // No accents when disabled
+}
+
+/// The stored form keeps a custom beat unit, which `Display` leaves to the tempo
+impl From<TimeSignature> for String {
+    fn from(time_signature: TimeSignature) -> Self {
+        match time_signature.beat_unit {
+            Some(beat_unit) => format!("{} ({})", time_signature, beat_unit),
+            None => time_signature.to_string(),
         }
-        
-        match self.time_signature {
-            TimeSignature::One => {
-                match self.beat_in_measure {
-                    _ => 0.0, // Weak beat
-                }
-            }
-            TimeSignature::Two => {
-                match self.beat_in_measure {
-                    1 => 1.0, // Strong beat
-                    _ => 0.0, // Weak beat
-                }
-            }
-            TimeSignature::Three => {
-                match self.beat_in_measure {
-                    1 => 1.0, // Strong beat
-                    _ => 0.0, // Weak beats
-                }
-            }
-            TimeSignature::Four => {
-                match self.beat_in_measure {
-                    1 => 1.0,   // Strong beat
-                    3 => 0.5,   // Medium beat
-                    _ => 0.0,   // Weak beats
-                }
-            }
-            TimeSignature::Five => {
-                match self.beat_in_measure {
-                    1 => 1.0, // Strong beat
-                    _ => 0.0, // Weak beats
-                }
-            }
-            TimeSignature::Six => {
-                match self.beat_in_measure {
-                    1 => 1.0,   // Strong beat
-                    4 => 0.5,   // Medium beat
-                    _ => 0.0,   // Weak beats
-                }
-            }
-            TimeSignature::Seven => {
-                match self.beat_in_measure {
-                    1 => 1.0, // Strong beat
-                    _ => 0.0, // Weak beats
-                }
-            }
-            TimeSignature::Eight=> {
-                match self.beat_in_measure {
-                    1 => 1.0,   // Strong beat
-                    5 => 0.5,   // Medium beat
-                    _ => 0.0,   // Weak beats
-                }
-            }
+    }
+}
+
+/// Note value counted as one beat, which the tempo refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "gui", derive(Serialize, Deserialize))]
+pub enum BeatUnit {
+    Half,
+    Quarter,
+    DottedQuarter,
+    Eighth,
+}
+
+impl BeatUnit {
+    /// Get the length in thirty-second notes
+    fn length(&self) -> u32 {
+        match self {
+            BeatUnit::Half => 16,
+            BeatUnit::Quarter => 8,
+            BeatUnit::DottedQuarter => 12,
+            BeatUnit::Eighth => 4,
         }
     }
     
-    pub fn is_strong_beat(&self) -> bool {
-        self.get_accent_strength() >= 1.0
+    /// Get the beat unit matching a time signature denominator, if there is one
+    pub fn from_denominator(denominator: u32) -> Option<Self> {
+        match denominator {
+            2 => Some(BeatUnit::Half),
+            4 => Some(BeatUnit::Quarter),
+            8 => Some(BeatUnit::Eighth),
+            _ => None,
+        }
     }
     
-    pub fn is_medium_beat(&self) -> bool {
-        let strength = self.get_accent_strength();
-        strength > 0.0 && strength < 1.0
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BeatUnit::Half => "Half",
+            BeatUnit::Quarter => "Quarter",
+            BeatUnit::DottedQuarter => "Dotted quarter",
+            BeatUnit::Eighth => "Eighth",
+        }
     }
     
-    pub fn is_weak_beat(&self) -> bool {
-        self.get_accent_strength() == 0.0
+    pub fn all() -> &'static [BeatUnit] {
+        &[BeatUnit::Half, BeatUnit::Quarter, BeatUnit::DottedQuarter, BeatUnit::Eighth]
     }
 }
 
-/// Metronome state structure for thread-safe access
-#[derive(Debug, Clone)]
-pub struct MetronomeState {
-    pub bpm: u32,
-    pub time_signature: TimeSignature,
-    pub beat_sound: SoundType,
-    pub accent_sound: SoundType,
-    pub is_running: bool,
-    pub start_time: Option<Instant>,
-    pub beat_count: u64,
-    pub current_beat_in_measure: u32,
-    pub accent_enabled: bool,
-    pub volume: f32,
+impl std::fmt::Display for BeatUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str().to_lowercase())
+    }
 }
 
-impl MetronomeState {
-    pub fn new(config: &MetronomeConfig) -> Self {
-        Self {
-            bpm: config.bpm,
-            time_signature: config.time_signature,
-            beat_sound: config.beat_sound.clone(),
-            accent_sound: config.accent_sound.clone(),
-            is_running: false,
-            start_time: None,
-            beat_count: 0,
-            current_beat_in_measure: 1,
-            accent_enabled: config.accent_enabled,
-            volume: config.volume,
-        }
-    }
+impl std::str::FromStr for BeatUnit {
+    type Err = String;
     
-    pub fn get_elapsed_time(&self) -> Duration {
-        match self.start_time {
-            Some(start) => start.elapsed(),
-            None => Duration::from_secs(0),
-        }
+    /// Accept names such as "quarter", "dotted-quarter" or "Dotted quarter"
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let name = s.trim().replace(['-', '_'], " ");
+        BeatUnit::all()
+            .iter()
+            .copied()
+            .find(|unit| unit.as_str().eq_ignore_ascii_case(&name))
+            .ok_or_else(|| format!("unknown beat unit '{}', expected half, quarter, dotted-quarter or eighth", s.trim()))
+    }
+}
+
+/// Slowest tempo accepted by default
+pub const MIN_BPM: f64 = 20.0;
+
+/// Fastest tempo accepted by default
+pub const MAX_BPM: f64 = 400.0;
+
+/// Fastest tempo a configured tempo range can allow
+pub const HIGHEST_BPM: f64 = 1000.0;
+
+/// Tempo in beats per minute, which may be fractional such as 72.5
+///
+/// Whole numbers convert with `From<u32>`; use [`Tempo::new`] or the active
+/// [`TempoRange`] to check that a tempo is playable.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "gui", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "gui", serde(transparent))]
+pub struct Tempo(f64);
+
+impl Tempo {
+    /// Create a tempo within the default range of 20 to 400 BPM
+    pub fn new(bpm: f64) -> Result<Self> {
+        TempoRange::default().validate(Self(bpm))
     }
     
-    pub fn get_interval(&self) -> Duration {
-        self.calculate_beat_interval()
+    /// Read a tempo such as "72.5" without checking it against a range
+    fn parse(s: &str) -> Result<Self> {
+        s.trim().parse::<f64>()
+            .map(Self)
+            .map_err(|_| MetronomeError::InvalidTempo(format!("'{}' is not a number", s.trim())))
     }
     
-    /// Calculate beat interval based on time signature and BPM
-    pub fn calculate_beat_interval(&self) -> Duration {
-        let base_seconds_per_beat = 60.0 / self.bpm as f64;
-        
-        // Adjust timing based on time signature
-        match self.time_signature {
-            TimeSignature::One => {
-                // None
-                Duration::from_secs_f64(base_seconds_per_beat)
-            }
-            TimeSignature::Two => {
-                // 2/4 time - quarter note gets the beat
-                Duration::from_secs_f64(base_seconds_per_beat)
-            }
-            TimeSignature::Three => {
-                // 3/4 time - quarter note gets the beat
-                Duration::from_secs_f64(base_seconds_per_beat)
-            }
-            TimeSignature::Four => {
-                // 4/4 time - quarter note gets the beat
-                Duration::from_secs_f64(base_seconds_per_beat)
-            }
-            TimeSignature::Five => {
-                // 6/8 time - eighth note gets the beat, but we count in compound time
-                // BPM refers to dotted quarter notes (3 eighth notes)
-                // So each eighth note is 1/3 of the dotted quarter
-                Duration::from_secs_f64(base_seconds_per_beat)
-            }
-            TimeSignature::Six => {
-                // 6/8 time - eighth note gets the beat, but we count in compound time
-                // BPM refers to dotted quarter notes (3 eighth notes)
-                // So each eighth note is 1/3 of the dotted quarter
-                Duration::from_secs_f64(base_seconds_per_beat)
-            }
-            TimeSignature::Seven => {
-                // 6/8 time - eighth note gets the beat, but we count in compound time
-                // BPM refers to dotted quarter notes (3 eighth notes)
-                // So each eighth note is 1/3 of the dotted quarter
-                Duration::from_secs_f64(base_seconds_per_beat)
-            }
-            TimeSignature::Eight => {
-                // 6/8 time - eighth note gets the beat, but we count in compound time
-                // BPM refers to dotted quarter notes (3 eighth notes)
-                // So each eighth note is 1/3 of the dotted quarter
-                Duration::from_secs_f64(base_seconds_per_beat)
-            }
-        }
+    /// Get the tempo in beats per minute
+    pub fn bpm(&self) -> f64 {
+        self.0
     }
     
-    /// Get the accent pattern for the current time signature
-    pub fn get_accent_pattern(&self) -> Vec<bool> {
-        match self.time_signature {
-            TimeSignature::One => vec![false], // weak
-            TimeSignature::Two => vec![true, false], // Strong-weak
-            TimeSignature::Three => vec![true, false, false], // Strong-weak-weak
-            TimeSignature::Four => vec![true, false, true, false], // Strong-weak-medium-weak
-            TimeSignature::Five => vec![true, false, false,false,false], // Strong-weak-weak-weak-weak
-            TimeSignature::Six => vec![true, false, false, true, false, false], // Strong-weak-weak-medium-weak-weak
-            TimeSignature::Seven => vec![true, false, false, false, false, false], // Strong-weak-weak-weak-weak-weak-weak
-            TimeSignature::Eight => vec![true, false, false, false, true, false, false, false], // Strong-weak-weak-weak-medium-weak-weak-weak
-        }
+    /// Get the time from one beat to the next
+    pub fn beat_interval(&self) -> Duration {
+        Duration::from_secs_f64(60.0 / self.0)
+    }
+}
+
+impl Default for Tempo {
+    fn default() -> Self {
+        Self(120.0)
+    }
+}
+
+impl From<u32> for Tempo {
+    fn from(bpm: u32) -> Self {
+        Self(bpm as f64)
+    }
+}
+
+impl From<f64> for Tempo {
+    fn from(bpm: f64) -> Self {
+        Self(bpm)
+    }
+}
+
+impl PartialEq<u32> for Tempo {
+    fn eq(&self, bpm: &u32) -> bool {
+        self.0 == *bpm as f64
+    }
+}
+
+impl std::fmt::Display for Tempo {
+    /// Whole tempos print without decimals, fractional ones with up to two
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(&((self.0 * 100.0).round() / 100.0), f)
     }
+}
+
+impl std::str::FromStr for Tempo {
+    type Err = MetronomeError;
     
-    /// Check if the current beat should be accented based on time signature
-    pub fn is_accent_beat(&self) -> bool {
-        if !self.accent_enabled {
-            return false; // No accents when disabled
+    fn from_str(s: &str) -> Result<Self> {
+        TempoRange::default().validate(Self::parse(s)?)
+    }
+}
+
+/// Range of tempos the metronome accepts, 20 to 400 BPM by default
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "gui", derive(Serialize, Deserialize))]
+pub struct TempoRange {
+    pub min: f64,
+    pub max: f64,
+}
+
+impl TempoRange {
+    /// Create a range within 20 to 1000 BPM, narrowing or widening the default range
+    pub fn new(min: f64, max: f64) -> Result<Self> {
+        if !(MIN_BPM..=HIGHEST_BPM).contains(&min) || !(MIN_BPM..=HIGHEST_BPM).contains(&max) || min >= max {
+            return Err(MetronomeError::InvalidTempo(format!(
+                "range {} to {} BPM, expected a minimum below the maximum within {} to {}", min, max, MIN_BPM, HIGHEST_BPM
+            )));
         }
-        let pattern = self.get_accent_pattern();
-        let beat_index = (self.current_beat_in_measure - 1) as usize;
-        pattern.get(beat_index).copied().unwrap_or(false)
+        Ok(Self { min, max })
     }
     
-    /// Get the accent strength (0.0 = no accent, 1.0 = strongest accent)
-    pub fn get_accent_strength(&self) -> f32 {
-        if !self.accent_enabled {
-            return 0.0; // No accents when disabled
-        }
-        match self.time_signature {
-            TimeSignature::One => {
-                match self.current_beat_in_measure {
-                    _ => 0.0, // Weak beat
-                }
-            }
-            TimeSignature::Two => {
-                match self.current_beat_in_measure {
-                    1 => 1.0, // Strong beat
-                    _ => 0.0, // Weak beat
-                }
-            }
-            TimeSignature::Three => {
-                match self.current_beat_in_measure {
-                    1 => 1.0, // Strong beat
-                    _ => 0.0, // Weak beats
-                }
-            }
-            TimeSignature::Four => {
-                match self.current_beat_in_measure {
-                    1 => 1.0,   // Strong beat
-                    3 => 0.5,   // Medium beat
-                    _ => 0.0,   // Weak beats
-                }
-            }
-            TimeSignature::Five => {
-                match self.current_beat_in_measure {
-                    1 => 1.0, // Strong beat
-                    _ => 0.0, // Weak beats
-                }
-            }
-            TimeSignature::Six => {
-                match self.current_beat_in_measure {
-                    1 => 1.0,   // Strong beat
-                    4 => 0.5,   // Medium beat
-                    _ => 0.0,   // Weak beats
-                }
-            }
-            TimeSignature::Seven => {
-                match self.current_beat_in_measure {
-                    1 => 1.0, // Strong beat
-                    _ => 0.0, // Weak beats
-                }
-            }
-            TimeSignature::Eight => {
-                match self.current_beat_in_measure {
-                    1 => 1.0,   // Strong beat
-                    5 => 0.5,   // Medium beat
-                    _ => 0.0,   // Weak beats
-                }
-            }
-        }
+    /// The widest range that can be configured, which every tempo must at least fit
+    pub fn widest() -> Self {
+        Self { min: MIN_BPM, max: HIGHEST_BPM }
     }
     
-    /// Calculate the expected time for the next beat
-    pub fn get_next_beat_time(&self) -> Option<Instant> {
-        if let Some(start_time) = self.start_time {
-            let interval = self.calculate_beat_interval();
-            let next_beat_time = start_time + interval * (self.beat_count + 1) as u32;
-            Some(next_beat_time)
-        } else {
-            None
-        }
+    pub fn contains(&self, tempo: Tempo) -> bool {
+        (self.min..=self.max).contains(&tempo.bpm())
     }
     
-    /// Get timing accuracy (how close we are to the expected beat time)
-    pub fn get_timing_accuracy(&self, actual_beat_time: Instant) -> Option<Duration> {
-        if let Some(expected_time) = self.get_next_beat_time() {
-            if actual_beat_time >= expected_time {
-                Some(actual_beat_time - expected_time)
-            } else {
-                Some(expected_time - actual_beat_time)
-            }
-        } else {
-            None
+    /// Return the tempo if it is within the range
+    pub fn validate(&self, tempo: Tempo) -> Result<Tempo> {
+        if !self.contains(tempo) {
+            return Err(MetronomeError::InvalidBpm(tempo.bpm()));
         }
+        Ok(tempo)
     }
     
-    pub fn increment_beat(&mut self) -> Beat {
-        self.beat_count += 1;
-        let beats_per_measure = self.time_signature.beats_per_measure();
-        self.current_beat_in_measure = ((self.beat_count - 1) % beats_per_measure as u64) as u32 + 1;
-        
-        Beat::new(self.beat_count, self.time_signature, self.bpm)
+    /// Bring a tempo into the range
+    pub fn clamp(&self, tempo: Tempo) -> Tempo {
+        Tempo(tempo.bpm().clamp(self.min, self.max))
     }
-    
-    pub fn start(&mut self) {
-        if !self.is_running {
-            self.start_time = Some(Instant::now());
-            self.beat_count = 0;
-            self.current_beat_in_measure = 1;
-            self.is_running = true;
+}
+
+impl Default for TempoRange {
+    fn default() -> Self {
+        Self { min: MIN_BPM, max: MAX_BPM }
+    }
+}
+
+/// Shape of the tempo change during a tempo ramp
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "gui", derive(Serialize, Deserialize))]
+pub enum TempoCurve {
+    /// Equal BPM steps per beat
+    #[default]
+    Linear,
+    /// Equal BPM ratios per beat, so the change feels even at any tempo
+    Exponential,
+}
+
+impl TempoCurve {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TempoCurve::Linear => "Linear",
+            TempoCurve::Exponential => "Exponential",
         }
     }
     
-    pub fn stop(&mut self) {
-        self.is_running = false;
-        self.start_time = None;
+    pub fn all() -> &'static [TempoCurve] {
+        &[TempoCurve::Linear, TempoCurve::Exponential]
     }
+}
+
+impl std::str::FromStr for TempoCurve {
+    type Err = String;
     
-    pub fn update_bpm(&mut self, bpm: u32) -> Result<()> {
-        if bpm < 60 || bpm > 200 {
-            return Err(MetronomeError::InvalidBpm(bpm));
-        }
-        self.bpm = bpm;
-        Ok(())
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        TempoCurve::all()
+            .iter()
+            .copied()
+            .find(|curve| curve.as_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("unknown tempo curve '{}', expected linear or exponential", s))
+    }
+}
+
+/// Gradual tempo change (accelerando or ritardando) over a number of bars
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "gui", derive(Serialize, Deserialize))]
+pub struct TempoRamp {
+    pub start_bpm: Tempo,
+    pub target_bpm: Tempo,
+    pub bars: u32,
+    pub curve: TempoCurve,
+}
+
+impl TempoRamp {
+    /// Create a linear ramp, validating the length and that both tempos fit the widest range
+    ///
+    /// Use [`TempoRamp::validate`] to check the tempos against the active range.
+    pub fn new(start_bpm: impl Into<Tempo>, target_bpm: impl Into<Tempo>, bars: u32) -> Result<Self> {
+        let ramp = Self {
+            start_bpm: start_bpm.into(),
+            target_bpm: target_bpm.into(),
+            bars,
+            curve: TempoCurve::Linear,
+        };
+        ramp.validate(&TempoRange::widest())?;
+        Ok(ramp)
     }
     
-    pub fn update_time_signature(&mut self, time_signature: TimeSignature) {
-        self.time_signature = time_signature;
-        // Reset beat position when changing time signature
-        if self.is_running {
-            self.current_beat_in_measure = 1;
+    /// Check the length and that both tempos are within the given range
+    pub fn validate(&self, tempo_range: &TempoRange) -> Result<()> {
+        tempo_range.validate(self.start_bpm)?;
+        tempo_range.validate(self.target_bpm)?;
+        if self.bars == 0 {
+            return Err(MetronomeError::InvalidTempoRamp("the ramp must last at least one bar".to_string()));
         }
+        Ok(())
     }
     
-    pub fn update_sounds(&mut self, beat_sound: SoundType, accent_sound: SoundType) {
-        self.beat_sound = beat_sound;
-        self.accent_sound = accent_sound;
+    pub fn with_curve(mut self, curve: TempoCurve) -> Self {
+        self.curve = curve;
+        self
     }
     
-    pub fn update_accent_enabled(&mut self, accent_enabled: bool) {
-        self.accent_enabled = accent_enabled;
+    /// Get the number of beats the ramp lasts in the given time signature
+    pub fn total_beats(&self, time_signature: TimeSignature) -> u64 {
+        self.bars as u64 * time_signature.beats_per_measure() as u64
     }
     
-    pub fn update_volume(&mut self, volume: f32) -> Result<()> {
-        if volume < 0.0 || volume > 1.0 {
-            return Err(MetronomeError::InvalidVolume(volume));
+    /// Get the tempo at the given progress through the ramp (0.0 = start, 1.0 = target)
+    pub fn tempo_at(&self, progress: f64) -> f64 {
+        let progress = progress.clamp(0.0, 1.0);
+        let start = self.start_bpm.bpm();
+        let target = self.target_bpm.bpm();
+        match self.curve {
+            TempoCurve::Linear => start + (target - start) * progress,
+            TempoCurve::Exponential => start * (target / start).powf(progress),
         }
-        self.volume = volume;
-        Ok(())
     }
 }
 
-/// GUI state structure for managing UI state
-#[derive(Debug, Clone)]
-pub struct GuiState {
-    pub bpm_input: String,
-    pub bpm_valid: bool,
-    pub selected_time_signature: TimeSignature,
-    pub selected_beat_sound: SoundType,
-    pub selected_accent_sound: SoundType,
-    pub is_running: bool,
-    pub error_message: Option<String>,
-    pub last_beat_visual: Option<Instant>,
-    pub accent_enabled: bool,
-    pub volume: f32,
+/// What the speed trainer does once a step at the target tempo has been played
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "gui", derive(Serialize, Deserialize))]
+pub enum TrainerEnd {
+    /// Stay at the target tempo
+    #[default]
+    Hold,
+    /// Start over from the start tempo
+    Loop,
+    /// Drop back by the given number of BPM and climb again
+    DropBack(u32),
 }
 
-impl GuiState {
-    pub fn new() -> Self {
-        Self {
-            bpm_input: "120".to_string(),
-            bpm_valid: true,
-            selected_time_signature: TimeSignature::default(),
-            selected_beat_sound: SoundType::default(),
-            selected_accent_sound: SoundType::BuiltinWood,
-            is_running: false,
-            error_message: None,
-            last_beat_visual: None,
-            accent_enabled: true, // Accents enabled by default
-            volume: 0.7, // Default volume at 70%
+impl TrainerEnd {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TrainerEnd::Hold => "Hold",
+            TrainerEnd::Loop => "Loop",
+            TrainerEnd::DropBack(_) => "Drop back",
         }
     }
+}
+
+/// Speed trainer that raises the tempo in steps every few bars until a target
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "gui", derive(Serialize, Deserialize))]
+pub struct SpeedTrainer {
+    pub start_bpm: Tempo,
+    pub target_bpm: Tempo,
+    /// BPM added at each step
+    pub increment: u32,
+    /// Bars played at each tempo before stepping up
+    pub bars_per_step: u32,
+    pub end: TrainerEnd,
+}
+
+impl SpeedTrainer {
+    /// Create a trainer that holds the target, validating step sizes and that the tempos
+    /// fit the widest range
+    ///
+    /// Use [`SpeedTrainer::validate`] to check the tempos against the active range.
+    pub fn new(start_bpm: impl Into<Tempo>, target_bpm: impl Into<Tempo>, increment: u32, bars_per_step: u32) -> Result<Self> {
+        let trainer = Self {
+            start_bpm: start_bpm.into(),
+            target_bpm: target_bpm.into(),
+            increment,
+            bars_per_step,
+            end: TrainerEnd::Hold,
+        };
+        trainer.validate(&TempoRange::widest())?;
+        Ok(trainer)
+    }
     
-    pub fn validate_bpm(&mut self, input: &str) -> Result<u32> {
-        match input.trim().parse::<u32>() {
-            Ok(bpm) if bpm >= 60 && bpm <= 200 => {
-                self.bpm_valid = true;
-                self.error_message = None;
-                Ok(bpm)
-            }
-            Ok(bpm) => {
-                self.bpm_valid = false;
-                self.error_message = Some(format!("BPM must be between 60 and 200 (got {})", bpm));
-                Err(MetronomeError::InvalidBpm(bpm))
-            }
-            Err(_) => {
-                self.bpm_valid = false;
-                self.error_message = Some("Invalid BPM value. Please enter a number.".to_string());
-                Err(MetronomeError::InvalidBpm(0))
-            }
+    /// Check the step sizes, the end behaviour and that both tempos are within the given range
+    pub fn validate(&self, tempo_range: &TempoRange) -> Result<()> {
+        tempo_range.validate(self.start_bpm)?;
+        tempo_range.validate(self.target_bpm)?;
+        if self.target_bpm <= self.start_bpm {
+            return Err(MetronomeError::InvalidSpeedTrainer(format!(
+                "target {} BPM must be above the start tempo of {} BPM", self.target_bpm, self.start_bpm
+            )));
+        }
+        if self.increment == 0 || self.bars_per_step == 0 {
+            return Err(MetronomeError::InvalidSpeedTrainer(
+                "the increment and bars per step must be at least 1".to_string()
+            ));
+        }
+        if self.end == TrainerEnd::DropBack(0) {
+            return Err(MetronomeError::InvalidSpeedTrainer("drop back by at least 1 BPM".to_string()));
         }
+        Ok(())
     }
     
-    pub fn set_error(&mut self, message: String) {
-        self.error_message = Some(message);
+    /// Set the end behaviour, validating the drop-back amount
+    pub fn with_end(mut self, end: TrainerEnd) -> Result<Self> {
+        if end == TrainerEnd::DropBack(0) {
+            return Err(MetronomeError::InvalidSpeedTrainer("drop back by at least 1 BPM".to_string()));
+        }
+        self.end = end;
+        Ok(self)
     }
     
-    pub fn clear_error(&mut self) {
-        self.error_message = None;
+    /// Get the number of beats played at each tempo in the given time signature
+    pub fn beats_per_step(&self, time_signature: TimeSignature) -> u64 {
+        self.bars_per_step as u64 * time_signature.beats_per_measure() as u64
     }
     
-    pub fn update_beat_visual(&mut self) {
-        self.last_beat_visual = Some(Instant::now());
+    /// Get the tempo of the step after one played at `current_bpm`; `None` means hold
+    pub fn next_bpm(&self, current_bpm: Tempo) -> Option<Tempo> {
+        if current_bpm < self.target_bpm {
+            let next = current_bpm.bpm() + self.increment as f64;
+            return Some(Tempo::from(next.min(self.target_bpm.bpm())));
+        }
+        match self.end {
+            TrainerEnd::Hold => None,
+            TrainerEnd::Loop => Some(self.start_bpm),
+            TrainerEnd::DropBack(amount) => {
+                let dropped = self.target_bpm.bpm() - amount as f64;
+                Some(Tempo::from(dropped.max(self.start_bpm.bpm())))
+            }
+        }
+    }
+}
+
+/// Gap click training: the click plays for some bars and then stays silent for some bars
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "gui", derive(Serialize, Deserialize))]
+pub struct GapClick {
+    /// Bars with the click audible
+    pub play_bars: u32,
+    /// Silent bars that follow
+    pub mute_bars: u32,
+}
+
+impl GapClick {
+    pub fn new(play_bars: u32, mute_bars: u32) -> Result<Self> {
+        let gap_click = Self { play_bars, mute_bars };
+        gap_click.validate()?;
+        Ok(gap_click)
     }
     
-    pub fn should_show_beat_visual(&self, duration: Duration) -> bool {
-        match self.last_beat_visual {
-            Some(last_beat) => last_beat.elapsed() < duration,
-            None => false,
+    /// Check that at least one bar plays and one bar is muted, e.g. after loading a config
+    pub fn validate(&self) -> Result<()> {
+        if self.play_bars == 0 || self.mute_bars == 0 {
+            return Err(MetronomeError::InvalidGapClick(
+                "play at least 1 bar and mute at least 1 bar".to_string()
+            ));
         }
+        Ok(())
     }
     
-    pub fn set_gui_error(&mut self, error: crate::error::GuiError) {
-        self.error_message = Some(error.to_string());
+    /// Get the length of one play-and-mute cycle in bars
+    pub fn cycle_bars(&self) -> u64 {
+        self.play_bars as u64 + self.mute_bars as u64
     }
     
-    pub fn handle_result<T>(&mut self, result: Result<T>) -> Option<T> {
-        match result {
-            Ok(value) => {
-                self.clear_error();
-                Some(value)
-            }
-            Err(error) => {
-                self.set_error(error.to_string());
-                None
-            }
+    /// Check whether a bar (0-based, counted from the start of the gap training) is silent
+    pub fn is_silent_bar(&self, bar: u64) -> bool {
+        bar % self.cycle_bars() >= self.play_bars as u64
+    }
+    
+    /// Get the 1-based position of a bar within its play or mute section
+    pub fn bar_in_section(&self, bar: u64) -> u32 {
+        let position = bar % self.cycle_bars();
+        if position < self.play_bars as u64 {
+            position as u32 + 1
+        } else {
+            (position - self.play_bars as u64) as u32 + 1
         }
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+impl std::str::FromStr for GapClick {
+    type Err = MetronomeError;
     
-    #[test]
-    fn test_metronome_config_creation() {
-        let config = MetronomeConfig::new(120);
-        assert_eq!(config.bpm, 120);
-        assert!(config.sound_enabled);
-        assert!(config.visual_enabled);
+    /// Parse "PLAY:MUTE" bar counts such as "4:2"
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || MetronomeError::InvalidGapClick(format!("'{}': expected bar counts such as 4:2", s.trim()));
+        let (play, mute) = s.trim().split_once(':').ok_or_else(invalid)?;
+        let play_bars = play.trim().parse::<u32>().map_err(|_| invalid())?;
+        let mute_bars = mute.trim().parse::<u32>().map_err(|_| invalid())?;
+        Self::new(play_bars, mute_bars)
     }
-    
-    #[test]
-    fn test_metronome_config_validation() {
-        let config = MetronomeConfig::new(120);
+}
+
+/// Random beat dropout: each click is silenced with a given probability
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "gui", derive(Serialize, Deserialize))]
+pub struct BeatDropout {
+    /// Chance of a click being silenced, from 0.0 to 1.0
+    pub probability: f32,
+    /// Never drop the main click of beat 1; its subdivision clicks can still drop
+    pub keep_downbeat: bool,
+    /// Seed for reproducible dropouts; a random seed is used when `None`
+    #[cfg_attr(feature = "gui", serde(default))]
+    pub seed: Option<u64>,
+}
+
+impl BeatDropout {
+    /// Create a dropout that keeps the downbeat, validating the probability
+    pub fn new(probability: f32) -> Result<Self> {
+        let dropout = Self {
+            probability,
+            keep_downbeat: true,
+            seed: None,
+        };
+        dropout.validate()?;
+        Ok(dropout)
+    }
+    
+    /// Check that the probability is between 0 and 1, e.g. after loading a config
+    pub fn validate(&self) -> Result<()> {
+        if !(0.0..=1.0).contains(&self.probability) {
+            return Err(MetronomeError::InvalidDropout(format!(
+                "probability must be between 0 and 1 (got {})", self.probability
+            )));
+        }
+        Ok(())
+    }
+    
+    pub fn with_keep_downbeat(mut self, keep_downbeat: bool) -> Self {
+        self.keep_downbeat = keep_downbeat;
+        self
+    }
+    
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+    
+    /// Create the random number generator for this dropout
+    pub fn create_rng(&self) -> fastrand::Rng {
+        match self.seed {
+            Some(seed) => fastrand::Rng::with_seed(seed),
+            None => fastrand::Rng::new(),
+        }
+    }
+    
+    /// Decide whether a click is dropped, drawing from the generator for every eligible click
+    ///
+    /// Each click is decided on its own, subdivision clicks included; only the main
+    /// click of beat 1 is exempt when the downbeat is kept.
+    pub fn should_drop(&self, beat_in_measure: u32, subdivision_index: u32, rng: &mut fastrand::Rng) -> bool {
+        if beat_in_measure == 1 && subdivision_index == 0 && self.keep_downbeat {
+            return false;
+        }
+        rng.f32() < self.probability
+    }
+}
+
+/// Bars counted in before playback starts, played with their own sound
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "gui", derive(Serialize, Deserialize))]
+pub struct CountIn {
+    pub bars: u32,
+    #[cfg_attr(feature = "gui", serde(default = "default_count_in_sound"))]
+    pub sound: SoundType,
+}
+
+impl CountIn {
+    /// Create a count-in of 1 to 4 bars with the beep sound
+    pub fn new(bars: u32) -> Result<Self> {
+        let count_in = Self {
+            bars,
+            sound: SoundType::BuiltinBeep,
+        };
+        count_in.validate()?;
+        Ok(count_in)
+    }
+    
+    /// Check that the count-in lasts 1 to 4 bars, e.g. after loading a config
+    pub fn validate(&self) -> Result<()> {
+        if !(1..=4).contains(&self.bars) {
+            return Err(MetronomeError::InvalidCountIn(format!("{} bars, expected 1 to 4", self.bars)));
+        }
+        Ok(())
+    }
+    
+    pub fn with_sound(mut self, sound: SoundType) -> Self {
+        self.sound = sound;
+        self
+    }
+    
+    /// Number of beats counted in
+    pub fn total_beats(&self, time_signature: TimeSignature) -> u64 {
+        self.bars as u64 * time_signature.beats_per_measure() as u64
+    }
+}
+
+/// Longest practice session that can be set
+const MAX_SESSION_DURATION: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Point at which a practice session stops on its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "gui", derive(Serialize, Deserialize))]
+pub enum SessionLimit {
+    /// Stop once this much time has passed since the start, count-in included
+    Duration(Duration),
+    /// Stop after this many bars, not counting the count-in
+    Bars(u32),
+}
+
+impl SessionLimit {
+    /// Create a limit on the length of the session, up to 24 hours
+    pub fn duration(duration: Duration) -> Result<Self> {
+        if duration.is_zero() || duration > MAX_SESSION_DURATION {
+            return Err(MetronomeError::InvalidSessionLimit(format!(
+                "{} seconds, expected 1 second to 24 hours", duration.as_secs()
+            )));
+        }
+        Ok(SessionLimit::Duration(duration))
+    }
+    
+    /// Create a limit on the number of bars played
+    pub fn bars(bars: u32) -> Result<Self> {
+        if bars == 0 {
+            return Err(MetronomeError::InvalidSessionLimit("0 bars, expected at least 1".to_string()));
+        }
+        Ok(SessionLimit::Bars(bars))
+    }
+    
+    /// Parse a session length such as "10m", "90s", "1h30m" or "2:30" (minutes and seconds)
+    pub fn parse_duration(input: &str) -> Result<Self> {
+        let text = input.trim().to_lowercase();
+        let invalid = || MetronomeError::InvalidSessionLimit(format!(
+            "'{}': expected a length such as 10m, 90s, 1h30m or 2:30", input.trim()
+        ));
+        
+        if let Some((minutes, seconds)) = text.split_once(':') {
+            let minutes = minutes.parse::<u64>().map_err(|_| invalid())?;
+            let seconds = seconds.parse::<u64>().map_err(|_| invalid())?;
+            if seconds >= 60 {
+                return Err(invalid());
+            }
+            let total = minutes.checked_mul(60).and_then(|total| total.checked_add(seconds)).ok_or_else(invalid)?;
+            return Self::duration(Duration::from_secs(total));
+        }
+        
+        // Each number needs a unit, and units may be combined from largest to smallest
+        let mut total: u64 = 0;
+        let mut number = String::new();
+        for c in text.chars() {
+            if c.is_ascii_digit() {
+                number.push(c);
+                continue;
+            }
+            let unit_seconds = match c {
+                'h' => 3600,
+                'm' => 60,
+                's' => 1,
+                _ => return Err(invalid()),
+            };
+            let value = number.parse::<u64>().map_err(|_| invalid())?;
+            total = value.checked_mul(unit_seconds)
+                .and_then(|seconds| total.checked_add(seconds))
+                .ok_or_else(invalid)?;
+            number.clear();
+        }
+        if text.is_empty() || !number.is_empty() {
+            return Err(invalid());
+        }
+        Self::duration(Duration::from_secs(total))
+    }
+    
+    pub fn validate(&self) -> Result<()> {
+        match *self {
+            SessionLimit::Duration(duration) => Self::duration(duration).map(|_| ()),
+            SessionLimit::Bars(bars) => Self::bars(bars).map(|_| ()),
+        }
+    }
+}
+
+impl std::fmt::Display for SessionLimit {
+    /// Times show as m:ss or h:mm:ss, bars as "1 bar" or "64 bars"
+    ///
+    /// Part seconds round up, so a countdown reaches 0:00 only when the time is up.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            SessionLimit::Duration(duration) => {
+                let seconds = duration.as_secs() + u64::from(duration.subsec_nanos() > 0);
+                if seconds >= 3600 {
+                    write!(f, "{}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)
+                } else {
+                    write!(f, "{}:{:02}", seconds / 60, seconds % 60)
+                }
+            }
+            SessionLimit::Bars(1) => write!(f, "1 bar"),
+            SessionLimit::Bars(bars) => write!(f, "{} bars", bars),
+        }
+    }
+}
+
+/// When tempo and time signature changes made during playback take effect
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "gui", derive(Serialize, Deserialize))]
+pub enum ChangeTiming {
+    /// From the next click
+    #[default]
+    Immediate,
+    /// Queued until the next downbeat, so the bar in progress is played out
+    NextBar,
+}
+
+impl ChangeTiming {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeTiming::Immediate => "Immediately",
+            ChangeTiming::NextBar => "At next bar",
+        }
+    }
+    
+    pub fn all() -> &'static [ChangeTiming] {
+        &[ChangeTiming::Immediate, ChangeTiming::NextBar]
+    }
+}
+
+/// Taps that differ from the median interval by more than this fraction are ignored
+const TAP_OUTLIER_TOLERANCE: f64 = 0.25;
+
+/// Tempo detection from taps
+///
+/// The tempo is the average of the recent tap intervals. Once there are enough
+/// intervals to judge, ones far from the median (a missed or doubled tap) are
+/// ignored, and a pause longer than `reset_after` starts a new measurement.
+#[derive(Debug, Clone)]
+pub struct TapTempo {
+    taps: VecDeque<Instant>,
+    /// Number of most recent taps the tempo is averaged over
+    pub max_taps: usize,
+    /// Pause after which the next tap starts over
+    pub reset_after: Duration,
+}
+
+impl Default for TapTempo {
+    fn default() -> Self {
+        Self {
+            taps: VecDeque::new(),
+            max_taps: 8,
+            reset_after: Duration::from_secs(2),
+        }
+    }
+}
+
+impl TapTempo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Average over the given number of taps; at least two are needed for a tempo
+    pub fn with_max_taps(mut self, max_taps: usize) -> Result<Self> {
+        if max_taps < 2 {
+            return Err(MetronomeError::InvalidTapTempo(format!(
+                "averaging needs at least 2 taps, got {}", max_taps
+            )));
+        }
+        self.max_taps = max_taps;
+        Ok(self)
+    }
+    
+    pub fn with_reset_after(mut self, reset_after: Duration) -> Result<Self> {
+        if reset_after.is_zero() {
+            return Err(MetronomeError::InvalidTapTempo("the reset pause must be longer than zero".to_string()));
+        }
+        self.reset_after = reset_after;
+        Ok(self)
+    }
+    
+    /// Register a tap now and return the detected tempo
+    pub fn tap(&mut self) -> Option<f64> {
+        self.tap_at(Instant::now())
+    }
+    
+    /// Register a tap at the given time and return the detected tempo
+    pub fn tap_at(&mut self, now: Instant) -> Option<f64> {
+        match self.taps.back() {
+            Some(&last) if now.saturating_duration_since(last) > self.reset_after => self.taps.clear(),
+            // Taps at the same instant would mean an infinite tempo
+            Some(&last) if now <= last => return self.tempo(),
+            _ => {}
+        }
+        self.taps.push_back(now);
+        while self.taps.len() > self.max_taps {
+            self.taps.pop_front();
+        }
+        self.tempo()
+    }
+    
+    /// Get the detected tempo in BPM, once there are at least two taps
+    pub fn tempo(&self) -> Option<f64> {
+        let mut intervals: Vec<f64> = self.taps.iter()
+            .zip(self.taps.iter().skip(1))
+            .map(|(earlier, later)| later.duration_since(*earlier).as_secs_f64())
+            .collect();
+        if intervals.is_empty() {
+            return None;
+        }
+        
+        // With three or more intervals the median tells which ones are off
+        if intervals.len() >= 3 {
+            let mut sorted = intervals.clone();
+            sorted.sort_by(f64::total_cmp);
+            let median = sorted[sorted.len() / 2];
+            intervals.retain(|interval| (interval - median).abs() <= median * TAP_OUTLIER_TOLERANCE);
+        }
+        let average = intervals.iter().sum::<f64>() / intervals.len() as f64;
+        Some(60.0 / average)
+    }
+    
+    /// Get the detected tempo as it was tapped, to be checked against the tempo range in use
+    pub fn bpm(&self) -> Option<Tempo> {
+        self.tempo().map(Tempo)
+    }
+    
+    /// Number of taps in the current measurement
+    pub fn tap_count(&self) -> usize {
+        self.taps.len()
+    }
+    
+    /// Forget all taps and start a new measurement
+    pub fn reset(&mut self) {
+        self.taps.clear();
+    }
+}
+
+/// Accent level of a single beat in the measure
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "gui", derive(Serialize, Deserialize))]
+pub enum AccentLevel {
+    /// Accent sound at full volume
+    Strong,
+    /// Accent sound at reduced volume
+    Medium,
+    /// Regular beat sound
+    #[default]
+    Weak,
+    /// Silent beat
+    Mute,
+}
+
+impl AccentLevel {
+    /// Get the accent strength (0.0 = no accent, 1.0 = strongest accent)
+    pub fn strength(&self) -> f32 {
+        match self {
+            AccentLevel::Strong => 1.0,
+            AccentLevel::Medium => 0.5,
+            AccentLevel::Weak | AccentLevel::Mute => 0.0,
+        }
+    }
+    
+    /// Get the level matching an accent strength
+    pub fn from_strength(strength: f32) -> Self {
+        if strength >= 1.0 {
+            AccentLevel::Strong
+        } else if strength > 0.0 {
+            AccentLevel::Medium
+        } else {
+            AccentLevel::Weak
+        }
+    }
+    
+    /// Get the next level when cycling through them in the pattern editor
+    pub fn next(&self) -> Self {
+        match self {
+            AccentLevel::Strong => AccentLevel::Medium,
+            AccentLevel::Medium => AccentLevel::Weak,
+            AccentLevel::Weak => AccentLevel::Mute,
+            AccentLevel::Mute => AccentLevel::Strong,
+        }
+    }
+    
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccentLevel::Strong => "Strong",
+            AccentLevel::Medium => "Medium",
+            AccentLevel::Weak => "Weak",
+            AccentLevel::Mute => "Mute",
+        }
+    }
+    
+    pub fn all() -> &'static [AccentLevel] {
+        &[
+            AccentLevel::Strong,
+            AccentLevel::Medium,
+            AccentLevel::Weak,
+            AccentLevel::Mute,
+        ]
+    }
+}
+
+/// Per-beat accent levels for one measure
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "gui", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "gui", serde(try_from = "Vec<AccentLevel>", into = "Vec<AccentLevel>"))]
+pub struct AccentPattern {
+    levels: Vec<AccentLevel>,
+}
+
+impl AccentPattern {
+    /// Create a pattern from one level per beat
+    pub fn new(levels: Vec<AccentLevel>) -> Result<Self> {
+        if levels.is_empty() || levels.len() > TimeSignature::MAX_NUMERATOR as usize {
+            return Err(MetronomeError::InvalidAccentPattern(format!(
+                "expected 1 to {} beats, got {}", TimeSignature::MAX_NUMERATOR, levels.len()
+            )));
+        }
+        Ok(Self { levels })
+    }
+    
+    /// Get the pattern implied by the time signature and its grouping
+    pub fn default_for(time_signature: TimeSignature) -> Self {
+        let levels = (1..=time_signature.beats_per_measure())
+            .map(|beat| AccentLevel::from_strength(time_signature.accent_strength(beat)))
+            .collect();
+        Self { levels }
+    }
+    
+    pub fn levels(&self) -> &[AccentLevel] {
+        &self.levels
+    }
+    
+    /// Get the number of beats the pattern covers
+    pub fn len(&self) -> usize {
+        self.levels.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.levels.is_empty()
+    }
+    
+    /// Check whether the pattern has one level for each beat of the time signature
+    pub fn fits(&self, time_signature: TimeSignature) -> bool {
+        self.levels.len() == time_signature.beats_per_measure() as usize
+    }
+    
+    /// Get the level of a beat (1-based); beats past the end are weak
+    pub fn level(&self, beat_in_measure: u32) -> AccentLevel {
+        beat_in_measure
+            .checked_sub(1)
+            .and_then(|index| self.levels.get(index as usize))
+            .copied()
+            .unwrap_or_default()
+    }
+    
+    /// Set the level of a beat (1-based); beats past the end are ignored
+    pub fn set_level(&mut self, beat_in_measure: u32, level: AccentLevel) {
+        if let Some(slot) = beat_in_measure.checked_sub(1).and_then(|index| self.levels.get_mut(index as usize)) {
+            *slot = level;
+        }
+    }
+    
+    /// Advance a beat (1-based) to the next accent level
+    pub fn cycle_level(&mut self, beat_in_measure: u32) {
+        let next = self.level(beat_in_measure).next();
+        self.set_level(beat_in_measure, next);
+    }
+}
+
+/// Stored patterns go through the same checks as `AccentPattern::new`
+impl TryFrom<Vec<AccentLevel>> for AccentPattern {
+    type Error = MetronomeError;
+    
+    fn try_from(levels: Vec<AccentLevel>) -> Result<Self> {
+        Self::new(levels)
+    }
+}
+
+impl From<AccentPattern> for Vec<AccentLevel> {
+    fn from(pattern: AccentPattern) -> Self {
+        pattern.levels
+    }
+}
+
+/// Sound type enumeration for different metronome sounds
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "gui", derive(Serialize, Deserialize))]
+pub enum SoundType {
+    /// Built-in click sound
+    BuiltinClick,
+    /// Built-in wood block sound
+    BuiltinWood,
+    /// Built-in beep sound
+    BuiltinBeep,
+    /// Custom sound from file
+    Custom(PathBuf),
+}
+
+impl SoundType {
+    /// Get a human-readable string representation
+    pub fn as_str(&self) -> String {
+        match self {
+            SoundType::BuiltinClick => "Click".to_string(),
+            SoundType::BuiltinWood => "Wood".to_string(),
+            SoundType::BuiltinBeep => "Beep".to_string(),
+            SoundType::Custom(path) => {
+                format!("Custom: {}", path.file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or("Unknown"))
+            }
+        }
+    }
+    
+    /// Get all built-in sound types
+    pub fn builtin_sounds() -> &'static [SoundType] {
+        &[
+            SoundType::BuiltinClick,
+            SoundType::BuiltinWood,
+            SoundType::BuiltinBeep,
+        ]
+    }
+    
+    /// Check if this is a built-in sound
+    pub fn is_builtin(&self) -> bool {
+        matches!(self, SoundType::BuiltinClick | SoundType::BuiltinWood | SoundType::BuiltinBeep)
+    }
+}
+
+impl Default for SoundType {
+    fn default() -> Self {
+        SoundType::BuiltinClick
+    }
+}
+
+/// Interpolation used when converting sounds to the output device's sample rate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "gui", derive(Serialize, Deserialize))]
+pub enum ResampleQuality {
+    /// Linear interpolation
+    Fast,
+    /// Four-point cubic interpolation
+    #[default]
+    Balanced,
+    /// Windowed sinc filter
+    High,
+}
+
+impl ResampleQuality {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ResampleQuality::Fast => "Fast",
+            ResampleQuality::Balanced => "Balanced",
+            ResampleQuality::High => "High",
+        }
+    }
+    
+    pub fn all() -> &'static [ResampleQuality] {
+        &[ResampleQuality::Fast, ResampleQuality::Balanced, ResampleQuality::High]
+    }
+}
+
+impl std::fmt::Display for ResampleQuality {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str().to_lowercase())
+    }
+}
+
+impl std::str::FromStr for ResampleQuality {
+    type Err = String;
+    
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        ResampleQuality::all()
+            .iter()
+            .copied()
+            .find(|quality| quality.as_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("unknown resample quality '{}', expected fast, balanced or high", s.trim()))
+    }
+}
+
+/// Subdivision of each beat into evenly spaced clicks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "gui", derive(Serialize, Deserialize))]
+pub enum Subdivision {
+    /// Main beats only
+    #[default]
+    None,
+    /// Two clicks per beat
+    Eighths,
+    /// Three clicks per beat
+    Triplets,
+    /// Four clicks per beat
+    Sixteenths,
+    /// Five clicks per beat
+    Quintuplets,
+}
+
+impl Subdivision {
+    /// Get the number of clicks per beat, including the beat itself
+    pub fn pulses_per_beat(&self) -> u32 {
+        match self {
+            Subdivision::None => 1,
+            Subdivision::Eighths => 2,
+            Subdivision::Triplets => 3,
+            Subdivision::Sixteenths => 4,
+            Subdivision::Quintuplets => 5,
+        }
+    }
+    
+    /// Get a human-readable string representation
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Subdivision::None => "None",
+            Subdivision::Eighths => "Eighths",
+            Subdivision::Triplets => "Triplets",
+            Subdivision::Sixteenths => "Sixteenths",
+            Subdivision::Quintuplets => "Quintuplets",
+        }
+    }
+    
+    /// Check whether swing applies, which needs the clicks of a beat to pair up
+    pub fn can_swing(&self) -> bool {
+        self.pulses_per_beat().is_multiple_of(2)
+    }
+    
+    /// Get the position of a click within its beat, from 0.0 (the beat) towards 1.0
+    ///
+    /// Swing pairs up the clicks and delays the second click of each pair, so in
+    /// eighths a swing of 0.67 plays the off-beat two thirds of the way through the
+    /// beat. Triplets and quintuplets have no pairs and always stay straight.
+    pub fn click_offset(&self, subdivision_index: u32, swing: f32) -> f64 {
+        let pulses = self.pulses_per_beat();
+        let straight = subdivision_index as f64 / pulses as f64;
+        if !self.can_swing() || subdivision_index.is_multiple_of(2) {
+            return straight;
+        }
+        let pair_length = 2.0 / pulses as f64;
+        straight + pair_length * (swing as f64 - STRAIGHT_SWING as f64)
+    }
+    
+    /// Get the subdivision with the given number of clicks per beat
+    pub fn from_pulses(pulses: u32) -> Option<Self> {
+        Subdivision::all().iter().copied().find(|subdivision| subdivision.pulses_per_beat() == pulses)
+    }
+    
+    /// Get all available subdivisions
+    pub fn all() -> &'static [Subdivision] {
+        &[
+            Subdivision::None,
+            Subdivision::Eighths,
+            Subdivision::Triplets,
+            Subdivision::Sixteenths,
+            Subdivision::Quintuplets,
+        ]
+    }
+}
+
+impl std::str::FromStr for Subdivision {
+    type Err = String;
+    
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Subdivision::all()
+            .iter()
+            .copied()
+            .find(|subdivision| subdivision.as_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!(
+                "Unknown subdivision '{}'. Expected one of: none, eighths, triplets, sixteenths, quintuplets",
+                s
+            ))
+    }
+}
+
+/// Swing of evenly spaced subdivision clicks
+pub const STRAIGHT_SWING: f32 = 0.5;
+
+/// Hardest swing, a dotted-eighth and sixteenth shuffle
+pub const MAX_SWING: f32 = 0.75;
+
+/// Check that a swing lies between straight and the hardest shuffle
+fn validate_swing(swing: f32) -> Result<()> {
+    if !(STRAIGHT_SWING..=MAX_SWING).contains(&swing) {
+        return Err(MetronomeError::InvalidSwing(swing));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "gui")]
+fn default_swing() -> f32 {
+    STRAIGHT_SWING
+}
+
+#[cfg(feature = "gui")]
+fn default_subdivision_sound() -> SoundType {
+    SoundType::BuiltinBeep
+}
+
+#[cfg(feature = "gui")]
+fn default_subdivision_volume() -> f32 {
+    0.5
+}
+
+#[cfg(feature = "gui")]
+fn default_accent_sound() -> SoundType {
+    SoundType::BuiltinWood
+}
+
+#[cfg(feature = "gui")]
+fn default_count_in_sound() -> SoundType {
+    SoundType::BuiltinBeep
+}
+
+/// A run of bars within a song played at one tempo and meter
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "gui", derive(Serialize, Deserialize))]
+pub struct SongSection {
+    /// Optional name such as "Verse" or "Bridge"
+    #[cfg_attr(feature = "gui", serde(default))]
+    pub label: Option<String>,
+    pub bars: u32,
+    pub bpm: Tempo,
+    #[cfg_attr(feature = "gui", serde(default))]
+    pub time_signature: TimeSignature,
+}
+
+impl SongSection {
+    pub fn new(bars: u32, bpm: impl Into<Tempo>, time_signature: TimeSignature) -> Result<Self> {
+        let section = Self {
+            label: None,
+            bars,
+            bpm: bpm.into(),
+            time_signature,
+        };
+        section.validate(&TempoRange::widest())?;
+        Ok(section)
+    }
+
+    /// Name the section; blank labels are dropped
+    pub fn with_label(mut self, label: &str) -> Self {
+        let label = label.trim();
+        self.label = (!label.is_empty()).then(|| label.to_string());
+        self
+    }
+
+    /// Number of beats in the section
+    pub fn total_beats(&self) -> u64 {
+        self.bars as u64 * self.time_signature.beats_per_measure() as u64
+    }
+
+    /// The section's label, or "Section N" for the given 0-based position
+    pub fn display_name(&self, index: usize) -> String {
+        match &self.label {
+            Some(label) => label.clone(),
+            None => format!("Section {}", index + 1),
+        }
+    }
+
+    /// Check the length and that the tempo is within the given range
+    pub fn validate(&self, tempo_range: &TempoRange) -> Result<()> {
+        if self.bars == 0 {
+            return Err(MetronomeError::InvalidSetlist("a song section needs at least one bar".to_string()));
+        }
+        tempo_range.validate(self.bpm).map(|_| ())
+    }
+}
+
+/// A setlist entry with its own tempo, meter, accents and sounds
+///
+/// Songs with sections change tempo and meter at the sections' bar lines; the
+/// song's own tempo and meter are then those of the first section.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "gui", derive(Serialize, Deserialize))]
+pub struct Song {
+    pub name: String,
+    pub bpm: Tempo,
+    #[cfg_attr(feature = "gui", serde(default))]
+    pub time_signature: TimeSignature,
+    #[cfg_attr(feature = "gui", serde(default))]
+    pub accent_pattern: Option<AccentPattern>,
+    #[cfg_attr(feature = "gui", serde(default))]
+    pub beat_sound: SoundType,
+    #[cfg_attr(feature = "gui", serde(default = "default_accent_sound"))]
+    pub accent_sound: SoundType,
+    /// Tempo and meter map; empty for songs that keep one tempo and meter throughout
+    #[cfg_attr(feature = "gui", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub sections: Vec<SongSection>,
+}
+
+impl Song {
+    pub fn new(name: &str, bpm: impl Into<Tempo>) -> Result<Self> {
+        let song = Self {
+            name: name.trim().to_string(),
+            bpm: bpm.into(),
+            time_signature: TimeSignature::default(),
+            accent_pattern: None,
+            beat_sound: SoundType::default(),
+            accent_sound: SoundType::BuiltinWood,
+            sections: Vec::new(),
+        };
+        song.validate(&TempoRange::widest())?;
+        Ok(song)
+    }
+    
+    /// Create a song from the tempo, meter, accents and sounds of a configuration
+    pub fn from_config(name: &str, config: &MetronomeConfig) -> Result<Self> {
+        let song = Self {
+            name: name.trim().to_string(),
+            bpm: config.bpm,
+            time_signature: config.time_signature,
+            accent_pattern: config.accent_pattern.clone(),
+            beat_sound: config.beat_sound.clone(),
+            accent_sound: config.accent_sound.clone(),
+            sections: Vec::new(),
+        };
+        song.validate(&config.tempo_range)?;
+        Ok(song)
+    }
+    
+    pub fn with_time_signature(mut self, time_signature: TimeSignature) -> Self {
+        self.time_signature = time_signature;
+        self
+    }
+    
+    pub fn with_accent_pattern(mut self, accent_pattern: AccentPattern) -> Self {
+        self.accent_pattern = Some(accent_pattern);
+        self
+    }
+    
+    pub fn with_sounds(mut self, beat_sound: SoundType, accent_sound: SoundType) -> Self {
+        self.beat_sound = beat_sound;
+        self.accent_sound = accent_sound;
+        self
+    }
+    
+    /// Append a section; the first section also sets the song's tempo and meter
+    pub fn with_section(mut self, section: SongSection) -> Self {
+        if self.sections.is_empty() {
+            self.bpm = section.bpm;
+            self.time_signature = section.time_signature;
+        }
+        self.sections.push(section);
+        self
+    }
+    
+    /// Total number of bars over all sections
+    pub fn total_bars(&self) -> u32 {
+        self.sections.iter().map(|section| section.bars).sum()
+    }
+    
+    /// Check the name, sections, that the accent pattern matches the time signature and that
+    /// every tempo is within the given range
+    pub fn validate(&self, tempo_range: &TempoRange) -> Result<()> {
+        if self.name.is_empty() {
+            return Err(MetronomeError::InvalidSetlist("every song needs a name".to_string()));
+        }
+        tempo_range.validate(self.bpm)?;
+        if let Some(pattern) = &self.accent_pattern {
+            if !pattern.fits(self.time_signature) {
+                return Err(MetronomeError::InvalidSetlist(format!(
+                    "'{}': the accent pattern has {} beats but {} has {}",
+                    self.name, pattern.len(), self.time_signature, self.time_signature.beats_per_measure()
+                )));
+            }
+        }
+        for (index, section) in self.sections.iter().enumerate() {
+            section.validate(tempo_range).map_err(|e| {
+                let details = match e {
+                    MetronomeError::InvalidSetlist(details) => details,
+                    other => other.to_string(),
+                };
+                MetronomeError::InvalidSetlist(format!("'{}', {}: {}", self.name, section.display_name(index), details))
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Ordered list of songs stepped through with next and previous
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "gui", derive(Serialize, Deserialize))]
+pub struct Setlist {
+    pub songs: Vec<Song>,
+    /// Index of the selected song
+    #[cfg_attr(feature = "gui", serde(skip))]
+    current: usize,
+}
+
+impl Setlist {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    pub fn with_song(mut self, song: Song) -> Self {
+        self.songs.push(song);
+        self
+    }
+    
+    pub fn len(&self) -> usize {
+        self.songs.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.songs.is_empty()
+    }
+    
+    pub fn add_song(&mut self, song: Song) {
+        self.songs.push(song);
+    }
+    
+    /// Remove a song, keeping the selection on the same song where possible
+    pub fn remove_song(&mut self, index: usize) -> Option<Song> {
+        if index >= self.songs.len() {
+            return None;
+        }
+        let song = self.songs.remove(index);
+        if index < self.current || self.current >= self.songs.len() {
+            self.current = self.current.saturating_sub(1);
+        }
+        Some(song)
+    }
+    
+    /// Overwrite a song's settings, keeping its sections
+    ///
+    /// The first section takes the new tempo and meter, so the song's own tempo and
+    /// meter stay those of its first section.
+    pub fn update_song(&mut self, index: usize, song: Song) -> Option<&Song> {
+        let current = self.songs.get_mut(index)?;
+        let mut sections = std::mem::take(&mut current.sections);
+        if let Some(first) = sections.first_mut() {
+            first.bpm = song.bpm;
+            first.time_signature = song.time_signature;
+        }
+        *current = Song { sections, ..song };
+        Some(current)
+    }
+    
+    /// Move a song to a new position, keeping the selection on the same song
+    pub fn move_song(&mut self, from: usize, to: usize) {
+        if from >= self.songs.len() || to >= self.songs.len() || from == to {
+            return;
+        }
+        let song = self.songs.remove(from);
+        self.songs.insert(to, song);
+        
+        if self.current == from {
+            self.current = to;
+        } else if from < self.current && self.current <= to {
+            self.current -= 1;
+        } else if to <= self.current && self.current < from {
+            self.current += 1;
+        }
+    }
+    
+    pub fn current_index(&self) -> usize {
+        self.current
+    }
+    
+    pub fn current_song(&self) -> Option<&Song> {
+        self.songs.get(self.current)
+    }
+    
+    /// Select a song by index
+    pub fn select(&mut self, index: usize) -> Option<&Song> {
+        if index >= self.songs.len() {
+            return None;
+        }
+        self.current = index;
+        self.current_song()
+    }
+    
+    /// Step to the next song; `None` at the end of the list
+    pub fn next_song(&mut self) -> Option<&Song> {
+        self.select(self.current + 1)
+    }
+    
+    /// Step to the previous song; `None` at the start of the list
+    pub fn previous_song(&mut self) -> Option<&Song> {
+        let index = self.current.checked_sub(1)?;
+        self.select(index)
+    }
+    
+    /// Check every song against the given tempo range
+    pub fn validate(&self, tempo_range: &TempoRange) -> Result<()> {
+        self.songs.iter().try_for_each(|song| song.validate(tempo_range))
+    }
+    
+    #[cfg(feature = "gui")]
+    pub fn save_to_file(&self, path: &std::path::Path) -> Result<()> {
+        use std::fs;
+        use crate::error::ConfigError;
+        
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| ConfigError::ParseError(format!("Failed to serialize setlist: {}", e)))?;
+        fs::write(path, json)
+            .map_err(|e| ConfigError::WriteError(format!("Failed to write setlist file: {}", e)))?;
+        Ok(())
+    }
+    
+    /// Load a setlist, checking its songs against the tempo range they will be played in
+    #[cfg(feature = "gui")]
+    pub fn load_from_file(path: &std::path::Path, tempo_range: &TempoRange) -> Result<Self> {
+        use std::fs;
+        use crate::error::ConfigError;
+        
+        let json = fs::read_to_string(path)
+            .map_err(|e| ConfigError::ReadError(format!("Failed to read setlist file: {}", e)))?;
+        let setlist: Self = serde_json::from_str(&json)
+            .map_err(|e| ConfigError::ParseError(format!("Failed to parse setlist: {}", e)))?;
+        setlist.validate(tempo_range)?;
+        Ok(setlist)
+    }
+}
+
+/// Level of medium accents relative to the main volume
+const MEDIUM_ACCENT_LEVEL: f32 = 0.6;
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "gui", derive(Serialize, Deserialize))]
+pub struct MetronomeConfig {
+    pub bpm: Tempo,
+    pub time_signature: TimeSignature,
+    pub beat_sound: SoundType,
+    pub accent_sound: SoundType,
+    pub sound_enabled: bool,
+    pub visual_enabled: bool,
+    pub accent_enabled: bool,
+    pub volume: f32,
+    #[cfg_attr(feature = "gui", serde(default))]
+    pub subdivision: Subdivision,
+    #[cfg_attr(feature = "gui", serde(default = "default_subdivision_sound"))]
+    pub subdivision_sound: SoundType,
+    /// Subdivision click level relative to `volume`
+    #[cfg_attr(feature = "gui", serde(default = "default_subdivision_volume"))]
+    pub subdivision_volume: f32,
+    /// Share of each pair of subdivision clicks taken by the first one (0.5 to 0.75)
+    #[cfg_attr(feature = "gui", serde(default = "default_swing"))]
+    pub swing: f32,
+    /// Custom per-beat accents; `None` follows the time signature
+    #[cfg_attr(feature = "gui", serde(default))]
+    pub accent_pattern: Option<AccentPattern>,
+    /// Tempo ramp applied when the metronome starts
+    #[cfg_attr(feature = "gui", serde(default))]
+    pub tempo_ramp: Option<TempoRamp>,
+    /// Speed trainer applied when the metronome starts
+    #[cfg_attr(feature = "gui", serde(default))]
+    pub speed_trainer: Option<SpeedTrainer>,
+    /// Bars to play and bars to leave silent
+    #[cfg_attr(feature = "gui", serde(default))]
+    pub gap_click: Option<GapClick>,
+    /// Random silencing of beats
+    #[cfg_attr(feature = "gui", serde(default))]
+    pub dropout: Option<BeatDropout>,
+    /// Bars counted in before playback starts
+    #[cfg_attr(feature = "gui", serde(default))]
+    pub count_in: Option<CountIn>,
+    /// Tempos that can be set
+    #[cfg_attr(feature = "gui", serde(default))]
+    pub tempo_range: TempoRange,
+    /// Time or number of bars after which playback stops
+    #[cfg_attr(feature = "gui", serde(default))]
+    pub session_limit: Option<SessionLimit>,
+    /// When tempo and meter changes made during playback take effect
+    #[cfg_attr(feature = "gui", serde(default))]
+    pub change_timing: ChangeTiming,
+    /// Interpolation used to convert sounds to the output sample rate
+    #[cfg_attr(feature = "gui", serde(default))]
+    pub resample_quality: ResampleQuality,
+}
+
+impl MetronomeConfig {
+    pub fn new(bpm: impl Into<Tempo>) -> Self {
+        Self {
+            bpm: bpm.into(),
+            time_signature: TimeSignature::default(),
+            beat_sound: SoundType::default(),
+            accent_sound: SoundType::BuiltinWood, // Different sound for accent
+            sound_enabled: true,
+            visual_enabled: true,
+            accent_enabled: true, // Accents enabled by default
+            volume: 0.7, // Default volume at 70%
+            subdivision: Subdivision::None,
+            subdivision_sound: SoundType::BuiltinBeep, // Distinct from beat and accent sounds
+            subdivision_volume: 0.5, // Subdivisions sit under the main beats
+            swing: STRAIGHT_SWING,
+            accent_pattern: None,
+            tempo_ramp: None,
+            speed_trainer: None,
+            gap_click: None,
+            dropout: None,
+            count_in: None,
+            tempo_range: TempoRange::default(),
+            session_limit: None,
+            change_timing: ChangeTiming::Immediate,
+            resample_quality: ResampleQuality::default(),
+        }
+    }
+    
+    pub fn default() -> Self {
+        Self::new(120)
+    }
+    
+    pub fn validate(&self) -> Result<()> {
+        TempoRange::new(self.tempo_range.min, self.tempo_range.max)?;
+        self.tempo_range.validate(self.bpm)?;
+        if let Some(tempo_ramp) = &self.tempo_ramp {
+            tempo_ramp.validate(&self.tempo_range)?;
+        }
+        if let Some(speed_trainer) = &self.speed_trainer {
+            speed_trainer.validate(&self.tempo_range)?;
+        }
+        validate_swing(self.swing)?;
+        if let Some(gap_click) = &self.gap_click {
+            gap_click.validate()?;
+        }
+        if let Some(count_in) = &self.count_in {
+            count_in.validate()?;
+        }
+        if let Some(session_limit) = &self.session_limit {
+            session_limit.validate()?;
+        }
+        if let Some(dropout) = &self.dropout {
+            dropout.validate()?;
+        }
+        Ok(())
+    }
+    
+    pub fn with_sound(mut self, enabled: bool) -> Self {
+        self.sound_enabled = enabled;
+        self
+    }
+    
+    pub fn with_visual(mut self, enabled: bool) -> Self {
+        self.visual_enabled = enabled;
+        self
+    }
+    
+    pub fn with_time_signature(mut self, time_signature: TimeSignature) -> Self {
+        self.time_signature = time_signature;
+        self
+    }
+    
+    pub fn with_sounds(mut self, beat_sound: SoundType, accent_sound: SoundType) -> Self {
+        self.beat_sound = beat_sound;
+        self.accent_sound = accent_sound;
+        self
+    }
+    
+    pub fn with_accent_enabled(mut self, enabled: bool) -> Self {
+        self.accent_enabled = enabled;
+        self
+    }
+    
+    pub fn with_accent_pattern(mut self, accent_pattern: AccentPattern) -> Self {
+        self.accent_pattern = Some(accent_pattern);
+        self
+    }
+    
+    pub fn with_tempo_ramp(mut self, tempo_ramp: TempoRamp) -> Self {
+        self.tempo_ramp = Some(tempo_ramp);
+        self
+    }
+    
+    pub fn with_speed_trainer(mut self, speed_trainer: SpeedTrainer) -> Self {
+        self.speed_trainer = Some(speed_trainer);
+        self
+    }
+    
+    pub fn with_gap_click(mut self, gap_click: GapClick) -> Self {
+        self.gap_click = Some(gap_click);
+        self
+    }
+    
+    pub fn with_dropout(mut self, dropout: BeatDropout) -> Self {
+        self.dropout = Some(dropout);
+        self
+    }
+    
+    pub fn with_count_in(mut self, count_in: CountIn) -> Self {
+        self.count_in = Some(count_in);
+        self
+    }
+    
+    pub fn with_session_limit(mut self, session_limit: SessionLimit) -> Self {
+        self.session_limit = Some(session_limit);
+        self
+    }
+    
+    pub fn with_change_timing(mut self, change_timing: ChangeTiming) -> Self {
+        self.change_timing = change_timing;
+        self
+    }
+    
+    pub fn with_resample_quality(mut self, resample_quality: ResampleQuality) -> Self {
+        self.resample_quality = resample_quality;
+        self
+    }
+    
+    pub fn with_tempo_range(mut self, tempo_range: TempoRange) -> Self {
+        self.tempo_range = tempo_range;
+        self
+    }
+    
+    pub fn with_volume(mut self, volume: f32) -> Self {
+        self.volume = volume.clamp(0.0, 1.0);
+        self
+    }
+    
+    pub fn with_subdivision(mut self, subdivision: Subdivision) -> Self {
+        self.subdivision = subdivision;
+        self
+    }
+    
+    pub fn with_subdivision_sound(mut self, sound: SoundType, volume: f32) -> Self {
+        self.subdivision_sound = sound;
+        self.subdivision_volume = volume.clamp(0.0, 1.0);
+        self
+    }
+    
+    pub fn with_swing(mut self, swing: f32) -> Self {
+        self.swing = swing.clamp(STRAIGHT_SWING, MAX_SWING);
+        self
+    }
+    
+    #[cfg(feature = "gui")]
+    pub fn save_to_file(&self, path: &std::path::Path) -> Result<()> {
+        use std::fs;
+        use crate::error::ConfigError;
+        
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| ConfigError::ParseError(format!("Failed to serialize config: {}", e)))?;
+        fs::write(path, json)
+            .map_err(|e| ConfigError::WriteError(format!("Failed to write config file: {}", e)))?;
+        Ok(())
+    }
+    
+    #[cfg(feature = "gui")]
+    pub fn load_from_file(path: &std::path::Path) -> Result<Self> {
+        use std::fs;
+        use crate::error::ConfigError;
+        
+        let json = fs::read_to_string(path)
+            .map_err(|e| ConfigError::ReadError(format!("Failed to read config file: {}", e)))?;
+        let config: Self = serde_json::from_str(&json)
+            .map_err(|e| ConfigError::ParseError(format!("Failed to parse config: {}", e)))?;
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Beat {
+    pub timestamp: Instant,
+    /// The grid position this beat was scheduled for
+    pub scheduled_time: Instant,
+    pub sequence_number: u64,
+    pub beat_in_measure: u32,
+    pub is_accent: bool,
+    pub bpm: Tempo,
+    pub time_signature: TimeSignature,
+    pub accent_enabled: bool,
+    pub accent_level: AccentLevel,
+    /// Position within the beat: 0 is the beat itself, higher values are subdivision clicks
+    pub subdivision_index: u32,
+    pub subdivision: Subdivision,
+    /// Whether this click falls in a silent bar of gap click training
+    pub in_gap: bool,
+    /// Whether this click was silenced by random dropout
+    pub dropped: bool,
+    /// Name of the song section playing, if the song has sections
+    pub section_name: Option<String>,
+    /// Bar within the current song section (1-based, 0 outside sections)
+    pub bar_in_section: u32,
+    /// Whether this is a count-in beat played before the real count starts
+    pub count_in: bool,
+}
+
+impl Beat {
+    pub fn new(sequence_number: u64, time_signature: TimeSignature, bpm: impl Into<Tempo>) -> Self {
+        Self::new_with_accent_setting(sequence_number, time_signature, bpm, true)
+    }
+    
+    pub fn new_with_accent_setting(sequence_number: u64, time_signature: TimeSignature, bpm: impl Into<Tempo>, accent_enabled: bool) -> Self {
+        let beats_per_measure = time_signature.beats_per_measure();
+        // Handle the case where sequence_number is 0 by treating it as beat 1
+        let effective_sequence = if sequence_number == 0 { 1 } else { sequence_number };
+        let beat_in_measure = ((effective_sequence - 1) % beats_per_measure as u64) as u32 + 1;
+        
+        let accent_level = AccentLevel::from_strength(time_signature.accent_strength(beat_in_measure));
+        let now = Instant::now();
+        
+        Self {
+            timestamp: now,
+            scheduled_time: now,
+            sequence_number: effective_sequence,
+            beat_in_measure,
+            is_accent: accent_enabled && accent_level.strength() > 0.0,
+            bpm: bpm.into(),
+            time_signature,
+            accent_enabled,
+            accent_level,
+            subdivision_index: 0,
+            subdivision: Subdivision::None,
+            in_gap: false,
+            dropped: false,
+            section_name: None,
+            bar_in_section: 0,
+            count_in: false,
+        }
+    }
+    
+    /// Override the accent level, e.g. from a custom accent pattern
+    pub fn with_accent_level(mut self, accent_level: AccentLevel) -> Self {
+        self.accent_level = accent_level;
+        self.is_accent = self.accent_enabled && !self.is_subdivision() && accent_level.strength() > 0.0;
+        self
+    }
+    
+    /// Turn this beat into the given subdivision click of the same beat
+    pub fn with_subdivision(mut self, subdivision: Subdivision, subdivision_index: u32) -> Self {
+        self.subdivision = subdivision;
+        self.subdivision_index = subdivision_index;
+        if subdivision_index > 0 {
+            self.is_accent = false;
+        }
+        self
+    }
+    
+    /// Mark this click as falling in a silent bar of gap click training
+    pub fn with_gap(mut self, in_gap: bool) -> Self {
+        self.in_gap = in_gap;
+        self
+    }
+    
+    /// Mark this click as silenced by random dropout
+    pub fn with_dropped(mut self, dropped: bool) -> Self {
+        self.dropped = dropped;
+        self
+    }
+    
+    /// Mark this beat as part of the count-in
+    pub fn with_count_in(mut self, count_in: bool) -> Self {
+        self.count_in = count_in;
+        self
+    }
+    
+    /// Place this click in a song section, or outside any with `None`
+    pub fn with_section(mut self, section: Option<(String, u32)>) -> Self {
+        (self.section_name, self.bar_in_section) = match section {
+            Some((name, bar)) => (Some(name), bar),
+            None => (None, 0),
+        };
+        self
+    }
+    
+    pub fn is_first_beat(&self) -> bool {
+        self.beat_in_measure == 1 && !self.is_subdivision()
+    }
+    
+    /// Check whether this is a subdivision click rather than a main beat
+    pub fn is_subdivision(&self) -> bool {
+        self.subdivision_index > 0
+    }
+    
+    /// Check whether this click is silent: a main beat muted by the accent pattern,
+    /// any click in a silent bar of gap click training, or a randomly dropped click
+    pub fn is_muted(&self) -> bool {
+        self.in_gap || self.dropped || (self.accent_level == AccentLevel::Mute && !self.is_subdivision())
+    }
+    
+    /// How late this beat was produced relative to its scheduled grid position
+    pub fn lateness(&self) -> Duration {
+        self.timestamp.saturating_duration_since(self.scheduled_time)
+    }
+    
+    pub fn get_accent_strength(&self) -> f32 {
+        if !self.accent_enabled || self.is_subdivision() {
+            return 0.0; // No accents when disabled or between beats
+        }
+        
+        self.accent_level.strength()
+    }
+    
+    pub fn is_strong_beat(&self) -> bool {
+        self.get_accent_strength() >= 1.0
+    }
+    
+    pub fn is_medium_beat(&self) -> bool {
+        let strength = self.get_accent_strength();
+        strength > 0.0 && strength < 1.0
+    }
+    
+    pub fn is_weak_beat(&self) -> bool {
+        self.get_accent_strength() == 0.0
+    }
+}
+
+/// Metronome state structure for thread-safe access
+#[derive(Debug, Clone)]
+pub struct MetronomeState {
+    pub bpm: Tempo,
+    /// Tempos that `update_bpm` accepts
+    pub tempo_range: TempoRange,
+    pub time_signature: TimeSignature,
+    pub beat_sound: SoundType,
+    pub accent_sound: SoundType,
+    pub is_running: bool,
+    pub start_time: Option<Instant>,
+    /// When playback was paused, while paused; the position and clock are kept
+    pub paused_at: Option<Instant>,
+    pub beat_count: u64,
+    pub current_beat_in_measure: u32,
+    pub accent_enabled: bool,
+    pub volume: f32,
+    pub subdivision: Subdivision,
+    pub subdivision_sound: SoundType,
+    pub subdivision_volume: f32,
+    /// Share of each pair of subdivision clicks taken by the first one (0.5 = straight)
+    pub swing: f32,
+    /// Position of the last click within the current beat (0 = the beat itself)
+    pub current_subdivision: u32,
+    /// Grid position from which upcoming beats are scheduled
+    pub schedule_anchor: Option<Instant>,
+    /// Beat count at the time `schedule_anchor` was set
+    pub schedule_anchor_beat: u64,
+    /// Custom per-beat accents; `None` follows the time signature
+    pub accent_pattern: Option<AccentPattern>,
+    /// Tempo ramp in progress; cleared once the target tempo is reached
+    pub tempo_ramp: Option<TempoRamp>,
+    /// Beat count at which the tempo ramp started
+    pub ramp_start_beat: u64,
+    /// Speed trainer in progress
+    pub speed_trainer: Option<SpeedTrainer>,
+    /// Number of tempo changes the speed trainer has made so far
+    pub trainer_step: u32,
+    /// Beat count at which the current trainer step started
+    pub trainer_step_start_beat: u64,
+    /// Gap click training in effect
+    pub gap_click: Option<GapClick>,
+    /// Bar (0-based, counted from the start) at which the gap click cycle begins
+    pub gap_start_bar: u64,
+    /// Random beat dropout in effect
+    pub dropout: Option<BeatDropout>,
+    /// Count-in played each time the metronome starts
+    pub count_in: Option<CountIn>,
+    /// Count-in beats still to play before the real count starts
+    pub count_in_beats_left: u64,
+    /// Time or number of bars after which no more beats are scheduled
+    pub session_limit: Option<SessionLimit>,
+    /// When `change_bpm` and `change_time_signature` take effect
+    pub change_timing: ChangeTiming,
+    /// Tempo waiting for the next downbeat
+    pub pending_bpm: Option<Tempo>,
+    /// Time signature waiting for the next downbeat
+    pub pending_time_signature: Option<TimeSignature>,
+    /// Tempo and meter map of the song playing; empty when there is none
+    pub sections: Vec<SongSection>,
+    /// Index of the song section playing
+    pub section_index: usize,
+    /// Beat count at which the current section started; bars and beats within
+    /// bars are counted from here
+    pub section_start_beat: u64,
+    /// Bar (0-based, counted from the start) at which the current section started
+    pub section_start_bar: u64,
+    /// Generator deciding which clicks drop out
+    dropout_rng: fastrand::Rng,
+}
+
+impl MetronomeState {
+    pub fn new(config: &MetronomeConfig) -> Self {
+        Self {
+            bpm: config.bpm,
+            tempo_range: config.tempo_range,
+            time_signature: config.time_signature,
+            beat_sound: config.beat_sound.clone(),
+            accent_sound: config.accent_sound.clone(),
+            is_running: false,
+            start_time: None,
+            paused_at: None,
+            beat_count: 0,
+            current_beat_in_measure: 1,
+            accent_enabled: config.accent_enabled,
+            volume: config.volume,
+            subdivision: config.subdivision,
+            subdivision_sound: config.subdivision_sound.clone(),
+            subdivision_volume: config.subdivision_volume,
+            swing: config.swing,
+            current_subdivision: 0,
+            schedule_anchor: None,
+            schedule_anchor_beat: 0,
+            accent_pattern: config.accent_pattern.clone()
+                .filter(|pattern| pattern.fits(config.time_signature)),
+            tempo_ramp: config.tempo_ramp,
+            ramp_start_beat: 0,
+            speed_trainer: config.speed_trainer,
+            trainer_step: 0,
+            trainer_step_start_beat: 0,
+            gap_click: config.gap_click,
+            gap_start_bar: 0,
+            dropout: config.dropout,
+            dropout_rng: config.dropout.map(|dropout| dropout.create_rng()).unwrap_or_default(),
+            count_in: config.count_in.clone(),
+            count_in_beats_left: 0,
+            session_limit: config.session_limit,
+            change_timing: config.change_timing,
+            pending_bpm: None,
+            pending_time_signature: None,
+            sections: Vec::new(),
+            section_index: 0,
+            section_start_beat: 0,
+            section_start_bar: 0,
+        }
+    }
+    
+    pub fn get_elapsed_time(&self) -> Duration {
+        match (self.start_time, self.paused_at) {
+            // The clock stands still while paused
+            (Some(start), Some(paused_at)) => paused_at.saturating_duration_since(start),
+            (Some(start), None) => start.elapsed(),
+            (None, _) => Duration::from_secs(0),
+        }
+    }
+    
+    pub fn get_interval(&self) -> Duration {
+        self.calculate_beat_interval()
+    }
+    
+    /// Calculate beat interval based on time signature and BPM
+    pub fn calculate_beat_interval(&self) -> Duration {
+        // The tempo counts beats in the time signature's beat unit
+        Duration::from_secs_f64(60.0 / self.get_current_tempo())
+    }
+    
+    /// Get the tempo of the current beat, following the tempo ramp if one is running
+    pub fn get_current_tempo(&self) -> f64 {
+        match (&self.tempo_ramp, self.get_ramp_progress()) {
+            (Some(ramp), Some(progress)) => ramp.tempo_at(progress),
+            _ => self.bpm.bpm(),
+        }
+    }
+    
+    /// Get how far through the tempo ramp the current beat is (0.0 to 1.0)
+    pub fn get_ramp_progress(&self) -> Option<f64> {
+        let ramp = self.tempo_ramp.as_ref()?;
+        let beats_done = self.beat_count.saturating_sub(self.ramp_start_beat);
+        Some((beats_done as f64 / ramp.total_beats(self.time_signature) as f64).min(1.0))
+    }
+    
+    /// Get the tempo the running ramp is heading towards
+    pub fn get_target_bpm(&self) -> Option<Tempo> {
+        self.tempo_ramp.as_ref().map(|ramp| ramp.target_bpm)
+    }
+    
+    /// Start a tempo ramp from the next beat, or cancel the current one with `None`
+    ///
+    /// A ramp replaces any running speed trainer or song sections.
+    pub fn update_tempo_ramp(&mut self, tempo_ramp: Option<TempoRamp>) {
+        let last_click_time = self.get_last_click_time();
+        if let Some(ramp) = &tempo_ramp {
+            self.bpm = ramp.start_bpm;
+            self.speed_trainer = None;
+            self.sections.clear();
+        }
+        self.tempo_ramp = tempo_ramp;
+        self.ramp_start_beat = self.beat_count;
+        self.anchor_schedule_at(last_click_time);
+    }
+    
+    /// Start a speed trainer from the next beat, or stop it with `None`
+    ///
+    /// A speed trainer replaces any running tempo ramp or song sections.
+    pub fn update_speed_trainer(&mut self, speed_trainer: Option<SpeedTrainer>) {
+        let last_click_time = self.get_last_click_time();
+        if let Some(trainer) = &speed_trainer {
+            self.bpm = trainer.start_bpm;
+            self.tempo_ramp = None;
+            self.sections.clear();
+        }
+        self.speed_trainer = speed_trainer;
+        self.trainer_step = 0;
+        self.trainer_step_start_beat = self.beat_count;
+        self.anchor_schedule_at(last_click_time);
+    }
+    
+    /// Move the tempo ramp on after a main beat, finishing it once the target is reached
+    ///
+    /// Returns whether the tempo may have changed.
+    fn advance_tempo_ramp(&mut self) -> bool {
+        let Some(ramp) = self.tempo_ramp else {
+            return false;
+        };
+        self.bpm = Tempo::from(self.get_current_tempo());
+        if self.get_ramp_progress().is_some_and(|progress| progress >= 1.0) {
+            self.bpm = ramp.target_bpm;
+            self.tempo_ramp = None;
+        }
+        true
+    }
+    
+    /// Step the speed trainer on once the current step's bars have been played
+    ///
+    /// Called before each main beat so the new tempo starts on the downbeat of the
+    /// next step, after the old tempo's last interval. Returns whether the tempo changed.
+    fn advance_speed_trainer(&mut self) -> bool {
+        let Some(trainer) = self.speed_trainer else {
+            return false;
+        };
+        let beats_in_step = self.beat_count.saturating_sub(self.trainer_step_start_beat);
+        if beats_in_step < trainer.beats_per_step(self.time_signature) {
+            return false;
+        }
+        
+        self.trainer_step_start_beat = self.beat_count;
+        match trainer.next_bpm(self.bpm) {
+            Some(next_bpm) => {
+                self.bpm = next_bpm;
+                self.trainer_step += 1;
+                true
+            }
+            None => false,
+        }
+    }
+    
+    /// Play a song's sections from the next beat, or drop them with an empty list
+    ///
+    /// The first section starts a new bar and takes over the tempo from any ramp
+    /// or trainer in progress.
+    pub fn update_sections(&mut self, sections: Vec<SongSection>) {
+        self.sections = sections;
+        if self.sections.is_empty() {
+            return;
+        }
+        let last_click_time = self.get_last_click_time();
+        self.tempo_ramp = None;
+        self.speed_trainer = None;
+        self.section_start_bar = self.get_next_bar_index();
+        self.enter_section(0);
+        self.anchor_schedule_at(last_click_time);
+    }
+    
+    /// Switch tempo and meter to the given section, starting it from the next beat
+    fn enter_section(&mut self, index: usize) {
+        let section = &self.sections[index];
+        self.section_index = index;
+        self.bpm = section.bpm;
+        self.time_signature = section.time_signature;
+        self.section_start_beat = self.beat_count;
+    }
+    
+    /// Move on to the next section once the current one's bars have been played
+    ///
+    /// Called before each main beat so the change lands exactly on the bar line;
+    /// after the last section the song starts over. Returns whether a section began.
+    fn advance_section(&mut self) -> bool {
+        let Some(section) = self.sections.get(self.section_index) else {
+            return false;
+        };
+        if self.beat_count.saturating_sub(self.section_start_beat) < section.total_beats() {
+            return false;
+        }
+        self.section_start_bar += section.bars as u64;
+        self.enter_section((self.section_index + 1) % self.sections.len());
+        true
+    }
+    
+    /// Get the section playing, if the song has sections
+    pub fn get_current_section(&self) -> Option<&SongSection> {
+        self.sections.get(self.section_index)
+    }
+    
+    /// Get the index of the section playing and the bar within it (1-based)
+    pub fn get_section_position(&self) -> Option<(usize, u32)> {
+        self.get_current_section()?;
+        let beats_in_section = self.beat_count.saturating_sub(self.section_start_beat + 1);
+        let bar = beats_in_section / self.time_signature.beats_per_measure() as u64 + 1;
+        Some((self.section_index, bar as u32))
+    }
+    
+    /// Get the bar (0-based, counted from the start) of the current beat
+    fn get_bar_index(&self) -> u64 {
+        let beats_in_section = self.beat_count.saturating_sub(self.section_start_beat + 1);
+        self.section_start_bar + beats_in_section / self.time_signature.beats_per_measure() as u64
+    }
+    
+    /// Get the bar (0-based, counted from the start) that the next downbeat begins
+    fn get_next_bar_index(&self) -> u64 {
+        let beats_in_section = self.beat_count.saturating_sub(self.section_start_beat);
+        self.section_start_bar + beats_in_section.div_ceil(self.time_signature.beats_per_measure() as u64)
+    }
+    
+    /// Get the accent pattern for the current time signature
+    pub fn get_accent_pattern(&self) -> Vec<bool> {
+        (1..=self.time_signature.beats_per_measure())
+            .map(|beat| self.get_accent_level(beat).strength() > 0.0)
+            .collect()
+    }
+    
+    /// Get the per-beat accent levels in effect, custom or derived from the time signature
+    pub fn get_effective_accent_pattern(&self) -> AccentPattern {
+        self.accent_pattern
+            .clone()
+            .filter(|pattern| pattern.fits(self.time_signature))
+            .unwrap_or_else(|| AccentPattern::default_for(self.time_signature))
+    }
+    
+    /// Start gap click training from the next downbeat, or end it with `None`
+    pub fn update_gap_click(&mut self, gap_click: Option<GapClick>) {
+        self.gap_click = gap_click;
+        self.gap_start_bar = self.get_next_bar_index();
+    }
+    
+    /// Get the bar of the current beat relative to the start of gap click training
+    fn get_gap_bar(&self) -> Option<u64> {
+        self.gap_click?;
+        if self.beat_count == 0 {
+            return None;
+        }
+        self.get_bar_index().checked_sub(self.gap_start_bar)
+    }
+    
+    /// Check whether the current beat falls in a silent bar of gap click training
+    pub fn is_in_gap(&self) -> bool {
+        match (self.gap_click, self.get_gap_bar()) {
+            (Some(gap_click), Some(bar)) => gap_click.is_silent_bar(bar),
+            _ => false,
+        }
+    }
+    
+    /// Get whether the current bar is silent and its 1-based position in the play or mute section
+    pub fn get_gap_position(&self) -> Option<(bool, u32)> {
+        let gap_click = self.gap_click?;
+        let bar = self.get_gap_bar()?;
+        Some((gap_click.is_silent_bar(bar), gap_click.bar_in_section(bar)))
+    }
+    
+    /// Set random beat dropout, or turn it off with `None`
+    ///
+    /// The generator restarts from the dropout's seed, so seeded runs repeat exactly.
+    pub fn update_dropout(&mut self, dropout: Option<BeatDropout>) {
+        if let Some(dropout) = &dropout {
+            self.dropout_rng = dropout.create_rng();
+        }
+        self.dropout = dropout;
+    }
+    
+    /// Set the count-in played from the next start, or play none with `None`
+    pub fn update_count_in(&mut self, count_in: Option<CountIn>) {
+        self.count_in = count_in;
+    }
+    
+    /// Check whether the metronome is still counting in
+    pub fn is_counting_in(&self) -> bool {
+        self.count_in_beats_left > 0
+    }
+    
+    /// Get the position in its bar of the count-in beat last played, while counting in
+    ///
+    /// The last count-in beat still counts until the real first beat is played.
+    pub fn get_count_in_beat(&self) -> Option<u32> {
+        let just_finished = self.count_in.is_some() && !self.is_counting_in() && self.beat_count == 0;
+        let counting = self.is_counting_in() && self.beat_count > 0;
+        (self.is_running && (counting || just_finished)).then_some(self.current_beat_in_measure)
+    }
+    
+    /// Play the next count-in beat at its scheduled time
+    ///
+    /// Count-in beats have no subdivisions, gaps or dropouts. After the last one the
+    /// beat count starts over, so the real first beat is beat 1 of bar 1.
+    fn increment_count_in_at(&mut self, now: Instant, scheduled_time: Option<Instant>) -> Beat {
+        self.beat_count += 1;
+        self.current_subdivision = 0;
+        let beats_per_measure = self.time_signature.beats_per_measure() as u64;
+        self.current_beat_in_measure = ((self.beat_count - 1) % beats_per_measure) as u32 + 1;
+        
+        let mut beat = Beat::new_with_accent_setting(self.beat_count, self.time_signature, self.bpm, self.accent_enabled)
+            .with_count_in(true);
+        beat.timestamp = now;
+        beat.scheduled_time = scheduled_time.unwrap_or(now);
+        
+        self.count_in_beats_left -= 1;
+        if self.count_in_beats_left == 0 {
+            self.beat_count = 0;
+            self.anchor_schedule_at(scheduled_time);
+        }
+        beat
+    }
+    
+    /// Set the time or number of bars after which playback stops, or remove the limit with `None`
+    pub fn update_session_limit(&mut self, session_limit: Option<SessionLimit>) {
+        self.session_limit = session_limit;
+    }
+    
+    /// Get the number of complete bars played since the start, not counting the count-in
+    pub fn get_bars_played(&self) -> u64 {
+        if self.is_counting_in() {
+            return 0;
+        }
+        let beats_in_section = self.beat_count.saturating_sub(self.section_start_beat);
+        self.section_start_bar + beats_in_section / self.time_signature.beats_per_measure() as u64
+    }
+    
+    /// Check whether the session limit has been reached, after which no more beats are due
+    ///
+    /// A bar limit is reached once its last bar has played in full, subdivision clicks
+    /// included; a time limit once the next click would fall after it.
+    pub fn is_session_complete(&self) -> bool {
+        if !self.is_running || self.is_counting_in() {
+            return false;
+        }
+        match self.session_limit {
+            Some(SessionLimit::Duration(duration)) => {
+                match (self.start_time, self.get_next_click_time()) {
+                    (Some(start_time), Some(next_click_time)) => next_click_time >= start_time + duration,
+                    _ => false,
+                }
+            }
+            Some(SessionLimit::Bars(bars)) => self.has_played_bars(bars),
+            None => false,
+        }
+    }
+    
+    /// Check whether `bars` bars have played in full since the start, subdivision clicks
+    /// included; this stays true after stopping until the metronome starts again
+    fn has_played_bars(&self, bars: u32) -> bool {
+        self.beat_count > 0
+            && !self.is_counting_in()
+            && self.get_next_click_position().1 == 0
+            && self.get_bars_played() >= bars as u64
+    }
+    
+    /// Get how much of the session limit is left: the time, or the bars including the current one
+    ///
+    /// A bar limit that has been reached leaves no bars, also once playback has stopped.
+    pub fn get_session_remaining(&self) -> Option<SessionLimit> {
+        let remaining = match self.session_limit? {
+            SessionLimit::Duration(duration) => SessionLimit::Duration(duration.saturating_sub(self.get_elapsed_time())),
+            SessionLimit::Bars(bars) if self.has_played_bars(bars) => SessionLimit::Bars(0),
+            SessionLimit::Bars(bars) => {
+                let started = (self.is_running || self.is_paused()) && self.beat_count > 0 && !self.is_counting_in();
+                let bars_before_current = if started { self.get_bar_index() } else { 0 };
+                SessionLimit::Bars(bars.saturating_sub(bars_before_current as u32))
+            }
+        };
+        Some(remaining)
+    }
+    
+    /// Get the accent level of a beat (1-based) from the custom pattern or the time signature
+    pub fn get_accent_level(&self, beat_in_measure: u32) -> AccentLevel {
+        // A song section in another meter falls back to that meter's accents
+        match &self.accent_pattern {
+            Some(pattern) if pattern.fits(self.time_signature) => pattern.level(beat_in_measure),
+            _ => AccentLevel::from_strength(self.time_signature.accent_strength(beat_in_measure)),
+        }
+    }
+    
+    /// Check if the current beat should be accented based on time signature
+    pub fn is_accent_beat(&self) -> bool {
+        if !self.accent_enabled {
+            return false; // No accents when disabled
+        }
+        let pattern = self.get_accent_pattern();
+        let beat_index = (self.current_beat_in_measure - 1) as usize;
+        pattern.get(beat_index).copied().unwrap_or(false)
+    }
+    
+    /// Get the accent strength (0.0 = no accent, 1.0 = strongest accent)
+    pub fn get_accent_strength(&self) -> f32 {
+        if !self.accent_enabled {
+            return 0.0; // No accents when disabled
+        }
+        self.get_accent_level(self.current_beat_in_measure).strength()
+    }
+    
+    /// Get the interval between consecutive clicks, including subdivision clicks, without swing
+    pub fn get_subdivision_interval(&self) -> Duration {
+        self.calculate_beat_interval() / self.get_click_subdivision().pulses_per_beat()
+    }
+    
+    /// Get the subdivision clicked, which falls back to the notes within each beat
+    ///
+    /// With a beat unit such as a dotted quarter in 6/8, the eighths are played as
+    /// subdivision clicks unless another subdivision is chosen.
+    pub fn get_click_subdivision(&self) -> Subdivision {
+        match self.subdivision {
+            Subdivision::None => self.time_signature.note_subdivision(),
+            subdivision => subdivision,
+        }
+    }
+    
+    /// Get the interval from the given click of a beat to the next click, with swing
+    ///
+    /// With swing the clicks of a beat are unevenly spaced, while the clicks on the
+    /// beat itself stay exactly one beat interval apart.
+    pub fn get_click_interval(&self, subdivision_index: u32) -> Duration {
+        let start = self.get_click_offset(subdivision_index);
+        let end = if subdivision_index + 1 < self.get_click_subdivision().pulses_per_beat() {
+            self.get_click_offset(subdivision_index + 1)
+        } else {
+            1.0
+        };
+        Duration::from_secs_f64(self.calculate_beat_interval().as_secs_f64() * (end - start))
+    }
+    
+    /// Get the position of a click within its beat (0.0 to 1.0), with swing
+    pub fn get_click_offset(&self, subdivision_index: u32) -> f64 {
+        self.get_click_subdivision().click_offset(subdivision_index, self.swing)
+    }
+    
+    /// Calculate the expected time for the next click, including subdivision clicks
+    ///
+    /// Returns `None` once the session limit has been reached.
+    pub fn get_next_beat_time(&self) -> Option<Instant> {
+        if self.is_session_complete() {
+            return None;
+        }
+        self.get_next_click_time()
+    }
+    
+    /// Calculate the scheduled time of the next click, ignoring the session limit
+    fn get_next_click_time(&self) -> Option<Instant> {
+        let (sequence_number, subdivision_index) = self.get_next_click_position();
+        self.get_scheduled_click_time(sequence_number, subdivision_index)
+    }
+    
+    /// Get the beat number and subdivision index of the next click
+    pub fn get_next_click_position(&self) -> (u64, u32) {
+        if self.beat_count > 0 && !self.is_counting_in() && self.current_subdivision + 1 < self.get_click_subdivision().pulses_per_beat() {
+            (self.beat_count, self.current_subdivision + 1)
+        } else {
+            (self.beat_count + 1, 0)
+        }
+    }
+    
+    /// Calculate the scheduled time of the given beat (1-based) from the schedule anchor
+    ///
+    /// Beats are placed at `anchor + n * interval` rather than one interval after the
+    /// previous beat fired, so polling latency never accumulates into drift.
+    pub fn get_scheduled_beat_time(&self, sequence_number: u64) -> Option<Instant> {
+        self.get_scheduled_click_time(sequence_number, 0)
+    }
+    
+    /// Calculate the scheduled time of a subdivision click within the given beat
+    pub fn get_scheduled_click_time(&self, sequence_number: u64, subdivision_index: u32) -> Option<Instant> {
+        let anchor = self.schedule_anchor?;
+        let beats_since_anchor = sequence_number.saturating_sub(self.schedule_anchor_beat) as f64
+            + self.get_click_offset(subdivision_index);
+        let offset = self.calculate_beat_interval().as_secs_f64() * beats_since_anchor;
+        Some(anchor + Duration::from_secs_f64(offset))
+    }
+    
+    /// Check whether the next scheduled beat is due at `now`
+    pub fn is_beat_due(&self, now: Instant) -> bool {
+        if !self.is_running {
+            return false;
+        }
+        match self.get_next_beat_time() {
+            Some(next_beat_time) => now >= next_beat_time,
+            None => false,
+        }
+    }
+    
+    /// Get the scheduled time of the most recent click
+    fn get_last_click_time(&self) -> Option<Instant> {
+        self.get_scheduled_click_time(self.beat_count, self.current_subdivision)
+    }
+    
+    /// Re-anchor the schedule so the grid continues from the given click time
+    ///
+    /// Called after the interval changes so that clicks already played keep their
+    /// positions and the new tempo takes over from the last click.
+    fn anchor_schedule_at(&mut self, last_click_time: Option<Instant>) {
+        if let Some(last_click_time) = last_click_time {
+            let fraction = self.get_click_offset(self.current_subdivision);
+            let offset = Duration::from_secs_f64(self.calculate_beat_interval().as_secs_f64() * fraction);
+            self.schedule_anchor = Some(last_click_time.checked_sub(offset).unwrap_or(last_click_time));
+            self.schedule_anchor_beat = self.beat_count;
+        }
+    }
+    
+    /// Get timing accuracy (how close we are to the expected beat time)
+    pub fn get_timing_accuracy(&self, actual_beat_time: Instant) -> Option<Duration> {
+        if let Some(expected_time) = self.get_next_beat_time() {
+            if actual_beat_time >= expected_time {
+                Some(actual_beat_time - expected_time)
+            } else {
+                Some(expected_time - actual_beat_time)
+            }
+        } else {
+            None
+        }
+    }
+    
+    pub fn increment_beat(&mut self) -> Beat {
+        self.increment_beat_at(Instant::now())
+    }
+    
+    /// Advance to the next click, recording `now` as the time it was produced
+    ///
+    /// With a subdivision set, this alternates between main beats and the
+    /// subdivision clicks between them; only main beats advance `beat_count`.
+    pub fn increment_beat_at(&mut self, now: Instant) -> Beat {
+        let (sequence_number, subdivision_index) = self.get_next_click_position();
+        let scheduled_time = self.get_scheduled_click_time(sequence_number, subdivision_index);
+        if self.is_counting_in() {
+            return self.increment_count_in_at(now, scheduled_time);
+        }
+        
+        // Queued changes and new song sections take effect on the downbeat, after the old
+        // tempo's last interval
+        let changes_applied = subdivision_index == 0 && self.apply_pending_changes_at_bar_line();
+        let section_changed = subdivision_index == 0 && self.advance_section();
+        let trainer_stepped = subdivision_index == 0 && self.advance_speed_trainer();
+        if subdivision_index == 0 {
+            self.beat_count += 1;
+            let beats_per_measure = self.time_signature.beats_per_measure();
+            let beats_in_section = self.beat_count - self.section_start_beat - 1;
+            self.current_beat_in_measure = (beats_in_section % beats_per_measure as u64) as u32 + 1;
+        }
+        self.current_subdivision = subdivision_index;
+        
+        // Every click, subdivisions included, drops out on its own
+        let dropped = match self.dropout {
+            Some(dropout) => dropout.should_drop(self.current_beat_in_measure, subdivision_index, &mut self.dropout_rng),
+            None => false,
+        };
+        
+        // Queued changes, sections, ramps and trainer steps change the tempo, so the grid
+        // restarts at this beat
+        if subdivision_index == 0 {
+            let tempo_changed = changes_applied | section_changed | trainer_stepped
+                | self.advance_tempo_ramp();
+            if tempo_changed {
+                self.anchor_schedule_at(scheduled_time);
+            }
+        }
+        
+        let mut beat = Beat::new_with_accent_setting(self.beat_count, self.time_signature, self.bpm, self.accent_enabled)
+            .with_accent_level(self.get_accent_level(self.current_beat_in_measure))
+            .with_subdivision(self.get_click_subdivision(), subdivision_index)
+            .with_gap(self.is_in_gap())
+            .with_dropped(dropped)
+            .with_section(self.get_section_position()
+                .map(|(index, bar)| (self.sections[index].display_name(index), bar)));
+        // Bars restart at section boundaries, so the position comes from the state
+        beat.beat_in_measure = self.current_beat_in_measure;
+        beat.timestamp = now;
+        beat.scheduled_time = scheduled_time.unwrap_or(now);
+        beat
+    }
+    
+    /// Get the sound and volume to play for the given beat
+    ///
+    /// Strong beats use the accent sound, medium beats the accent sound at a lower level
+    /// and weak beats the beat sound. Muted beats come back with a volume of zero.
+    pub fn get_sound_for_beat(&self, beat: &Beat) -> (SoundType, f32) {
+        if beat.count_in {
+            // The count-in sound stresses the first beat of each bar
+            let sound = self.count_in.as_ref().map_or(SoundType::BuiltinBeep, |count_in| count_in.sound.clone());
+            let volume = if beat.is_first_beat() { self.volume } else { self.volume * MEDIUM_ACCENT_LEVEL };
+            (sound, volume)
+        } else if beat.is_subdivision() {
+            (self.subdivision_sound.clone(), self.volume * self.subdivision_volume)
+        } else if beat.is_muted() {
+            (self.beat_sound.clone(), 0.0)
+        } else if beat.is_strong_beat() {
+            (self.accent_sound.clone(), self.volume)
+        } else if beat.is_medium_beat() {
+            (self.accent_sound.clone(), self.volume * MEDIUM_ACCENT_LEVEL)
+        } else {
+            (self.beat_sound.clone(), self.volume)
+        }
+    }
+    
+    /// Set a custom accent pattern, or return to the time signature's pattern with `None`
+    pub fn update_accent_pattern(&mut self, accent_pattern: Option<AccentPattern>) -> Result<()> {
+        if let Some(pattern) = &accent_pattern {
+            if !pattern.fits(self.time_signature) {
+                return Err(MetronomeError::InvalidAccentPattern(format!(
+                    "{} beats do not match {} time", pattern.len(), self.time_signature
+                )));
+            }
+        }
+        self.accent_pattern = accent_pattern;
+        Ok(())
+    }
+    
+    /// Start from the beginning; a paused metronome starts over rather than resuming
+    pub fn start(&mut self) {
+        if !self.is_running {
+            let now = Instant::now();
+            self.start_time = Some(now);
+            self.paused_at = None;
+            self.schedule_anchor = Some(now);
+            self.schedule_anchor_beat = 0;
+            self.beat_count = 0;
+            self.current_beat_in_measure = 1;
+            self.current_subdivision = 0;
+            self.ramp_start_beat = 0;
+            if let Some(ramp) = &self.tempo_ramp {
+                self.bpm = ramp.start_bpm;
+            }
+            self.trainer_step = 0;
+            self.trainer_step_start_beat = 0;
+            self.gap_start_bar = 0;
+            if let Some(dropout) = &self.dropout {
+                self.dropout_rng = dropout.create_rng();
+            }
+            if let Some(trainer) = &self.speed_trainer {
+                self.bpm = trainer.start_bpm;
+            }
+            self.section_start_beat = 0;
+            self.section_start_bar = 0;
+            if !self.sections.is_empty() {
+                self.enter_section(0);
+            }
+            self.count_in_beats_left = self.count_in.as_ref()
+                .map_or(0, |count_in| count_in.total_beats(self.time_signature));
+            self.is_running = true;
+        }
+    }
+    
+    pub fn stop(&mut self) {
+        // There is no bar line left to wait for
+        self.take_pending_changes();
+        self.is_running = false;
+        self.start_time = None;
+        self.paused_at = None;
+        self.schedule_anchor = None;
+    }
+    
+    /// Check whether playback is paused, as opposed to stopped
+    pub fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+    
+    pub fn pause(&mut self) {
+        self.pause_at(Instant::now());
+    }
+    
+    /// Pause playback at `now`, keeping the bar and beat position and the elapsed time
+    pub fn pause_at(&mut self, now: Instant) {
+        if self.is_running {
+            self.is_running = false;
+            self.paused_at = Some(now);
+        }
+    }
+    
+    pub fn resume(&mut self) {
+        self.resume_at(Instant::now());
+    }
+    
+    /// Resume a paused metronome at `now`, continuing from the next click
+    ///
+    /// The schedule and the elapsed time move on by the length of the pause, so the
+    /// next click comes as long after resuming as it was still due when pausing.
+    pub fn resume_at(&mut self, now: Instant) {
+        if let Some(paused_at) = self.paused_at.take() {
+            let paused_for = now.saturating_duration_since(paused_at);
+            self.start_time = self.start_time.map(|start_time| start_time + paused_for);
+            self.schedule_anchor = self.schedule_anchor.map(|anchor| anchor + paused_for);
+            self.is_running = true;
+        }
+    }
+    
+    pub fn update_bpm(&mut self, bpm: impl Into<Tempo>) -> Result<()> {
+        let bpm = self.tempo_range.validate(bpm.into())?;
+        let last_click_time = self.get_last_click_time();
+        self.bpm = bpm;
+        self.pending_bpm = None;
+        // Setting a tempo directly takes over from any ramp, trainer or song sections
+        self.tempo_ramp = None;
+        self.speed_trainer = None;
+        self.sections.clear();
+        self.anchor_schedule_at(last_click_time);
+        Ok(())
+    }
+    
+    /// Set the tempos that can be set, bringing the current tempo into the new range
+    pub fn update_tempo_range(&mut self, tempo_range: TempoRange) -> Result<()> {
+        let tempo_range = TempoRange::new(tempo_range.min, tempo_range.max)?;
+        self.tempo_range = tempo_range;
+        self.pending_bpm = self.pending_bpm.map(|bpm| tempo_range.clamp(bpm));
+        if !tempo_range.contains(self.bpm) {
+            self.update_bpm(tempo_range.clamp(self.bpm))?;
+        }
+        Ok(())
+    }
+    
+    pub fn update_time_signature(&mut self, time_signature: TimeSignature) {
+        let last_click_time = self.get_last_click_time();
+        let old_pulses = self.get_click_subdivision().pulses_per_beat();
+        // The new meter starts a bar at the next beat, after the bars begun in the old one
+        if !self.is_counting_in() {
+            self.section_start_bar = self.get_next_bar_index();
+            self.section_start_beat = self.beat_count;
+        }
+        self.time_signature = time_signature;
+        self.pending_time_signature = None;
+        // The beat unit can change the notes clicked within each beat
+        self.current_subdivision = self.current_subdivision * self.get_click_subdivision().pulses_per_beat() / old_pulses;
+        self.sections.clear();
+        // A custom accent pattern only survives if the measure length is unchanged
+        if self.accent_pattern.as_ref().is_some_and(|pattern| !pattern.fits(time_signature)) {
+            self.accent_pattern = None;
+        }
+        self.anchor_schedule_at(last_click_time);
+    }
+    
+    /// Set whether `change_bpm` and `change_time_signature` wait for the next bar line
+    ///
+    /// Changes already queued still wait for it.
+    pub fn update_change_timing(&mut self, change_timing: ChangeTiming) {
+        self.change_timing = change_timing;
+    }
+    
+    /// Change the tempo now or at the next downbeat, depending on `change_timing`
+    pub fn change_bpm(&mut self, bpm: impl Into<Tempo>) -> Result<()> {
+        let bpm = self.tempo_range.validate(bpm.into())?;
+        if self.should_wait_for_bar_line() {
+            self.pending_bpm = Some(bpm);
+            Ok(())
+        } else {
+            self.update_bpm(bpm)
+        }
+    }
+    
+    /// Change the time signature now or at the next downbeat, depending on `change_timing`
+    ///
+    /// Queued, the bar in progress is played out and the new meter starts at its beat 1.
+    pub fn change_time_signature(&mut self, time_signature: TimeSignature) {
+        if self.should_wait_for_bar_line() {
+            self.pending_time_signature = Some(time_signature);
+        } else {
+            self.update_time_signature(time_signature);
+        }
+    }
+    
+    /// Check whether tempo or meter changes are waiting for the next downbeat
+    pub fn has_pending_changes(&self) -> bool {
+        self.pending_bpm.is_some() || self.pending_time_signature.is_some()
+    }
+    
+    /// Changes only have a bar line to wait for while playing or paused
+    fn should_wait_for_bar_line(&self) -> bool {
+        self.change_timing == ChangeTiming::NextBar && (self.is_running || self.is_paused())
+    }
+    
+    /// Put queued changes into effect if the next beat starts a bar
+    ///
+    /// Bars are counted on from this beat, so a new meter starts at its own beat 1.
+    /// Returns whether anything changed.
+    fn apply_pending_changes_at_bar_line(&mut self) -> bool {
+        if !self.has_pending_changes() {
+            return false;
+        }
+        let beats_in_section = self.beat_count.saturating_sub(self.section_start_beat);
+        if !beats_in_section.is_multiple_of(self.time_signature.beats_per_measure() as u64) {
+            return false;
+        }
+        self.section_start_bar = self.get_next_bar_index();
+        self.section_start_beat = self.beat_count;
+        self.take_pending_changes()
+    }
+    
+    /// Put queued changes into effect, returning whether there were any
+    ///
+    /// Like setting them directly, they take over from any ramp, trainer or song sections.
+    fn take_pending_changes(&mut self) -> bool {
+        let changed = self.has_pending_changes();
+        if let Some(bpm) = self.pending_bpm.take() {
+            self.bpm = bpm;
+            self.tempo_ramp = None;
+            self.speed_trainer = None;
+            self.sections.clear();
+        }
+        if let Some(time_signature) = self.pending_time_signature.take() {
+            self.time_signature = time_signature;
+            self.sections.clear();
+            if self.accent_pattern.as_ref().is_some_and(|pattern| !pattern.fits(time_signature)) {
+                self.accent_pattern = None;
+            }
+        }
+        changed
+    }
+    
+    pub fn update_sounds(&mut self, beat_sound: SoundType, accent_sound: SoundType) {
+        self.beat_sound = beat_sound;
+        self.accent_sound = accent_sound;
+    }
+    
+    /// Switch to a setlist song; a running metronome restarts the count from beat 1
+    pub fn apply_song(&mut self, song: &Song) -> Result<()> {
+        song.validate(&self.tempo_range)?;
+        self.update_bpm(song.bpm)?;
+        self.update_time_signature(song.time_signature);
+        self.update_accent_pattern(song.accent_pattern.clone())?;
+        self.update_sections(song.sections.clone());
+        self.update_sounds(song.beat_sound.clone(), song.accent_sound.clone());
+        if self.is_running {
+            self.stop();
+            self.start();
+        }
+        Ok(())
+    }
+    
+    /// Change the subdivision while keeping the main beat grid in place
+    ///
+    /// The position within the current beat is mapped onto the new subdivision so
+    /// the next click is the first one of the new grid after the last click played.
+    pub fn update_subdivision(&mut self, subdivision: Subdivision) {
+        let old_pulses = self.get_click_subdivision().pulses_per_beat();
+        self.subdivision = subdivision;
+        self.current_subdivision = self.current_subdivision * self.get_click_subdivision().pulses_per_beat() / old_pulses;
+    }
+    
+    /// Set the swing of subdivision clicks, from 0.5 (straight) to 0.75 (hard shuffle)
+    ///
+    /// Only off-beat clicks move, so the next beat keeps its place on the grid.
+    pub fn update_swing(&mut self, swing: f32) -> Result<()> {
+        validate_swing(swing)?;
+        self.swing = swing;
+        Ok(())
+    }
+    
+    pub fn update_subdivision_sound(&mut self, sound: SoundType, volume: f32) -> Result<()> {
+        if !(0.0..=1.0).contains(&volume) {
+            return Err(MetronomeError::InvalidVolume(volume));
+        }
+        self.subdivision_sound = sound;
+        self.subdivision_volume = volume;
+        Ok(())
+    }
+    
+    pub fn update_accent_enabled(&mut self, accent_enabled: bool) {
+        self.accent_enabled = accent_enabled;
+    }
+    
+    pub fn update_volume(&mut self, volume: f32) -> Result<()> {
+        if volume < 0.0 || volume > 1.0 {
+            return Err(MetronomeError::InvalidVolume(volume));
+        }
+        self.volume = volume;
+        Ok(())
+    }
+}
+
+/// GUI state structure for managing UI state
+#[derive(Debug, Clone)]
+pub struct GuiState {
+    pub bpm_input: String,
+    pub bpm_valid: bool,
+    /// Tempos accepted in the BPM field
+    pub tempo_range: TempoRange,
+    pub selected_time_signature: TimeSignature,
+    pub selected_beat_sound: SoundType,
+    pub selected_accent_sound: SoundType,
+    pub is_running: bool,
+    pub error_message: Option<String>,
+    pub last_beat_visual: Option<Instant>,
+    pub accent_enabled: bool,
+    pub volume: f32,
+    pub selected_subdivision: Subdivision,
+    pub selected_subdivision_sound: SoundType,
+    pub subdivision_volume: f32,
+    pub swing: f32,
+    pub grouping_input: String,
+    pub accent_pattern: Option<AccentPattern>,
+    pub ramp_enabled: bool,
+    pub ramp_target_bpm: f64,
+    pub ramp_bars: u32,
+    pub ramp_curve: TempoCurve,
+    pub trainer_enabled: bool,
+    pub trainer_target_bpm: f64,
+    pub trainer_increment: u32,
+    pub trainer_bars: u32,
+    pub trainer_end: TrainerEnd,
+    pub trainer_drop_bpm: u32,
+    pub gap_enabled: bool,
+    pub gap_play_bars: u32,
+    pub gap_mute_bars: u32,
+    /// Chance of a click dropping out, 0.0 turns dropout off
+    pub dropout_probability: f32,
+    pub dropout_keep_downbeat: bool,
+    /// Bars counted in at start, 0 turns the count-in off
+    pub count_in_bars: u32,
+    pub count_in_sound: SoundType,
+    pub session_limit_enabled: bool,
+    /// Limit the session by bars rather than by time
+    pub session_limit_by_bars: bool,
+    pub session_minutes: u32,
+    pub session_bars: u32,
+    /// Whether the last run stopped at the session limit
+    pub session_finished: bool,
+    pub change_timing: ChangeTiming,
+    pub resample_quality: ResampleQuality,
+    pub setlist: Setlist,
+    pub song_name_input: String,
+    pub tap_tempo: TapTempo,
+}
+
+impl GuiState {
+    pub fn new() -> Self {
+        Self {
+            bpm_input: "120".to_string(),
+            bpm_valid: true,
+            tempo_range: TempoRange::default(),
+            selected_time_signature: TimeSignature::default(),
+            selected_beat_sound: SoundType::default(),
+            selected_accent_sound: SoundType::BuiltinWood,
+            is_running: false,
+            error_message: None,
+            last_beat_visual: None,
+            accent_enabled: true, // Accents enabled by default
+            volume: 0.7, // Default volume at 70%
+            selected_subdivision: Subdivision::None,
+            selected_subdivision_sound: SoundType::BuiltinBeep,
+            subdivision_volume: 0.5,
+            swing: STRAIGHT_SWING,
+            grouping_input: String::new(),
+            accent_pattern: None,
+            ramp_enabled: false,
+            ramp_target_bpm: 140.0,
+            ramp_bars: 8,
+            ramp_curve: TempoCurve::Linear,
+            trainer_enabled: false,
+            trainer_target_bpm: 140.0,
+            trainer_increment: 5,
+            trainer_bars: 4,
+            trainer_end: TrainerEnd::Hold,
+            trainer_drop_bpm: 10,
+            gap_enabled: false,
+            gap_play_bars: 4,
+            gap_mute_bars: 4,
+            dropout_probability: 0.0,
+            dropout_keep_downbeat: true,
+            count_in_bars: 0,
+            count_in_sound: SoundType::BuiltinBeep,
+            session_limit_enabled: false,
+            session_limit_by_bars: false,
+            session_minutes: 10,
+            session_bars: 64,
+            session_finished: false,
+            change_timing: ChangeTiming::Immediate,
+            resample_quality: ResampleQuality::default(),
+            setlist: Setlist::new(),
+            song_name_input: String::new(),
+            tap_tempo: TapTempo::new(),
+        }
+    }
+    
+    pub fn validate_bpm(&mut self, input: &str) -> Result<Tempo> {
+        match input.trim().parse::<f64>() {
+            Ok(bpm) if self.tempo_range.contains(Tempo::from(bpm)) => {
+                self.bpm_valid = true;
+                self.error_message = None;
+                Ok(Tempo::from(bpm))
+            }
+            Ok(bpm) => {
+                self.bpm_valid = false;
+                self.error_message = Some(format!(
+                    "BPM must be between {} and {} (got {})",
+                    self.tempo_range.min, self.tempo_range.max, bpm
+                ));
+                Err(MetronomeError::InvalidBpm(bpm))
+            }
+            Err(_) => {
+                self.bpm_valid = false;
+                self.error_message = Some("Invalid BPM value. Please enter a number.".to_string());
+                Err(MetronomeError::InvalidTempo(format!("'{}' is not a number", input.trim())))
+            }
+        }
+    }
+    
+    /// Parse the BPM field, which may hold a fractional tempo such as 72.5
+    pub fn get_input_bpm(&self) -> Result<Tempo> {
+        self.tempo_range.validate(Tempo::parse(&self.bpm_input)?)
+    }
+    
+    /// Apply a grouping such as "2+2+3" to the selected time signature; empty input clears it
+    pub fn validate_grouping(&mut self, input: &str) -> Result<TimeSignature> {
+        let input = input.trim();
+        let result = if input.is_empty() {
+            Ok(self.selected_time_signature.without_grouping())
+        } else {
+            input
+                .split('+')
+                .map(|group| group.trim().parse::<u32>())
+                .collect::<std::result::Result<Vec<u32>, _>>()
+                .map_err(|_| MetronomeError::InvalidTimeSignature(format!("'{}': expected beat groups such as 2+2+3", input)))
+                .and_then(|groups| self.selected_time_signature.with_grouping(&groups))
+        };
+        
+        match result {
+            Ok(time_signature) => {
+                self.selected_time_signature = time_signature;
+                self.error_message = None;
+                Ok(time_signature)
+            }
+            Err(e) => {
+                self.error_message = Some(e.to_string());
+                Err(e)
+            }
+        }
+    }
+    
+    /// Build the tempo ramp from the ramp controls, starting at the entered BPM
+    pub fn get_tempo_ramp(&self) -> Result<Option<TempoRamp>> {
+        if !self.ramp_enabled {
+            return Ok(None);
+        }
+        let start_bpm = self.get_input_bpm()?;
+        let ramp = TempoRamp::new(start_bpm, self.ramp_target_bpm, self.ramp_bars)?
+            .with_curve(self.ramp_curve);
+        ramp.validate(&self.tempo_range)?;
+        Ok(Some(ramp))
+    }
+    
+    /// Build the speed trainer from the trainer controls, starting at the entered BPM
+    pub fn get_speed_trainer(&self) -> Result<Option<SpeedTrainer>> {
+        if !self.trainer_enabled {
+            return Ok(None);
+        }
+        let start_bpm = self.get_input_bpm()?;
+        let end = match self.trainer_end {
+            TrainerEnd::DropBack(_) => TrainerEnd::DropBack(self.trainer_drop_bpm),
+            end => end,
+        };
+        let trainer = SpeedTrainer::new(start_bpm, self.trainer_target_bpm, self.trainer_increment, self.trainer_bars)?
+            .with_end(end)?;
+        trainer.validate(&self.tempo_range)?;
+        Ok(Some(trainer))
+    }
+    
+    /// Build the gap click setting from the gap click controls
+    pub fn get_gap_click(&self) -> Result<Option<GapClick>> {
+        if !self.gap_enabled {
+            return Ok(None);
+        }
+        GapClick::new(self.gap_play_bars, self.gap_mute_bars).map(Some)
+    }
+    
+    /// Build the random beat dropout from the dropout controls
+    pub fn get_dropout(&self) -> Result<Option<BeatDropout>> {
+        if self.dropout_probability <= 0.0 {
+            return Ok(None);
+        }
+        let dropout = BeatDropout::new(self.dropout_probability)?
+            .with_keep_downbeat(self.dropout_keep_downbeat);
+        Ok(Some(dropout))
+    }
+    
+    /// Build the count-in from the count-in controls
+    pub fn get_count_in(&self) -> Result<Option<CountIn>> {
+        if self.count_in_bars == 0 {
+            return Ok(None);
+        }
+        Ok(Some(CountIn::new(self.count_in_bars)?.with_sound(self.count_in_sound.clone())))
+    }
+    
+    /// Build the session limit from the session limit controls
+    pub fn get_session_limit(&self) -> Result<Option<SessionLimit>> {
+        if !self.session_limit_enabled {
+            return Ok(None);
+        }
+        let session_limit = if self.session_limit_by_bars {
+            SessionLimit::bars(self.session_bars)?
+        } else {
+            SessionLimit::duration(Duration::from_secs(self.session_minutes as u64 * 60))?
+        };
+        Ok(Some(session_limit))
+    }
+    
+    /// Carry a custom beat unit of the selected time signature over to another one, if it fits
+    ///
+    /// Switching from 6/8 in dotted quarters to 12/8 keeps counting dotted quarters, while
+    /// a meter that cannot be counted in them goes back to its own note value.
+    pub fn with_selected_beat_unit(&self, time_signature: TimeSignature) -> TimeSignature {
+        let selected = self.selected_time_signature;
+        match selected.beat_unit().filter(|_| selected.has_custom_beat_unit()) {
+            Some(beat_unit) => time_signature.with_beat_unit(beat_unit).unwrap_or(time_signature),
+            None => time_signature,
+        }
+    }
+    
+    /// Describe the tempo and meter changes waiting for the next bar, e.g. "pending: 7/8 at next bar"
+    pub fn pending_change_text(pending_bpm: Option<Tempo>, pending_time_signature: Option<TimeSignature>) -> Option<String> {
+        let changes: Vec<String> = pending_bpm.map(|bpm| format!("{} BPM", bpm))
+            .into_iter()
+            .chain(pending_time_signature.map(|time_sig| time_sig.to_string()))
+            .collect();
+        if changes.is_empty() {
+            return None;
+        }
+        Some(format!("pending: {} at next bar", changes.join(", ")))
+    }
+    
+    /// Build a setlist song from the current tempo, meter, accent and sound selections
+    pub fn song_from_inputs(&self, name: &str) -> Result<Song> {
+        let bpm = self.get_input_bpm()?;
+        let mut song = Song::new(name, bpm)?
+            .with_time_signature(self.selected_time_signature)
+            .with_sounds(self.selected_beat_sound.clone(), self.selected_accent_sound.clone());
+        if let Some(pattern) = self.accent_pattern.clone().filter(|pattern| pattern.fits(self.selected_time_signature)) {
+            song = song.with_accent_pattern(pattern);
+        }
+        Ok(song)
+    }
+    
+    /// Show a setlist song's settings in the controls
+    ///
+    /// Ramps and the speed trainer are switched off, as the song sets a fixed tempo.
+    pub fn load_song(&mut self, song: &Song) {
+        self.bpm_input = song.bpm.to_string();
+        self.bpm_valid = true;
+        self.selected_time_signature = song.time_signature;
+        self.grouping_input = Self::grouping_text(song.time_signature);
+        self.accent_pattern = song.accent_pattern.clone();
+        self.selected_beat_sound = song.beat_sound.clone();
+        self.selected_accent_sound = song.accent_sound.clone();
+        self.ramp_enabled = false;
+        self.trainer_enabled = false;
+    }
+    
+    /// Register a tap and show the detected tempo in the BPM field
+    ///
+    /// Like typing a tempo, a tapped tempo switches ramps and the speed trainer off. A
+    /// tempo outside the tempo range leaves the field as it is and shows an error instead.
+    pub fn register_tap(&mut self, now: Instant) -> Option<Tempo> {
+        self.tap_tempo.tap_at(now);
+        let bpm = self.tap_tempo.bpm()?;
+        if let Err(e) = self.tempo_range.validate(bpm) {
+            self.set_error(e.to_string());
+            return None;
+        }
+        self.bpm_input = bpm.to_string();
+        self.bpm_valid = true;
+        self.ramp_enabled = false;
+        self.trainer_enabled = false;
+        Some(bpm)
+    }
+    
+    /// Get the grouping input text that matches a time signature
+    pub fn grouping_text(time_signature: TimeSignature) -> String {
+        if time_signature.has_custom_grouping() {
+            let groups: Vec<String> = time_signature.groups().iter().map(|g| g.to_string()).collect();
+            groups.join("+")
+        } else {
+            String::new()
+        }
+    }
+    
+    pub fn set_error(&mut self, message: String) {
+        self.error_message = Some(message);
+    }
+    
+    pub fn clear_error(&mut self) {
+        self.error_message = None;
+    }
+    
+    pub fn update_beat_visual(&mut self) {
+        self.last_beat_visual = Some(Instant::now());
+    }
+    
+    pub fn should_show_beat_visual(&self, duration: Duration) -> bool {
+        match self.last_beat_visual {
+            Some(last_beat) => last_beat.elapsed() < duration,
+            None => false,
+        }
+    }
+    
+    pub fn set_gui_error(&mut self, error: crate::error::GuiError) {
+        self.error_message = Some(error.to_string());
+    }
+    
+    pub fn handle_result<T>(&mut self, result: Result<T>) -> Option<T> {
+        match result {
+            Ok(value) => {
+                self.clear_error();
+                Some(value)
+            }
+            Err(error) => {
+                self.set_error(error.to_string());
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_metronome_config_creation() {
+        let config = MetronomeConfig::new(120);
+        assert_eq!(config.bpm, 120);
+        assert!(config.sound_enabled);
+        assert!(config.visual_enabled);
+    }
+    
+    #[test]
+    fn test_metronome_config_validation() {
+        let config = MetronomeConfig::new(120);
         assert!(config.validate().is_ok());
         
-        let config = MetronomeConfig::new(59);
+        let config = MetronomeConfig::new(19);
+        assert!(config.validate().is_err());
+        
+        let config = MetronomeConfig::new(401);
+        assert!(config.validate().is_err());
+    }
+    
+    #[test]
+    fn test_metronome_config_builder() {
+        let config = MetronomeConfig::new(120)
+            .with_sound(false)
+            .with_visual(true)
+            .with_accent_enabled(false);
+        
+        assert!(!config.sound_enabled);
+        assert!(config.visual_enabled);
+        assert!(!config.accent_enabled);
+    }
+    
+    #[test]
+    fn test_beat_creation() {
+        let beat = Beat::new(1, TimeSignature::new(4, 4), 120);
+        assert_eq!(beat.sequence_number, 1);
+        assert_eq!(beat.bpm, 120);
+        assert_eq!(beat.beat_in_measure, 1);
+        assert!(beat.is_accent);
+        assert!(beat.is_first_beat());
+    }
+    
+    #[test]
+    fn test_beat_creation_with_zero_sequence() {
+        // Test that Beat::new handles sequence_number 0 without panicking
+        let beat = Beat::new(0, TimeSignature::new(4, 4), 120);
+        assert_eq!(beat.sequence_number, 1); // Should be normalized to 1
+        assert_eq!(beat.bpm, 120);
+        assert_eq!(beat.beat_in_measure, 1);
+        assert!(beat.is_accent);
+        assert!(beat.is_first_beat());
+    }
+    
+    #[test]
+    fn test_beat_accent_enabled_disabled() {
+        // Test Beat with accents enabled (default)
+        let beat_enabled = Beat::new_with_accent_setting(1, TimeSignature::new(4, 4), 120, true);
+        assert!(beat_enabled.accent_enabled);
+        assert_eq!(beat_enabled.get_accent_strength(), 1.0); // Strong beat
+        
+        let beat_enabled_weak = Beat::new_with_accent_setting(2, TimeSignature::new(4, 4), 120, true);
+        assert!(beat_enabled_weak.accent_enabled);
+        assert_eq!(beat_enabled_weak.get_accent_strength(), 0.0); // Weak beat
+        
+        // Test Beat with accents disabled
+        let beat_disabled = Beat::new_with_accent_setting(1, TimeSignature::new(4, 4), 120, false);
+        assert!(!beat_disabled.accent_enabled);
+        assert_eq!(beat_disabled.get_accent_strength(), 0.0); // No accent when disabled
+        
+        let beat_disabled_weak = Beat::new_with_accent_setting(2, TimeSignature::new(4, 4), 120, false);
+        assert!(!beat_disabled_weak.accent_enabled);
+        assert_eq!(beat_disabled_weak.get_accent_strength(), 0.0); // Still no accent
+    }
+    
+    #[test]
+    fn test_time_signature() {
+        assert_eq!(TimeSignature::new(4, 4).beats_per_measure(), 4);
+        assert_eq!(TimeSignature::new(3, 4).beats_per_measure(), 3);
+        assert_eq!(TimeSignature::new(2, 4).beats_per_measure(), 2);
+        assert_eq!(TimeSignature::new(6, 8).beats_per_measure(), 6);
+        
+        assert_eq!(TimeSignature::new(4, 4).to_string(), "4/4");
+        assert_eq!(TimeSignature::new(3, 4).to_string(), "3/4");
+        assert_eq!(TimeSignature::new(11, 8).to_string(), "11/8");
+        assert_eq!(TimeSignature::new(11, 8).beats_per_measure(), 11);
+    }
+    
+    #[test]
+    fn test_time_signature_parsing() {
+        assert_eq!("11/8".parse::<TimeSignature>().unwrap(), TimeSignature::new(11, 8));
+        assert_eq!(" 15 / 16 ".parse::<TimeSignature>().unwrap(), TimeSignature::new(15, 16));
+        
+        // Names written by older configs
+        assert_eq!("Three".parse::<TimeSignature>().unwrap(), TimeSignature::new(3, 4));
+        assert_eq!("Eight".parse::<TimeSignature>().unwrap(), TimeSignature::new(8, 8));
+        
+        assert!("0/4".parse::<TimeSignature>().is_err());
+        assert!("33/4".parse::<TimeSignature>().is_err());
+        assert!("7/6".parse::<TimeSignature>().is_err());
+        assert!("7".parse::<TimeSignature>().is_err());
+        assert!(matches!(
+            TimeSignature::try_new(4, 3),
+            Err(MetronomeError::InvalidTimeSignature(_))
+        ));
+    }
+    
+    #[test]
+    fn test_time_signature_accent_patterns() {
+        let strengths = |ts: TimeSignature| -> Vec<f32> {
+            (1..=ts.beats_per_measure()).map(|beat| ts.accent_strength(beat)).collect()
+        };
+        
+        // The previous fixed patterns are unchanged
+        assert_eq!(strengths(TimeSignature::new(1, 4)), vec![0.0]);
+        assert_eq!(strengths(TimeSignature::new(4, 4)), vec![1.0, 0.0, 0.5, 0.0]);
+        assert_eq!(strengths(TimeSignature::new(6, 8)), vec![1.0, 0.0, 0.0, 0.5, 0.0, 0.0]);
+        assert_eq!(strengths(TimeSignature::new(8, 8)), vec![1.0, 0.0, 0.0, 0.0, 0.5, 0.0, 0.0, 0.0]);
+        
+        // Compound meters accent every group of three
+        assert_eq!(strengths(TimeSignature::new(12, 8)),
+            vec![1.0, 0.0, 0.0, 0.5, 0.0, 0.0, 0.5, 0.0, 0.0, 0.5, 0.0, 0.0]);
+        assert!(TimeSignature::new(15, 16).is_compound());
+        assert!(!TimeSignature::new(6, 4).is_compound());
+        
+        // Irregular meters only accent the downbeat
+        let eleven = strengths(TimeSignature::new(11, 8));
+        assert_eq!(eleven.len(), 11);
+        assert_eq!(eleven[0], 1.0);
+        assert!(eleven[1..].iter().all(|&s| s == 0.0));
+        
+        assert_eq!(TimeSignature::new(4, 4).describe_pattern(), "Strong-weak-medium-weak");
+    }
+    
+    #[test]
+    fn test_time_signature_grouping() {
+        let seven = TimeSignature::new(7, 8);
+        assert_eq!(seven.groups(), vec![7]);
+        assert_eq!(TimeSignature::new(6, 8).groups(), vec![3, 3]);
+        assert_eq!(TimeSignature::new(4, 4).groups(), vec![2, 2]);
+        
+        let grouped = seven.with_grouping(&[2, 2, 3]).unwrap();
+        assert!(grouped.has_custom_grouping());
+        assert_eq!(grouped.groups(), vec![2, 2, 3]);
+        assert_eq!(grouped.beats_per_measure(), 7);
+        assert_eq!(grouped.to_string(), "2+2+3/8");
+        assert_ne!(grouped, seven);
+        
+        // Each group's first pulse is accented, the downbeat most strongly
+        let strengths: Vec<f32> = (1..=7).map(|beat| grouped.accent_strength(beat)).collect();
+        assert_eq!(strengths, vec![1.0, 0.0, 0.5, 0.0, 0.5, 0.0, 0.0]);
+        
+        let config = MetronomeConfig::new(120).with_time_signature(seven.with_grouping(&[3, 2, 2]).unwrap());
+        let state = MetronomeState::new(&config);
+        assert_eq!(state.get_accent_pattern(), vec![true, false, false, true, false, true, false]);
+        
+        // Groups must cover the measure exactly
+        assert!(seven.with_grouping(&[3, 3]).is_err());
+        assert!(seven.with_grouping(&[0, 7]).is_err());
+        
+        // A single group returns to the default grouping
+        assert_eq!(grouped.with_grouping(&[7]).unwrap(), seven);
+        assert_eq!(grouped.without_grouping(), seven);
+    }
+    
+    #[test]
+    fn test_time_signature_grouping_parsing() {
+        let parsed: TimeSignature = "3+3+2/8".parse().unwrap();
+        assert_eq!(parsed.beats_per_measure(), 8);
+        assert_eq!(parsed.groups(), vec![3, 3, 2]);
+        assert_eq!(parsed.to_string().parse::<TimeSignature>().unwrap(), parsed);
+        
+        assert!("3+x/8".parse::<TimeSignature>().is_err());
+        assert!("3+0/8".parse::<TimeSignature>().is_err());
+        assert!("20+20/8".parse::<TimeSignature>().is_err());
+        assert!("4294967295+1/8".parse::<TimeSignature>().is_err());
+    }
+    
+    #[test]
+    fn test_time_signature_beat_unit() {
+        let six_eight = TimeSignature::new(6, 8).with_beat_unit(BeatUnit::DottedQuarter).unwrap();
+        assert_eq!(six_eight.beats_per_measure(), 2);
+        assert_eq!(six_eight.beat_unit(), Some(BeatUnit::DottedQuarter));
+        assert_eq!(six_eight.note_subdivision(), Subdivision::Triplets);
+        assert_eq!((six_eight.accent_strength(1), six_eight.accent_strength(2)), (1.0, 0.5));
+        assert_eq!(six_eight.tempo_with_unit(60), "60 (dotted quarter)");
+        
+        // The meter is shown on its own, while the stored form keeps the beat unit
+        assert_eq!(six_eight.to_string(), "6/8");
+        assert_eq!(String::from(six_eight), "6/8 (dotted quarter)");
+        assert_eq!("6/8 (dotted-quarter)".parse::<TimeSignature>().unwrap(), six_eight);
+        
+        // Without a beat unit the denominator's note value is counted
+        let cut_time = TimeSignature::new(2, 2);
+        assert_eq!((cut_time.beats_per_measure(), cut_time.beat_unit()), (2, Some(BeatUnit::Half)));
+        assert_eq!(TimeSignature::new(4, 4).with_beat_unit(BeatUnit::Quarter).unwrap(), TimeSignature::new(4, 4));
+        assert_eq!(TimeSignature::new(15, 16).tempo_with_unit(90), "90");
+        
+        let in_halves = TimeSignature::new(4, 4).with_beat_unit(BeatUnit::Half).unwrap();
+        assert_eq!((in_halves.beats_per_measure(), in_halves.note_subdivision()), (2, Subdivision::Eighths));
+        let in_eighths = TimeSignature::new(3, 4).with_beat_unit(BeatUnit::Eighth).unwrap();
+        assert_eq!((in_eighths.beats_per_measure(), in_eighths.note_subdivision()), (6, Subdivision::None));
+        assert!(!in_eighths.is_group_start(2));
+        
+        assert!(TimeSignature::new(7, 8).with_beat_unit(BeatUnit::DottedQuarter).is_err());
+        assert!("6/8 (whole)".parse::<TimeSignature>().is_err());
+        assert_eq!("Dotted quarter".parse::<BeatUnit>(), Ok(BeatUnit::DottedQuarter));
+        assert!("sixteenth".parse::<BeatUnit>().is_err());
+    }
+    
+    #[test]
+    fn test_dotted_quarter_beats_with_eighth_clicks() {
+        let time_signature = TimeSignature::new(6, 8).with_beat_unit(BeatUnit::DottedQuarter).unwrap();
+        let config = MetronomeConfig::new(60).with_time_signature(time_signature);
+        let mut state = MetronomeState::new(&config);
+        assert_eq!(state.calculate_beat_interval(), Duration::from_secs(1));
+        state.start();
+        
+        // Two beats a second apart per bar, each followed by two more eighths
+        let clicks: Vec<(u32, u32)> = (0..7).map(|_| {
+            let scheduled = state.get_next_beat_time().unwrap();
+            let beat = state.increment_beat_at(scheduled);
+            (beat.beat_in_measure, beat.subdivision_index)
+        }).collect();
+        assert_eq!(clicks, vec![(1, 0), (1, 1), (1, 2), (2, 0), (2, 1), (2, 2), (1, 0)]);
+        assert_eq!(state.get_subdivision_interval(), Duration::from_secs(1) / 3);
+        
+        // A chosen subdivision takes the place of the eighths
+        state.update_subdivision(Subdivision::Eighths);
+        assert_eq!(state.get_click_subdivision(), Subdivision::Eighths);
+    }
+    
+    #[test]
+    fn test_sound_type() {
+        assert_eq!(SoundType::BuiltinClick.as_str(), "Click");
+        assert_eq!(SoundType::BuiltinWood.as_str(), "Wood");
+        assert_eq!(SoundType::BuiltinBeep.as_str(), "Beep");
+        
+        assert!(SoundType::BuiltinClick.is_builtin());
+        assert!(!SoundType::Custom(std::path::PathBuf::from("test.wav")).is_builtin());
+    }
+    
+    #[test]
+    fn test_beat_sequence_in_measure() {
+        // Test 4/4 time signature
+        let beat1 = Beat::new(1, TimeSignature::new(4, 4), 120);
+        let beat2 = Beat::new(2, TimeSignature::new(4, 4), 120);
+        let beat3 = Beat::new(3, TimeSignature::new(4, 4), 120);
+        let beat4 = Beat::new(4, TimeSignature::new(4, 4), 120);
+        let beat5 = Beat::new(5, TimeSignature::new(4, 4), 120); // Next measure
+        
+        assert_eq!(beat1.beat_in_measure, 1);
+        assert!(beat1.is_accent);
+        
+        assert_eq!(beat2.beat_in_measure, 2);
+        assert!(!beat2.is_accent);
+        
+        assert_eq!(beat3.beat_in_measure, 3);
+        // In 4/4 time, beat 3 is a medium accent (not a strong accent but not weak either)
+        assert!(beat3.is_accent); // This is now true due to our enhanced accent logic
+        
+        assert_eq!(beat4.beat_in_measure, 4);
+        assert!(!beat4.is_accent);
+        
+        assert_eq!(beat5.beat_in_measure, 1); // Back to first beat of next measure
+        assert!(beat5.is_accent);
+    }
+    
+    #[test]
+    fn test_metronome_state_creation() {
+        let config = MetronomeConfig::new(120);
+        let state = MetronomeState::new(&config);
+        
+        assert_eq!(state.bpm, 120);
+        assert_eq!(state.time_signature, TimeSignature::new(4, 4));
+        assert!(!state.is_running);
+        assert_eq!(state.beat_count, 0);
+        assert_eq!(state.current_beat_in_measure, 1);
+    }
+    
+    #[test]
+    fn test_metronome_state_start_stop() {
+        let config = MetronomeConfig::new(120);
+        let mut state = MetronomeState::new(&config);
+        
+        assert!(!state.is_running);
+        state.start();
+        assert!(state.is_running);
+        assert!(state.start_time.is_some());
+        
+        state.stop();
+        assert!(!state.is_running);
+        assert!(state.start_time.is_none());
+    }
+    
+    #[test]
+    fn test_pause_keeps_position_and_clock() {
+        let config = MetronomeConfig::new(120).with_time_signature(TimeSignature::new(3, 4));
+        let mut state = MetronomeState::new(&config);
+        state.start();
+        let start_time = state.start_time.unwrap();
+        for _ in 0..4 {
+            let scheduled = state.get_next_beat_time().unwrap();
+            state.increment_beat_at(scheduled);
+        }
+        let next_beat_time = state.get_next_beat_time().unwrap();
+        
+        // Paused 0.2 s after beat 1 of bar 2
+        let paused_at = start_time + Duration::from_millis(2200);
+        state.pause_at(paused_at);
+        assert!(state.is_paused());
+        assert!(!state.is_running);
+        assert!(!state.is_beat_due(paused_at + Duration::from_secs(5)));
+        assert_eq!(state.get_elapsed_time(), Duration::from_millis(2200));
+        assert_eq!((state.beat_count, state.current_beat_in_measure), (4, 1));
+        
+        // Resuming ten seconds later continues with beat 2 of the bar, as far after
+        // resuming as it was due after pausing
+        state.resume_at(paused_at + Duration::from_secs(10));
+        assert!(state.is_running && !state.is_paused());
+        assert_eq!(state.start_time, Some(start_time + Duration::from_secs(10)));
+        let scheduled = state.get_next_beat_time().unwrap();
+        assert!(scheduled.duration_since(next_beat_time).abs_diff(Duration::from_secs(10)) < Duration::from_micros(1));
+        let beat = state.increment_beat_at(scheduled);
+        assert_eq!((beat.sequence_number, beat.beat_in_measure), (5, 2));
+        
+        // Starting while paused starts over instead of resuming
+        state.pause();
+        state.start();
+        assert!(!state.is_paused());
+        assert_eq!(state.beat_count, 0);
+    }
+    
+    #[test]
+    fn test_changes_wait_for_next_bar() {
+        let config = MetronomeConfig::new(120).with_change_timing(ChangeTiming::NextBar);
+        let mut state = MetronomeState::new(&config);
+        
+        // While stopped there is no bar to wait for
+        state.change_bpm(100).unwrap();
+        assert_eq!(state.bpm, 100);
+        state.update_bpm(120).unwrap();
+        
+        state.start();
+        for _ in 0..2 {
+            let scheduled = state.get_next_beat_time().unwrap();
+            state.increment_beat_at(scheduled);
+        }
+        assert!(state.change_bpm(19).is_err());
+        state.change_bpm(60).unwrap();
+        state.change_time_signature(TimeSignature::new(7, 8));
+        assert!(state.has_pending_changes());
+        assert_eq!((state.bpm, state.time_signature), (Tempo::from(120), TimeSignature::new(4, 4)));
+        
+        // The bar is played out at the old tempo and meter
+        let mut last_time = state.get_last_click_time().unwrap();
+        for beat_in_measure in [3, 4] {
+            let scheduled = state.get_next_beat_time().unwrap();
+            assert!((scheduled - last_time).abs_diff(Duration::from_millis(500)) < Duration::from_micros(1));
+            let beat = state.increment_beat_at(scheduled);
+            assert_eq!(beat.beat_in_measure, beat_in_measure);
+            last_time = scheduled;
+        }
+        
+        // The changes land on the downbeat, and the new meter counts from its beat 1
+        let scheduled = state.get_next_beat_time().unwrap();
+        assert!((scheduled - last_time).abs_diff(Duration::from_millis(500)) < Duration::from_micros(1));
+        let beat = state.increment_beat_at(scheduled);
+        assert_eq!((beat.beat_in_measure, beat.time_signature), (1, TimeSignature::new(7, 8)));
+        assert!(!state.has_pending_changes());
+        assert_eq!(state.bpm, 60);
+        last_time = scheduled;
+        for beat_in_measure in [2, 3, 4, 5, 6, 7, 1] {
+            let scheduled = state.get_next_beat_time().unwrap();
+            assert!((scheduled - last_time).abs_diff(Duration::from_secs(1)) < Duration::from_micros(1));
+            assert_eq!(state.increment_beat_at(scheduled).beat_in_measure, beat_in_measure);
+            last_time = scheduled;
+        }
+        assert_eq!(state.get_bars_played(), 2);
+        
+        // A direct change replaces the queued one, and stopping applies what is left
+        state.change_bpm(90).unwrap();
+        state.update_bpm(80).unwrap();
+        assert_eq!(state.pending_bpm, None);
+        state.change_time_signature(TimeSignature::new(3, 4));
+        state.stop();
+        assert_eq!(state.time_signature, TimeSignature::new(3, 4));
+        assert!(!state.has_pending_changes());
+    }
+    
+    #[test]
+    fn test_immediate_meter_change_starts_a_new_bar() {
+        let mut state = MetronomeState::new(&MetronomeConfig::new(120));
+        state.start();
+        for _ in 0..6 {
+            let scheduled = state.get_next_beat_time().unwrap();
+            state.increment_beat_at(scheduled);
+        }
+        assert_eq!((state.current_beat_in_measure, state.get_bars_played()), (2, 1));
+        
+        // The bar cut short still counts, and 3/4 counts from its beat 1 at the next beat
+        state.change_time_signature(TimeSignature::new(3, 4));
+        assert_eq!(state.get_bars_played(), 2);
+        for beat_in_measure in [1, 2, 3, 1] {
+            let scheduled = state.get_next_beat_time().unwrap();
+            assert_eq!(state.increment_beat_at(scheduled).beat_in_measure, beat_in_measure);
+        }
+        assert_eq!(state.get_bars_played(), 3);
+    }
+    
+    #[test]
+    fn test_metronome_state_bpm_update() {
+        let config = MetronomeConfig::new(120);
+        let mut state = MetronomeState::new(&config);
+        
+        assert!(state.update_bpm(140).is_ok());
+        assert_eq!(state.bpm, 140);
+        
+        assert!(state.update_bpm(19).is_err());
+        assert!(state.update_bpm(401).is_err());
+    }
+    
+    #[test]
+    fn test_fractional_tempo_and_range() {
+        let tempo: Tempo = "72.5".parse().unwrap();
+        assert_eq!(tempo.bpm(), 72.5);
+        assert_eq!(tempo.to_string(), "72.5");
+        assert_eq!(Tempo::from(120).to_string(), "120");
+        assert!((tempo.beat_interval().as_secs_f64() - 60.0 / 72.5).abs() < 1e-9);
+        assert!(matches!("fast".parse::<Tempo>(), Err(MetronomeError::InvalidTempo(_))));
+        assert!(Tempo::new(19.9).is_err());
+        assert!(Tempo::new(400.0).is_ok());
+        
+        let range = TempoRange::new(60.0, 200.0).unwrap();
+        assert!(range.contains(Tempo::from(60)));
+        assert!(!range.contains(Tempo::from(200.5)));
+        assert!(matches!(range.validate(Tempo::from(59.5)), Err(MetronomeError::InvalidBpm(bpm)) if bpm == 59.5));
+        assert_eq!(range.clamp(Tempo::from(250)), 200);
+        assert!(TempoRange::new(10.0, 200.0).is_err());
+        assert!(TempoRange::new(120.0, 120.0).is_err());
+        
+        let mut state = MetronomeState::new(&MetronomeConfig::new(240));
+        assert!(state.update_bpm(72.5).is_ok());
+        assert!((state.get_interval().as_secs_f64() - 60.0 / 72.5).abs() < 1e-9);
+        state.update_bpm(240).unwrap();
+        assert!(state.update_tempo_range(range).is_ok());
+        assert_eq!(state.bpm, 200);
+        assert!(state.update_bpm(220).is_err());
+    }
+    
+    #[test]
+    fn test_gui_state_creation() {
+        let gui_state = GuiState::new();
+        
+        assert_eq!(gui_state.bpm_input, "120");
+        assert!(gui_state.bpm_valid);
+        assert_eq!(gui_state.selected_time_signature, TimeSignature::new(4, 4));
+        assert!(!gui_state.is_running);
+        assert!(gui_state.error_message.is_none());
+    }
+    
+    #[test]
+    fn test_gui_state_bpm_validation() {
+        let mut gui_state = GuiState::new();
+        
+        assert!(gui_state.validate_bpm("120").is_ok());
+        assert!(gui_state.bpm_valid);
+        assert!(gui_state.error_message.is_none());
+        
+        assert!(gui_state.validate_bpm("19").is_err());
+        assert!(!gui_state.bpm_valid);
+        assert!(gui_state.error_message.is_some());
+        
+        assert!(gui_state.validate_bpm("invalid").is_err());
+        assert!(!gui_state.bpm_valid);
+        assert!(gui_state.error_message.is_some());
+    }
+    
+    #[test]
+    fn test_gui_state_error_handling() {
+        let mut gui_state = GuiState::new();
+        
+        // Test setting and clearing errors
+        gui_state.set_error("Test error".to_string());
+        assert!(gui_state.error_message.is_some());
+        
+        gui_state.clear_error();
+        assert!(gui_state.error_message.is_none());
+        
+        // Test handling results
+        let ok_result: Result<u32> = Ok(120);
+        let value = gui_state.handle_result(ok_result);
+        assert_eq!(value, Some(120));
+        assert!(gui_state.error_message.is_none());
+        
+        let err_result: Result<u32> = Err(MetronomeError::InvalidBpm(19.0));
+        let value = gui_state.handle_result(err_result);
+        assert_eq!(value, None);
+        assert!(gui_state.error_message.is_some());
+    }
+    
+    #[test]
+    fn test_time_signature_intervals() {
+        let config = MetronomeConfig::new(120); // 120 BPM
+        let mut state = MetronomeState::new(&config);
+        
+        // Test 4/4 time (default)
+        let interval_4_4 = state.calculate_beat_interval();
+        assert_eq!(interval_4_4, Duration::from_millis(500)); // 120 BPM = 500ms per beat
+        
+        // Test 6/8 time (compound time)
+        state.time_signature = TimeSignature::new(6, 8);
+        let interval_6_8 = state.calculate_beat_interval();
+        // In our implementation, 6/8 time uses the same interval as 4/4 (500ms per beat)
+        assert_eq!(interval_6_8, Duration::from_millis(500));
+        
+        // Test other time signatures
+        state.time_signature = TimeSignature::new(3, 4);
+        let interval_3_4 = state.calculate_beat_interval();
+        assert_eq!(interval_3_4, Duration::from_millis(500)); // Same as 4/4
+    }
+    
+    #[test]
+    fn test_accent_patterns() {
+        let config = MetronomeConfig::new(120);
+        let mut state = MetronomeState::new(&config);
+        
+        // Test 4/4 accent pattern
+        state.time_signature = TimeSignature::new(4, 4);
+        let pattern_4_4 = state.get_accent_pattern();
+        assert_eq!(pattern_4_4, vec![true, false, true, false]); // Strong-weak-medium-weak
+        
+        // Test 3/4 accent pattern
+        state.time_signature = TimeSignature::new(3, 4);
+        let pattern_3_4 = state.get_accent_pattern();
+        assert_eq!(pattern_3_4, vec![true, false, false]); // Strong-weak-weak
+        
+        // Test 6/8 accent pattern
+        state.time_signature = TimeSignature::new(6, 8);
+        let pattern_6_8 = state.get_accent_pattern();
+        assert_eq!(pattern_6_8, vec![true, false, false, true, false, false]); // Strong-weak-weak-medium-weak-weak
+    }
+    
+    #[test]
+    fn test_accent_strength_calculation() {
+        let config = MetronomeConfig::new(120);
+        let mut state = MetronomeState::new(&config);
+        
+        // Test 4/4 accent strengths
+        state.time_signature = TimeSignature::new(4, 4);
+        
+        state.current_beat_in_measure = 1;
+        assert_eq!(state.get_accent_strength(), 1.0); // Strong beat
+        
+        state.current_beat_in_measure = 2;
+        assert_eq!(state.get_accent_strength(), 0.0); // Weak beat
+        
+        state.current_beat_in_measure = 3;
+        assert_eq!(state.get_accent_strength(), 0.5); // Medium beat
+        
+        state.current_beat_in_measure = 4;
+        assert_eq!(state.get_accent_strength(), 0.0); // Weak beat
+    }
+    
+    #[test]
+    fn test_beat_accent_methods() {
+        // Test 4/4 time signature beats
+        let beat1 = Beat::new(1, TimeSignature::new(4, 4), 120);
+        assert!(beat1.is_strong_beat());
+        assert!(!beat1.is_medium_beat());
+        assert!(!beat1.is_weak_beat());
+        assert_eq!(beat1.get_accent_strength(), 1.0);
+        
+        let beat2 = Beat::new(2, TimeSignature::new(4, 4), 120);
+        assert!(!beat2.is_strong_beat());
+        assert!(!beat2.is_medium_beat());
+        assert!(beat2.is_weak_beat());
+        assert_eq!(beat2.get_accent_strength(), 0.0);
+        
+        let beat3 = Beat::new(3, TimeSignature::new(4, 4), 120);
+        assert!(!beat3.is_strong_beat());
+        assert!(beat3.is_medium_beat());
+        assert!(!beat3.is_weak_beat());
+        assert_eq!(beat3.get_accent_strength(), 0.5);
+    }
+    
+    #[test]
+    fn test_timing_accuracy_calculation() {
+        let config = MetronomeConfig::new(120);
+        let mut state = MetronomeState::new(&config);
+        state.start();
+        
+        // Test that timing accuracy calculation works
+        // We'll test with a known time offset
+        let start_time = state.start_time.unwrap();
+        let expected_first_beat = start_time + state.calculate_beat_interval();
+        
+        // Simulate checking accuracy at the expected time
+        let accuracy = state.get_timing_accuracy(expected_first_beat);
+        assert!(accuracy.is_some());
+        
+        // The accuracy should be very small (close to 0) when timing is perfect
+        if let Some(acc) = accuracy {
+            assert!(acc.as_millis() <= 1); // Should be very close to expected time
+        }
+    }
+    
+    #[test]
+    fn test_volume_functionality() {
+        // Test MetronomeConfig with volume
+        let config = MetronomeConfig::new(120).with_volume(0.5);
+        assert_eq!(config.volume, 0.5);
+        
+        // Test volume clamping
+        let config_high = MetronomeConfig::new(120).with_volume(1.5);
+        assert_eq!(config_high.volume, 1.0); // Should be clamped to 1.0
+        
+        let config_low = MetronomeConfig::new(120).with_volume(-0.5);
+        assert_eq!(config_low.volume, 0.0); // Should be clamped to 0.0
+        
+        // Test MetronomeState volume update
+        let config = MetronomeConfig::new(120);
+        let mut state = MetronomeState::new(&config);
+        
+        assert_eq!(state.volume, 0.7); // Default volume
+        
+        // Test valid volume update
+        assert!(state.update_volume(0.8).is_ok());
+        assert_eq!(state.volume, 0.8);
+        
+        // Test invalid volume updates
+        assert!(state.update_volume(-0.1).is_err());
+        assert!(state.update_volume(1.1).is_err());
+        assert_eq!(state.volume, 0.8); // Should remain unchanged after invalid update
+    }
+    
+    #[test]
+    fn test_gui_state_volume() {
+        let gui_state = GuiState::new();
+        assert_eq!(gui_state.volume, 0.7); // Default volume
+    }
+    
+    #[test]
+    fn test_subdivision_click_sequence() {
+        let config = MetronomeConfig::new(120).with_subdivision(Subdivision::Triplets);
+        let mut state = MetronomeState::new(&config);
+        state.start();
+        let start_time = state.start_time.unwrap();
+        
+        let expected = [(1, 0), (1, 1), (1, 2), (2, 0), (2, 1)];
+        for (tick, &(beat_number, sub_index)) in expected.iter().enumerate() {
+            let scheduled = state.get_next_beat_time().unwrap();
+            let beat = state.increment_beat_at(scheduled);
+            
+            assert_eq!(beat.sequence_number, beat_number);
+            assert_eq!(beat.subdivision_index, sub_index);
+            assert_eq!(beat.is_subdivision(), sub_index > 0);
+            assert_eq!(state.beat_count, beat_number);
+            
+            // Triplet clicks are a third of a 500 ms beat apart, starting one beat in
+            let offset = Duration::from_secs_f64(0.5 + tick as f64 * 0.5 / 3.0);
+            assert!(beat.scheduled_time.duration_since(start_time).abs_diff(offset) < Duration::from_micros(1));
+        }
+    }
+    
+    #[test]
+    fn test_subdivision_sound_selection() {
+        let config = MetronomeConfig::new(120)
+            .with_subdivision(Subdivision::Eighths)
+            .with_subdivision_sound(SoundType::BuiltinBeep, 0.5);
+        let mut state = MetronomeState::new(&config);
+        state.start();
+        
+        let downbeat = state.increment_beat();
+        assert!(downbeat.is_accent);
+        assert_eq!(state.get_sound_for_beat(&downbeat), (SoundType::BuiltinWood, 0.7));
+        
+        let offbeat = state.increment_beat();
+        assert!(!offbeat.is_accent);
+        assert_eq!(offbeat.get_accent_strength(), 0.0);
+        assert_eq!(state.get_sound_for_beat(&offbeat), (SoundType::BuiltinBeep, 0.7 * 0.5));
+        
+        assert!(state.update_subdivision_sound(SoundType::BuiltinClick, 1.5).is_err());
+    }
+    
+    #[test]
+    fn test_swing_delays_off_beats_only() {
+        let config = MetronomeConfig::new(120)
+            .with_subdivision(Subdivision::Eighths)
+            .with_swing(0.75);
+        let mut state = MetronomeState::new(&config);
+        state.start();
+        let start_time = state.start_time.unwrap();
+        
+        // The off-beat moves to three quarters of the 500 ms beat, the beats stay put
+        let expected = [0.5, 0.875, 1.0, 1.375, 1.5];
+        for offset in expected {
+            let scheduled = state.get_next_beat_time().unwrap();
+            let beat = state.increment_beat_at(scheduled);
+            let offset = Duration::from_secs_f64(offset);
+            assert!(beat.scheduled_time.duration_since(start_time).abs_diff(offset) < Duration::from_micros(1));
+        }
+        assert!(state.get_click_interval(0).abs_diff(Duration::from_millis(375)) < Duration::from_micros(1));
+        assert!(state.get_click_interval(1).abs_diff(Duration::from_millis(125)) < Duration::from_micros(1));
+        assert_eq!(state.get_subdivision_interval(), Duration::from_millis(250));
+        
+        // Sixteenths swing within each eighth; triplets cannot swing
+        assert_eq!(Subdivision::Sixteenths.click_offset(1, 0.75), 0.375);
+        assert_eq!(Subdivision::Sixteenths.click_offset(2, 0.75), 0.5);
+        assert_eq!(Subdivision::Sixteenths.click_offset(3, 0.75), 0.875);
+        assert_eq!(Subdivision::Triplets.click_offset(1, 0.75), 1.0 / 3.0);
+        assert!(!Subdivision::Triplets.can_swing());
+        assert_eq!(Subdivision::Eighths.click_offset(1, STRAIGHT_SWING), 0.5);
+        
+        assert!(matches!(state.update_swing(0.8), Err(MetronomeError::InvalidSwing(_))));
+        assert!(state.update_swing(0.4).is_err());
+        assert_eq!(state.swing, 0.75);
+        state.update_swing(STRAIGHT_SWING).unwrap();
+        assert_eq!(state.get_click_offset(1), 0.5);
+        
+        // A config with a swing out of range, e.g. edited by hand, is rejected
+        let mut config = MetronomeConfig::new(120).with_swing(MAX_SWING);
+        assert!(config.validate().is_ok());
+        config.swing = 3.0;
+        assert!(matches!(config.validate(), Err(MetronomeError::InvalidSwing(swing)) if swing == 3.0));
+        config.swing = 0.2;
         assert!(config.validate().is_err());
+    }
+    
+    #[cfg(feature = "gui")]
+    #[test]
+    fn test_config_load_rejects_invalid_swing() {
+        let path = std::env::temp_dir().join(format!("cli-metronome-swing-{}.json", std::process::id()));
+        let mut json = serde_json::to_value(MetronomeConfig::new(120)).unwrap();
+        json["swing"] = serde_json::json!(3.0);
+        std::fs::write(&path, json.to_string()).unwrap();
+        assert!(matches!(MetronomeConfig::load_from_file(&path), Err(MetronomeError::InvalidSwing(_))));
+        std::fs::remove_file(&path).unwrap();
+    }
+    
+    #[test]
+    fn test_subdivision_change_keeps_position() {
+        let config = MetronomeConfig::new(120).with_subdivision(Subdivision::Eighths);
+        let mut state = MetronomeState::new(&config);
+        state.start();
+        state.increment_beat();
+        state.increment_beat(); // the "and" of beat 1
+        
+        // Halfway through the beat in sixteenths is the third click
+        state.update_subdivision(Subdivision::Sixteenths);
+        assert_eq!(state.current_subdivision, 2);
+        assert_eq!(state.get_next_click_position(), (1, 3));
+        
+        // A third of the way into a triplet beat rounds down to the downbeat in eighths
+        state.update_subdivision(Subdivision::Triplets);
+        state.current_subdivision = 1;
+        state.update_subdivision(Subdivision::Eighths);
+        assert_eq!(state.get_next_click_position(), (1, 1));
+    }
+    
+    #[test]
+    fn test_accent_pattern_levels() {
+        assert_eq!(AccentPattern::default_for(TimeSignature::new(4, 4)).levels(),
+            &[AccentLevel::Strong, AccentLevel::Weak, AccentLevel::Medium, AccentLevel::Weak]);
+        assert_eq!(AccentPattern::default_for(TimeSignature::new(1, 4)).levels(), &[AccentLevel::Weak]);
+        
+        // Cycling visits every level and wraps around
+        let mut pattern = AccentPattern::default_for(TimeSignature::new(3, 4));
+        for expected in [AccentLevel::Medium, AccentLevel::Weak, AccentLevel::Mute, AccentLevel::Strong] {
+            pattern.cycle_level(1);
+            assert_eq!(pattern.level(1), expected);
+        }
+        assert_eq!(pattern.level(0), AccentLevel::Weak);
+        assert_eq!(pattern.level(4), AccentLevel::Weak);
+        
+        assert!(AccentPattern::new(Vec::new()).is_err());
+        assert!(AccentPattern::new(vec![AccentLevel::Weak; 33]).is_err());
+    }
+    
+    #[cfg(feature = "gui")]
+    #[test]
+    fn test_accent_pattern_deserialize_is_validated() {
+        let pattern = AccentPattern::new(vec![AccentLevel::Strong, AccentLevel::Mute]).unwrap();
+        let json = serde_json::to_string(&pattern).unwrap();
+        assert_eq!(json, r#"["Strong","Mute"]"#);
+        assert_eq!(serde_json::from_str::<AccentPattern>(&json).unwrap(), pattern);
+        
+        // Loading bypasses neither the beat count check nor the level names
+        assert!(serde_json::from_str::<AccentPattern>("[]").is_err());
+        let too_long = serde_json::to_string(&vec![AccentLevel::Weak; 33]).unwrap();
+        assert!(serde_json::from_str::<AccentPattern>(&too_long).is_err());
+        assert!(serde_json::from_str::<AccentPattern>(r#"["Strong","Loud"]"#).is_err());
+    }
+    
+    #[test]
+    fn test_accent_pattern_drives_sounds() {
+        // "Click only on 2 and 4"
+        let backbeat = AccentPattern::new(vec![
+            AccentLevel::Mute, AccentLevel::Strong, AccentLevel::Mute, AccentLevel::Strong,
+        ]).unwrap();
+        let config = MetronomeConfig::new(120).with_accent_pattern(backbeat);
+        let mut state = MetronomeState::new(&config);
+        state.start();
+        
+        let beats: Vec<Beat> = (0..4).map(|_| state.increment_beat()).collect();
+        assert!(beats[0].is_muted() && beats[2].is_muted());
+        assert!(beats[1].is_strong_beat() && beats[3].is_strong_beat());
+        assert_eq!(state.get_sound_for_beat(&beats[0]).1, 0.0);
+        assert_eq!(state.get_sound_for_beat(&beats[1]), (SoundType::BuiltinWood, 0.7));
+        
+        // Medium beats use the accent sound at a lower level, weak beats the beat sound
+        let pattern = AccentPattern::new(vec![
+            AccentLevel::Strong, AccentLevel::Medium, AccentLevel::Weak, AccentLevel::Weak,
+        ]).unwrap();
+        state.update_accent_pattern(Some(pattern)).unwrap();
+        let beat = state.increment_beat();
+        assert_eq!(state.get_sound_for_beat(&beat), (SoundType::BuiltinWood, 0.7));
+        let beat = state.increment_beat();
+        assert!(beat.is_medium_beat());
+        assert_eq!(state.get_sound_for_beat(&beat), (SoundType::BuiltinWood, 0.7 * MEDIUM_ACCENT_LEVEL));
+        let beat = state.increment_beat();
+        assert_eq!(state.get_sound_for_beat(&beat), (SoundType::BuiltinClick, 0.7));
+        
+        // Disabling accents plays every unmuted beat as a regular beat
+        state.update_accent_enabled(false);
+        state.update_accent_pattern(Some(AccentPattern::new(vec![AccentLevel::Mute, AccentLevel::Strong, AccentLevel::Weak, AccentLevel::Weak]).unwrap())).unwrap();
+        state.increment_beat(); // finish the measure
+        let beat = state.increment_beat();
+        assert!(beat.is_muted());
+        let beat = state.increment_beat();
+        assert!(!beat.is_accent);
+        assert_eq!(state.get_sound_for_beat(&beat), (SoundType::BuiltinClick, 0.7));
+    }
+    
+    #[test]
+    fn test_accent_pattern_follows_measure_length() {
+        let mut state = MetronomeState::new(&MetronomeConfig::new(120));
+        let five = AccentPattern::new(vec![AccentLevel::Strong; 5]).unwrap();
+        assert!(matches!(
+            state.update_accent_pattern(Some(five)),
+            Err(MetronomeError::InvalidAccentPattern(_))
+        ));
+        
+        let four = AccentPattern::new(vec![AccentLevel::Weak; 4]).unwrap();
+        state.update_accent_pattern(Some(four.clone())).unwrap();
+        assert_eq!(state.get_effective_accent_pattern(), four);
+        
+        // Same length keeps the pattern, a different length returns to the default
+        state.update_time_signature(TimeSignature::new(4, 8));
+        assert_eq!(state.accent_pattern, Some(four));
+        state.update_time_signature(TimeSignature::new(3, 4));
+        assert_eq!(state.accent_pattern, None);
+        assert_eq!(state.get_effective_accent_pattern(), AccentPattern::default_for(TimeSignature::new(3, 4)));
+    }
+    
+    #[test]
+    fn test_tempo_ramp_curves() {
+        let ramp = TempoRamp::new(90, 140, 32).unwrap();
+        assert_eq!(ramp.tempo_at(0.0), 90.0);
+        assert_eq!(ramp.tempo_at(0.5), 115.0);
+        assert_eq!(ramp.tempo_at(1.0), 140.0);
+        assert_eq!(ramp.tempo_at(2.0), 140.0);
+        assert_eq!(ramp.total_beats(TimeSignature::new(4, 4)), 128);
+        
+        // Exponential ramps pass through the geometric mean halfway
+        let exponential = TempoRamp::new(80, 180, 4).unwrap().with_curve(TempoCurve::Exponential);
+        assert!((exponential.tempo_at(0.5) - 120.0).abs() < 1e-9);
+        assert_eq!(exponential.tempo_at(1.0), 180.0);
+        
+        // Ritardando works the same way downwards
+        assert_eq!(TempoRamp::new(140, 90, 4).unwrap().tempo_at(0.5), 115.0);
+        
+        assert!(matches!(TempoRamp::new(10, 140, 4), Err(MetronomeError::InvalidBpm(bpm)) if bpm == 10.0));
+        assert!(matches!(TempoRamp::new(90, 140, 0), Err(MetronomeError::InvalidTempoRamp(_))));
+        assert_eq!("Exponential".parse::<TempoCurve>(), Ok(TempoCurve::Exponential));
+    }
+    
+    #[test]
+    fn test_tempo_ramp_schedules_every_beat() {
+        // 90 to 140 BPM over two bars of 4/4
+        let ramp = TempoRamp::new(90, 140, 2).unwrap();
+        let config = MetronomeConfig::new(120).with_tempo_ramp(ramp);
+        let mut state = MetronomeState::new(&config);
+        state.start();
+        assert_eq!(state.bpm, 90);
+        
+        let start_time = state.start_time.unwrap();
+        let mut expected_offset = 0.0;
+        for beats_done in 0..8u32 {
+            // Each interval uses the tempo reached after the beats played so far
+            let tempo = 90.0 + 50.0 * beats_done as f64 / 8.0;
+            assert!((state.get_current_tempo() - tempo).abs() < 1e-9);
+            expected_offset += 60.0 / tempo;
+            
+            let scheduled = state.get_next_beat_time().unwrap();
+            assert!((scheduled.duration_since(start_time).as_secs_f64() - expected_offset).abs() < 1e-6);
+            state.increment_beat_at(scheduled);
+        }
+        
+        // The target is held once the ramp is over
+        assert_eq!(state.bpm, 140);
+        assert_eq!(state.tempo_ramp, None);
+        assert_eq!(state.get_current_tempo(), 140.0);
+        assert_eq!(state.get_target_bpm(), None);
+    }
+    
+    #[test]
+    fn test_tempo_ramp_starts_from_next_beat_and_cancels() {
+        let mut state = MetronomeState::new(&MetronomeConfig::new(100));
+        state.start();
+        for _ in 0..5 {
+            state.increment_beat();
+        }
+        
+        state.update_tempo_ramp(Some(TempoRamp::new(100, 60, 1).unwrap()));
+        assert_eq!(state.get_ramp_progress(), Some(0.0));
+        assert_eq!(state.get_target_bpm(), Some(Tempo::from(60)));
+        state.increment_beat();
+        state.increment_beat();
+        assert_eq!(state.get_ramp_progress(), Some(0.5));
+        assert_eq!(state.bpm, 80);
         
-        let config = MetronomeConfig::new(201);
+        // Setting the tempo directly ends the ramp
+        state.update_bpm(120).unwrap();
+        assert_eq!(state.tempo_ramp, None);
+        assert_eq!(state.get_current_tempo(), 120.0);
+    }
+    
+    #[test]
+    fn test_speed_trainer_steps() {
+        let trainer = SpeedTrainer::new(80, 95, 5, 4).unwrap();
+        assert_eq!(trainer.next_bpm(Tempo::from(80)), Some(Tempo::from(85)));
+        assert_eq!(trainer.next_bpm(Tempo::from(93)), Some(Tempo::from(95)));
+        assert_eq!(trainer.next_bpm(Tempo::from(95)), None);
+        assert_eq!(trainer.with_end(TrainerEnd::Loop).unwrap().next_bpm(Tempo::from(95)), Some(Tempo::from(80)));
+        assert_eq!(trainer.with_end(TrainerEnd::DropBack(10)).unwrap().next_bpm(Tempo::from(95)), Some(Tempo::from(85)));
+        assert_eq!(trainer.with_end(TrainerEnd::DropBack(50)).unwrap().next_bpm(Tempo::from(95)), Some(Tempo::from(80)));
+        assert_eq!(trainer.beats_per_step(TimeSignature::new(7, 8)), 28);
+        
+        assert!(matches!(SpeedTrainer::new(120, 100, 5, 4), Err(MetronomeError::InvalidSpeedTrainer(_))));
+        assert!(matches!(SpeedTrainer::new(80, 120, 0, 4), Err(MetronomeError::InvalidSpeedTrainer(_))));
+        assert!(matches!(SpeedTrainer::new(80, 120, 5, 0), Err(MetronomeError::InvalidSpeedTrainer(_))));
+        assert!(matches!(SpeedTrainer::new(80, 1200, 5, 4), Err(MetronomeError::InvalidBpm(bpm)) if bpm == 1200.0));
+        let fast = SpeedTrainer::new(80, 420, 5, 4).unwrap();
+        assert!(matches!(fast.validate(&TempoRange::default()), Err(MetronomeError::InvalidBpm(bpm)) if bpm == 420.0));
+        assert!(trainer.with_end(TrainerEnd::DropBack(0)).is_err());
+    }
+    
+    #[test]
+    fn test_ramps_and_trainers_follow_the_tempo_range() {
+        let wide = TempoRange::new(20.0, 600.0).unwrap();
+        let ramp = TempoRamp::new(300, 500, 4).unwrap();
+        let trainer = SpeedTrainer::new(300, 500, 10, 2).unwrap();
+        assert!(matches!(ramp.validate(&TempoRange::default()), Err(MetronomeError::InvalidBpm(bpm)) if bpm == 500.0));
+        assert!(ramp.validate(&wide).is_ok());
+        assert!(trainer.validate(&TempoRange::default()).is_err());
+        assert!(trainer.validate(&wide).is_ok());
+        
+        // A configuration checks its ramp and trainer against its own range
+        let config = MetronomeConfig::new(300).with_tempo_ramp(ramp);
         assert!(config.validate().is_err());
+        assert!(config.with_tempo_range(wide).validate().is_ok());
+        let config = MetronomeConfig::new(300).with_speed_trainer(trainer).with_tempo_range(wide);
+        assert!(config.validate().is_ok());
+        assert!(config.with_tempo_range(TempoRange::new(60.0, 400.0).unwrap()).validate().is_err());
+        
+        // The GUI builds them within its range
+        let mut gui_state = GuiState::new();
+        gui_state.tempo_range = wide;
+        gui_state.bpm_input = "450".to_string();
+        gui_state.ramp_enabled = true;
+        gui_state.ramp_target_bpm = 500.0;
+        assert_eq!(gui_state.get_tempo_ramp().unwrap().unwrap().target_bpm, 500);
+        gui_state.tempo_range = TempoRange::new(20.0, 480.0).unwrap();
+        assert!(gui_state.get_tempo_ramp().is_err());
     }
     
     #[test]
-    fn test_metronome_config_builder() {
+    fn test_tempo_ramp_keeps_fractional_tempos() {
+        // 90 to 100 BPM over one bar of 4/4 moves 2.5 BPM per beat
+        let config = MetronomeConfig::new(90).with_tempo_ramp(TempoRamp::new(90, 100, 1).unwrap());
+        let mut state = MetronomeState::new(&config);
+        state.start();
+        state.increment_beat();
+        assert_eq!(state.bpm.bpm(), 92.5);
+        assert!((state.get_interval().as_secs_f64() - 60.0 / 92.5).abs() < 1e-9);
+    }
+    
+    #[test]
+    fn test_speed_trainer_changes_tempo_at_bar_lines() {
+        // 80 to 90 BPM in steps of 5 every bar of 3/4, then loop
+        let trainer = SpeedTrainer::new(80, 90, 5, 1).unwrap()
+            .with_end(TrainerEnd::Loop).unwrap();
         let config = MetronomeConfig::new(120)
-            .with_sound(false)
-            .with_visual(true)
-            .with_accent_enabled(false);
+            .with_time_signature(TimeSignature::new(3, 4))
+            .with_speed_trainer(trainer);
+        let mut state = MetronomeState::new(&config);
+        state.start();
+        assert_eq!(state.bpm, 80);
         
-        assert!(!config.sound_enabled);
-        assert!(config.visual_enabled);
-        assert!(!config.accent_enabled);
+        let start_time = state.start_time.unwrap();
+        let mut expected_offset = 0.0;
+        let mut tempos = Vec::new();
+        for _ in 0..12 {
+            expected_offset += 60.0 / state.bpm.bpm();
+            let scheduled = state.get_next_beat_time().unwrap();
+            assert!((scheduled.duration_since(start_time).as_secs_f64() - expected_offset).abs() < 1e-6);
+            let beat = state.increment_beat_at(scheduled);
+            
+            // The first beat at a new tempo is a downbeat
+            if tempos.last().is_some_and(|&bpm| bpm != beat.bpm) {
+                assert_eq!(beat.beat_in_measure, 1);
+            }
+            tempos.push(beat.bpm);
+        }
+        
+        // Every bar has a single tempo and the fourth bar starts over
+        assert_eq!(tempos, vec![80, 80, 80, 85, 85, 85, 90, 90, 90, 80, 80, 80]);
+        assert_eq!(state.trainer_step, 3);
     }
     
     #[test]
-    fn test_beat_creation() {
-        let beat = Beat::new(1, TimeSignature::Four, 120);
-        assert_eq!(beat.sequence_number, 1);
-        assert_eq!(beat.bpm, 120);
-        assert_eq!(beat.beat_in_measure, 1);
-        assert!(beat.is_accent);
-        assert!(beat.is_first_beat());
+    fn test_speed_trainer_holds_target_and_cancels() {
+        let trainer = SpeedTrainer::new(100, 105, 5, 1).unwrap();
+        let mut state = MetronomeState::new(&MetronomeConfig::new(120));
+        state.start();
+        state.update_speed_trainer(Some(trainer));
+        assert_eq!(state.bpm, 100);
+        for _ in 0..12 {
+            state.increment_beat();
+        }
+        
+        // The target is held and the trainer stays active
+        assert_eq!(state.bpm, 105);
+        assert_eq!(state.trainer_step, 1);
+        assert_eq!(state.speed_trainer, Some(trainer));
+        
+        // A ramp replaces the trainer, and a direct tempo change ends either
+        state.update_tempo_ramp(Some(TempoRamp::new(105, 120, 2).unwrap()));
+        assert_eq!(state.speed_trainer, None);
+        state.update_speed_trainer(Some(trainer));
+        assert_eq!(state.tempo_ramp, None);
+        state.update_bpm(150).unwrap();
+        assert_eq!(state.speed_trainer, None);
+        assert_eq!(state.bpm, 150);
     }
     
     #[test]
-    fn test_beat_creation_with_zero_sequence() {
-        // Test that Beat::new handles sequence_number 0 without panicking
-        let beat = Beat::new(0, TimeSignature::Four, 120);
-        assert_eq!(beat.sequence_number, 1); // Should be normalized to 1
-        assert_eq!(beat.bpm, 120);
-        assert_eq!(beat.beat_in_measure, 1);
-        assert!(beat.is_accent);
-        assert!(beat.is_first_beat());
+    fn test_gap_click_sections() {
+        let gap_click = GapClick::new(2, 1).unwrap();
+        let silent: Vec<bool> = (0..6).map(|bar| gap_click.is_silent_bar(bar)).collect();
+        assert_eq!(silent, vec![false, false, true, false, false, true]);
+        assert_eq!(gap_click.bar_in_section(1), 2);
+        assert_eq!(gap_click.bar_in_section(2), 1);
+        
+        assert_eq!("4:2".parse::<GapClick>().unwrap(), GapClick::new(4, 2).unwrap());
+        assert!(matches!("4".parse::<GapClick>(), Err(MetronomeError::InvalidGapClick(_))));
+        assert!("0:2".parse::<GapClick>().is_err());
+        assert!("4:x".parse::<GapClick>().is_err());
+    }
+    
+    #[test]
+    fn test_gap_click_mutes_whole_bars() {
+        // One bar of 3/4 with the click, one silent, with eighth-note subdivisions
+        let config = MetronomeConfig::new(120)
+            .with_time_signature(TimeSignature::new(3, 4))
+            .with_gap_click(GapClick::new(1, 1).unwrap());
+        let mut state = MetronomeState::new(&config);
+        state.update_subdivision(Subdivision::Eighths);
+        state.start();
+        
+        let start_time = state.start_time.unwrap();
+        let mut muted = Vec::new();
+        for click in 0..18u32 {
+            let scheduled = state.get_next_beat_time().unwrap();
+            // Silent bars keep the grid, so the click returns exactly on the downbeat
+            assert!((scheduled.duration_since(start_time).as_secs_f64() - (0.5 + click as f64 * 0.25)).abs() < 1e-6);
+            let beat = state.increment_beat_at(scheduled);
+            muted.push(beat.is_muted());
+        }
+        
+        let expected: Vec<bool> = (0..18).map(|click| (6..12).contains(&click)).collect();
+        assert_eq!(muted, expected);
+        assert_eq!(state.beat_count, 9);
+        assert_eq!(state.get_gap_position(), Some((false, 1)));
+        
+        // The click was audible in the third bar and the fourth is silent again
+        let beat = state.increment_beat();
+        assert!(beat.is_muted());
+        assert!(beat.in_gap);
+        assert_eq!(state.get_gap_position(), Some((true, 1)));
+    }
+    
+    #[test]
+    fn test_gap_click_starts_on_next_downbeat() {
+        let mut state = MetronomeState::new(&MetronomeConfig::new(120));
+        state.start();
+        for _ in 0..2 {
+            state.increment_beat();
+        }
+        
+        // Set mid-bar: the rest of this bar plays and the cycle starts with bar 2
+        state.update_gap_click(Some(GapClick::new(1, 2).unwrap()));
+        assert_eq!(state.get_gap_position(), None);
+        let muted: Vec<bool> = (0..14).map(|_| state.increment_beat().is_muted()).collect();
+        let mut expected = vec![false; 6];
+        expected.extend([true; 8]);
+        assert_eq!(muted, expected);
+        assert_eq!(state.get_gap_position(), Some((true, 2)));
+        
+        // Ending the training brings the click straight back
+        state.update_gap_click(None);
+        assert!(!state.increment_beat().is_muted());
+        assert_eq!(state.get_gap_position(), None);
     }
     
     #[test]
-    fn test_beat_accent_enabled_disabled() {
-        // Test Beat with accents enabled (default)
-        let beat_enabled = Beat::new_with_accent_setting(1, TimeSignature::Four, 120, true);
-        assert!(beat_enabled.accent_enabled);
-        assert_eq!(beat_enabled.get_accent_strength(), 1.0); // Strong beat
+    fn test_dropout_is_reproducible_with_seed() {
+        let dropout = BeatDropout::new(0.5).unwrap().with_seed(42);
+        let config = MetronomeConfig::new(120).with_dropout(dropout);
+        let run = || {
+            let mut state = MetronomeState::new(&config);
+            state.start();
+            (0..64).map(|_| state.increment_beat()).collect::<Vec<Beat>>()
+        };
         
-        let beat_enabled_weak = Beat::new_with_accent_setting(2, TimeSignature::Four, 120, true);
-        assert!(beat_enabled_weak.accent_enabled);
-        assert_eq!(beat_enabled_weak.get_accent_strength(), 0.0); // Weak beat
+        let first: Vec<bool> = run().iter().map(|beat| beat.dropped).collect();
+        let second: Vec<bool> = run().iter().map(|beat| beat.dropped).collect();
+        assert_eq!(first, second);
         
-        // Test Beat with accents disabled
-        let beat_disabled = Beat::new_with_accent_setting(1, TimeSignature::Four, 120, false);
-        assert!(!beat_disabled.accent_enabled);
-        assert_eq!(beat_disabled.get_accent_strength(), 0.0); // No accent when disabled
+        // Beat 1 is kept, while other beats drop out roughly half of the time
+        let beats = run();
+        assert!(beats.iter().filter(|beat| beat.beat_in_measure == 1).all(|beat| !beat.is_muted()));
+        let dropped = beats.iter().filter(|beat| beat.dropped).count();
+        assert!(dropped > 8 && dropped < 40, "dropped {} of 48 eligible beats", dropped);
         
-        let beat_disabled_weak = Beat::new_with_accent_setting(2, TimeSignature::Four, 120, false);
-        assert!(!beat_disabled_weak.accent_enabled);
-        assert_eq!(beat_disabled_weak.get_accent_strength(), 0.0); // Still no accent
+        // Dropped beats still arrive as events, in sequence, but are silent
+        assert!(beats.iter().enumerate().all(|(i, beat)| beat.sequence_number == i as u64 + 1));
+        assert!(beats.iter().filter(|beat| beat.dropped).all(|beat| beat.is_muted()));
     }
     
     #[test]
-    fn test_time_signature() {
-        assert_eq!(TimeSignature::Four.beats_per_measure(), 4);
-        assert_eq!(TimeSignature::Three.beats_per_measure(), 3);
-        assert_eq!(TimeSignature::Two.beats_per_measure(), 2);
-        assert_eq!(TimeSignature::Six.beats_per_measure(), 6);
+    fn test_dropout_probability_and_downbeat() {
+        assert!(matches!(BeatDropout::new(1.5), Err(MetronomeError::InvalidDropout(_))));
+        assert!(BeatDropout::new(-0.1).is_err());
+        assert!(BeatDropout::new(f32::NAN).is_err());
+        
+        // Every click but the main click of the downbeat drops, its subdivision included
+        let config = MetronomeConfig::new(120)
+            .with_subdivision(Subdivision::Eighths)
+            .with_dropout(BeatDropout::new(1.0).unwrap().with_seed(1));
+        let mut state = MetronomeState::new(&config);
+        state.start();
+        let muted: Vec<bool> = (0..8).map(|_| state.increment_beat().is_muted()).collect();
+        assert_eq!(muted, vec![false, true, true, true, true, true, true, true]);
         
-        assert_eq!(TimeSignature::Four.as_str(), "4/4");
-        assert_eq!(TimeSignature::Three.as_str(), "3/4");
+        // A beat's main click and its subdivision clicks are decided separately
+        state.update_dropout(Some(BeatDropout::new(0.5).unwrap().with_seed(7)));
+        let clicks: Vec<Beat> = (0..256).map(|_| state.increment_beat()).collect();
+        assert!(clicks.chunks(2).any(|beat| beat[0].dropped != beat[1].dropped));
+        
+        // Without keeping the downbeat, everything drops; with no chance, nothing does
+        state.update_dropout(Some(BeatDropout::new(1.0).unwrap().with_keep_downbeat(false)));
+        assert!((0..8).all(|_| state.increment_beat().is_muted()));
+        state.update_dropout(Some(BeatDropout::new(0.0).unwrap()));
+        assert!((0..8).all(|_| !state.increment_beat().is_muted()));
+        state.update_dropout(None);
+        assert!(!state.increment_beat().dropped);
     }
     
     #[test]
-    fn test_sound_type() {
-        assert_eq!(SoundType::BuiltinClick.as_str(), "Click");
-        assert_eq!(SoundType::BuiltinWood.as_str(), "Wood");
-        assert_eq!(SoundType::BuiltinBeep.as_str(), "Beep");
-        
-        assert!(SoundType::BuiltinClick.is_builtin());
-        assert!(!SoundType::Custom(std::path::PathBuf::from("test.wav")).is_builtin());
+    fn test_dropout_probability_is_validated_in_config() {
+        let mut config = MetronomeConfig::new(120).with_dropout(BeatDropout::new(0.3).unwrap());
+        assert!(config.validate().is_ok());
+        config.dropout = Some(BeatDropout { probability: 5.0, keep_downbeat: true, seed: None });
+        assert!(matches!(config.validate(), Err(MetronomeError::InvalidDropout(_))));
+        config.dropout = Some(BeatDropout { probability: -0.5, keep_downbeat: false, seed: None });
+        assert!(config.validate().is_err());
     }
     
+    #[cfg(feature = "gui")]
     #[test]
-    fn test_beat_sequence_in_measure() {
-        // Test 4/4 time signature
-        let beat1 = Beat::new(1, TimeSignature::Four, 120);
-        let beat2 = Beat::new(2, TimeSignature::Four, 120);
-        let beat3 = Beat::new(3, TimeSignature::Four, 120);
-        let beat4 = Beat::new(4, TimeSignature::Four, 120);
-        let beat5 = Beat::new(5, TimeSignature::Four, 120); // Next measure
+    fn test_config_load_rejects_invalid_gap_click() {
+        let path = std::env::temp_dir().join(format!("cli-metronome-gap-{}.json", std::process::id()));
+        let mut json = serde_json::to_value(MetronomeConfig::new(120)).unwrap();
+        json["gap_click"] = serde_json::json!({"play_bars": 0, "mute_bars": 0});
+        std::fs::write(&path, json.to_string()).unwrap();
+        assert!(matches!(MetronomeConfig::load_from_file(&path), Err(MetronomeError::InvalidGapClick(_))));
         
-        assert_eq!(beat1.beat_in_measure, 1);
-        assert!(beat1.is_accent);
+        json["gap_click"] = serde_json::json!({"play_bars": 3, "mute_bars": 1});
+        std::fs::write(&path, json.to_string()).unwrap();
+        let loaded = MetronomeConfig::load_from_file(&path).unwrap();
+        assert_eq!(loaded.gap_click, Some(GapClick::new(3, 1).unwrap()));
+        std::fs::remove_file(&path).unwrap();
+    }
+    
+    #[cfg(feature = "gui")]
+    #[test]
+    fn test_config_load_rejects_invalid_dropout() {
+        let path = std::env::temp_dir().join(format!("cli-metronome-dropout-{}.json", std::process::id()));
+        let mut json = serde_json::to_value(MetronomeConfig::new(120)).unwrap();
+        json["dropout"] = serde_json::json!({"probability": 5.0, "keep_downbeat": true, "seed": null});
+        std::fs::write(&path, json.to_string()).unwrap();
+        assert!(matches!(MetronomeConfig::load_from_file(&path), Err(MetronomeError::InvalidDropout(_))));
         
-        assert_eq!(beat2.beat_in_measure, 2);
-        assert!(!beat2.is_accent);
+        json["dropout"]["probability"] = serde_json::json!(0.25);
+        std::fs::write(&path, json.to_string()).unwrap();
+        let loaded = MetronomeConfig::load_from_file(&path).unwrap();
+        assert_eq!(loaded.dropout.unwrap().probability, 0.25);
+        std::fs::remove_file(&path).unwrap();
+    }
+    
+    #[test]
+    fn test_setlist_navigation_and_reordering() {
+        let mut setlist = Setlist::new();
+        for (name, bpm) in [("One", 100), ("Two", 120), ("Three", 140), ("Four", 160)] {
+            setlist.add_song(Song::new(name, bpm).unwrap());
+        }
+        assert_eq!(setlist.current_song().unwrap().name, "One");
+        assert!(setlist.previous_song().is_none());
+        assert_eq!(setlist.next_song().unwrap().name, "Two");
+        assert_eq!(setlist.select(3).unwrap().name, "Four");
+        assert!(setlist.next_song().is_none());
+        assert_eq!(setlist.current_index(), 3);
         
-        assert_eq!(beat3.beat_in_measure, 3);
-        // In 4/4 time, beat 3 is a medium accent (not a strong accent but not weak either)
-        assert!(beat3.is_accent); // This is now true due to our enhanced accent logic
+        // The selection follows the selected song when the list changes
+        setlist.select(1);
+        setlist.move_song(1, 3);
+        assert_eq!(setlist.current_song().unwrap().name, "Two");
+        setlist.move_song(0, 3);
+        assert_eq!(setlist.current_index(), 2);
+        assert_eq!(setlist.current_song().unwrap().name, "Two");
+        let names: Vec<&str> = setlist.songs.iter().map(|song| song.name.as_str()).collect();
+        assert_eq!(names, vec!["Three", "Four", "Two", "One"]);
         
-        assert_eq!(beat4.beat_in_measure, 4);
-        assert!(!beat4.is_accent);
+        setlist.remove_song(0);
+        assert_eq!(setlist.current_song().unwrap().name, "Two");
         
-        assert_eq!(beat5.beat_in_measure, 1); // Back to first beat of next measure
-        assert!(beat5.is_accent);
+        // Removing the selected song selects the one that took its place
+        setlist.remove_song(1);
+        assert_eq!(setlist.current_song().unwrap().name, "One");
+        setlist.remove_song(1);
+        assert_eq!(setlist.current_song().unwrap().name, "Four");
+        assert!(setlist.remove_song(5).is_none());
     }
     
     #[test]
-    fn test_metronome_state_creation() {
-        let config = MetronomeConfig::new(120);
-        let state = MetronomeState::new(&config);
+    fn test_update_song_keeps_sections_in_step() {
+        let mut setlist = Setlist::new()
+            .with_song(Song::new("Suite", 100).unwrap()
+                .with_section(SongSection::new(8, 100, TimeSignature::new(4, 4)).unwrap().with_label("A"))
+                .with_section(SongSection::new(2, 130, TimeSignature::new(7, 8)).unwrap()))
+            .with_song(Song::new("Plain", 90).unwrap());
         
-        assert_eq!(state.bpm, 120);
-        assert_eq!(state.time_signature, TimeSignature::Four);
-        assert!(!state.is_running);
-        assert_eq!(state.beat_count, 0);
-        assert_eq!(state.current_beat_in_measure, 1);
+        // The first section follows the new header; later sections are left alone
+        let updated = Song::new("Suite", 112).unwrap().with_time_signature(TimeSignature::new(3, 4));
+        let song = setlist.update_song(0, updated).unwrap().clone();
+        assert_eq!(song.bpm, song.sections[0].bpm);
+        assert_eq!(song.time_signature, song.sections[0].time_signature);
+        assert_eq!(song.sections[0].bpm, Tempo::from(112));
+        assert_eq!(song.sections[0].label.as_deref(), Some("A"));
+        assert_eq!(song.sections[1], SongSection::new(2, 130, TimeSignature::new(7, 8)).unwrap());
+        
+        // Songs without sections are simply replaced
+        let song = setlist.update_song(1, Song::new("Plain", 95).unwrap()).unwrap();
+        assert_eq!(song.bpm, Tempo::from(95));
+        assert!(song.sections.is_empty());
+        assert!(setlist.update_song(2, Song::new("Missing", 95).unwrap()).is_none());
     }
     
     #[test]
-    fn test_metronome_state_start_stop() {
-        let config = MetronomeConfig::new(120);
-        let mut state = MetronomeState::new(&config);
+    fn test_song_validation_and_apply() {
+        assert!(matches!(Song::new("  ", 120), Err(MetronomeError::InvalidSetlist(_))));
+        assert!(matches!(Song::new("Fast", 1200), Err(MetronomeError::InvalidBpm(bpm)) if bpm == 1200.0));
+        let mismatched = Song::new("Waltz", 90).unwrap()
+            .with_time_signature(TimeSignature::new(3, 4))
+            .with_accent_pattern(AccentPattern::default_for(TimeSignature::new(4, 4)));
+        assert!(mismatched.validate(&TempoRange::default()).is_err());
         
-        assert!(!state.is_running);
+        // Songs are checked against the tempo range they are played in
+        let fast = Song::new("Fast", 420).unwrap();
+        assert!(matches!(fast.validate(&TempoRange::default()), Err(MetronomeError::InvalidBpm(bpm)) if bpm == 420.0));
+        let mut state = MetronomeState::new(&MetronomeConfig::new(120));
+        assert!(state.apply_song(&fast).is_err());
+        state.update_tempo_range(TempoRange::new(20.0, 500.0).unwrap()).unwrap();
+        state.apply_song(&fast).unwrap();
+        assert_eq!(state.bpm, 420);
+        
+        let backbeat = AccentPattern::new(vec![AccentLevel::Mute, AccentLevel::Strong]).unwrap();
+        let song = Song::new("Shuffle", 150).unwrap()
+            .with_time_signature(TimeSignature::new(2, 4))
+            .with_accent_pattern(backbeat.clone())
+            .with_sounds(SoundType::BuiltinBeep, SoundType::BuiltinClick);
+        
+        let config = MetronomeConfig::new(100).with_tempo_ramp(TempoRamp::new(100, 120, 4).unwrap());
+        let mut state = MetronomeState::new(&config);
         state.start();
+        for _ in 0..5 {
+            state.increment_beat();
+        }
+        
+        // A new song starts over from beat 1 with all of its settings
+        state.apply_song(&song).unwrap();
         assert!(state.is_running);
-        assert!(state.start_time.is_some());
+        assert_eq!(state.beat_count, 0);
+        assert_eq!(state.bpm, 150);
+        assert_eq!(state.tempo_ramp, None);
+        assert_eq!(state.time_signature, TimeSignature::new(2, 4));
+        assert_eq!(state.accent_pattern, Some(backbeat));
+        assert_eq!(state.beat_sound, SoundType::BuiltinBeep);
+        assert_eq!(state.accent_sound, SoundType::BuiltinClick);
+        let beat = state.increment_beat();
+        assert_eq!(beat.beat_in_measure, 1);
+        assert!(beat.is_muted());
         
-        state.stop();
-        assert!(!state.is_running);
-        assert!(state.start_time.is_none());
+        let from_config = Song::from_config("Default", &MetronomeConfig::new(110)).unwrap();
+        assert_eq!(from_config, Song::new("Default", 110).unwrap());
     }
     
     #[test]
-    fn test_metronome_state_bpm_update() {
-        let config = MetronomeConfig::new(120);
-        let mut state = MetronomeState::new(&config);
+    fn test_song_sections_switch_at_bar_lines() {
+        // 2 bars of 4/4 at 120, 1 bar of 7/8 at 140, then 1 bar of 3/4 at 90
+        let song = Song::new("Odd Chart", 100).unwrap()
+            .with_section(SongSection::new(2, 120, TimeSignature::new(4, 4)).unwrap().with_label("Intro"))
+            .with_section(SongSection::new(1, 140, TimeSignature::new(7, 8)).unwrap().with_label("Break"))
+            .with_section(SongSection::new(1, 90, TimeSignature::new(3, 4)).unwrap());
+        assert_eq!((song.bpm, song.time_signature), (Tempo::from(120), TimeSignature::new(4, 4)));
+        assert_eq!(song.total_bars(), 4);
         
-        assert!(state.update_bpm(140).is_ok());
-        assert_eq!(state.bpm, 140);
+        let mut state = MetronomeState::new(&MetronomeConfig::new(100));
+        state.apply_song(&song).unwrap();
+        state.start();
+        assert_eq!(state.bpm, 120);
         
-        assert!(state.update_bpm(59).is_err());
-        assert!(state.update_bpm(201).is_err());
+        let start_time = state.start_time.unwrap();
+        let mut expected_offset = 0.0;
+        let mut previous_bpm = state.bpm;
+        let mut beats = Vec::new();
+        for _ in 0..22 {
+            // The interval leading into a bar line still belongs to the old tempo
+            expected_offset += 60.0 / previous_bpm.bpm();
+            let scheduled = state.get_next_beat_time().unwrap();
+            assert!((scheduled.duration_since(start_time).as_secs_f64() - expected_offset).abs() < 1e-6);
+            let beat = state.increment_beat_at(scheduled);
+            previous_bpm = beat.bpm;
+            beats.push(beat);
+        }
+        
+        let positions: Vec<_> = beats.iter()
+            .map(|beat| (beat.section_name.clone().unwrap(), beat.bar_in_section, beat.beat_in_measure, beat.bpm))
+            .collect();
+        assert_eq!(positions[7], ("Intro".to_string(), 2, 4, Tempo::from(120)));
+        assert_eq!(positions[8], ("Break".to_string(), 1, 1, Tempo::from(140)));
+        assert_eq!(positions[14], ("Break".to_string(), 1, 7, Tempo::from(140)));
+        assert_eq!(positions[15], ("Section 3".to_string(), 1, 1, Tempo::from(90)));
+        assert_eq!(beats[15].time_signature, TimeSignature::new(3, 4));
+        assert!(beats[15].is_first_beat());
+        
+        // After the last section the song starts over
+        assert_eq!(positions[18], ("Intro".to_string(), 1, 1, Tempo::from(120)));
+        assert_eq!(positions[21], ("Intro".to_string(), 1, 4, Tempo::from(120)));
+        assert_eq!(state.get_section_position(), Some((0, 1)));
+        
+        // Setting a tempo by hand leaves the song map behind
+        state.update_bpm(100).unwrap();
+        assert!(state.get_current_section().is_none());
+        assert_eq!(state.increment_beat().section_name, None);
     }
     
     #[test]
-    fn test_gui_state_creation() {
-        let gui_state = GuiState::new();
+    fn test_song_section_validation() {
+        assert!(matches!(SongSection::new(0, 120, TimeSignature::default()), Err(MetronomeError::InvalidSetlist(_))));
+        assert!(matches!(SongSection::new(4, 10, TimeSignature::default()), Err(MetronomeError::InvalidBpm(bpm)) if bpm == 10.0));
+        let section = SongSection::new(4, 120, TimeSignature::new(6, 8)).unwrap().with_label("  ");
+        assert_eq!(section.label, None);
+        assert_eq!(section.total_beats(), 24);
+        assert_eq!(section.display_name(1), "Section 2");
         
-        assert_eq!(gui_state.bpm_input, "120");
-        assert!(gui_state.bpm_valid);
-        assert_eq!(gui_state.selected_time_signature, TimeSignature::Four);
-        assert!(!gui_state.is_running);
-        assert!(gui_state.error_message.is_none());
+        let mut song = Song::new("Broken", 120).unwrap().with_section(section);
+        song.sections[0].bpm = Tempo::from(450);
+        match song.validate(&TempoRange::default()) {
+            Err(MetronomeError::InvalidSetlist(details)) => assert!(details.contains("'Broken', Section 1")),
+            other => panic!("expected a setlist error, got {:?}", other),
+        }
     }
     
     #[test]
-    fn test_gui_state_bpm_validation() {
-        let mut gui_state = GuiState::new();
+    fn test_gap_click_counts_bars_across_sections() {
+        let song = Song::new("Mixed", 120).unwrap()
+            .with_section(SongSection::new(1, 120, TimeSignature::new(4, 4)).unwrap())
+            .with_section(SongSection::new(1, 120, TimeSignature::new(3, 4)).unwrap());
+        let mut state = MetronomeState::new(&MetronomeConfig::new(120));
+        state.apply_song(&song).unwrap();
+        state.update_gap_click(Some(GapClick::new(1, 1).unwrap()));
+        state.start();
         
-        assert!(gui_state.validate_bpm("120").is_ok());
-        assert!(gui_state.bpm_valid);
-        assert!(gui_state.error_message.is_none());
+        // One bar with the click, one silent, whatever the length of each bar
+        let gaps: Vec<_> = (0..14).map(|_| state.increment_beat().in_gap).collect();
+        assert_eq!(gaps, [
+            vec![false; 4], vec![true; 3], vec![false; 4], vec![true; 3],
+        ].concat());
+    }
+    
+    #[test]
+    fn test_tap_tempo_averages_recent_taps() {
+        let start = Instant::now();
+        let mut tap_tempo = TapTempo::new().with_max_taps(5).unwrap();
+        assert_eq!(tap_tempo.tap_at(start), None);
         
-        assert!(gui_state.validate_bpm("59").is_err());
-        assert!(!gui_state.bpm_valid);
-        assert!(gui_state.error_message.is_some());
+        // Slightly uneven taps around 120 BPM average out
+        let offsets = [0.49, 1.0, 1.51, 2.0];
+        let tempos: Vec<_> = offsets.iter()
+            .map(|offset| tap_tempo.tap_at(start + Duration::from_secs_f64(*offset)).unwrap())
+            .collect();
+        assert!((tempos[0] - 60.0 / 0.49).abs() < 1e-6);
+        assert!((tempos[3] - 120.0).abs() < 1e-6);
+        assert_eq!(tap_tempo.bpm().unwrap().bpm().round(), 120.0);
         
-        assert!(gui_state.validate_bpm("invalid").is_err());
-        assert!(!gui_state.bpm_valid);
-        assert!(gui_state.error_message.is_some());
+        // Only the most recent taps count, so a new tempo takes over
+        for offset in [2.4, 2.8, 3.2, 3.6] {
+            tap_tempo.tap_at(start + Duration::from_secs_f64(offset));
+        }
+        assert_eq!(tap_tempo.tap_count(), 5);
+        assert!((tap_tempo.bpm().unwrap().bpm() - 150.0).abs() < 1e-6);
     }
     
     #[test]
-    fn test_gui_state_error_handling() {
-        let mut gui_state = GuiState::new();
+    fn test_tap_tempo_ignores_outliers_and_resets() {
+        let start = Instant::now();
+        let mut tap_tempo = TapTempo::new();
         
-        // Test setting and clearing errors
-        gui_state.set_error("Test error".to_string());
-        assert!(gui_state.error_message.is_some());
+        // A missed tap leaves a double interval that is left out of the average
+        for offset in [0.0, 0.5, 1.0, 2.0, 2.5, 3.0] {
+            tap_tempo.tap_at(start + Duration::from_secs_f64(offset));
+        }
+        assert!((tap_tempo.tempo().unwrap() - 120.0).abs() < 1e-6);
         
-        gui_state.clear_error();
-        assert!(gui_state.error_message.is_none());
+        // A repeated instant is not a tap
+        tap_tempo.tap_at(start + Duration::from_secs(3));
+        assert_eq!(tap_tempo.tap_count(), 6);
         
-        // Test handling results
-        let ok_result: Result<u32> = Ok(120);
-        let value = gui_state.handle_result(ok_result);
-        assert_eq!(value, Some(120));
-        assert!(gui_state.error_message.is_none());
+        // A long pause starts a new measurement
+        assert_eq!(tap_tempo.tap_at(start + Duration::from_secs(6)), None);
+        assert_eq!(tap_tempo.tap_count(), 1);
+        assert_eq!(tap_tempo.tap_at(start + Duration::from_secs_f64(6.125)), Some(480.0));
+        // Tempos above the default range are kept for a wider range to accept or reject
+        assert_eq!(tap_tempo.bpm(), Some(Tempo::from(480.0)));
+        tap_tempo.reset();
+        assert_eq!(tap_tempo.tempo(), None);
         
-        let err_result: Result<u32> = Err(MetronomeError::InvalidBpm(59));
-        let value = gui_state.handle_result(err_result);
-        assert_eq!(value, None);
-        assert!(gui_state.error_message.is_some());
+        assert!(matches!(TapTempo::new().with_max_taps(1), Err(MetronomeError::InvalidTapTempo(_))));
+        assert!(TapTempo::new().with_reset_after(Duration::ZERO).is_err());
     }
     
     #[test]
-    fn test_time_signature_intervals() {
-        let config = MetronomeConfig::new(120); // 120 BPM
+    fn test_count_in_precedes_first_bar() {
+        let config = MetronomeConfig::new(120)
+            .with_time_signature(TimeSignature::new(3, 4))
+            .with_subdivision(Subdivision::Eighths)
+            .with_count_in(CountIn::new(1).unwrap().with_sound(SoundType::BuiltinWood));
         let mut state = MetronomeState::new(&config);
+        state.start();
+        let start_time = state.start_time.unwrap();
+        assert!(state.is_counting_in());
         
-        // Test 4/4 time (default)
-        let interval_4_4 = state.calculate_beat_interval();
-        assert_eq!(interval_4_4, Duration::from_millis(500)); // 120 BPM = 500ms per beat
+        // Count-in beats are one beat apart with no subdivision clicks
+        for (index, beat_in_measure) in [1, 2, 3].into_iter().enumerate() {
+            let scheduled = state.get_next_beat_time().unwrap();
+            let beat = state.increment_beat_at(scheduled);
+            assert!(beat.count_in);
+            assert_eq!(beat.beat_in_measure, beat_in_measure);
+            assert_eq!(state.get_count_in_beat(), Some(beat_in_measure));
+            let offset = Duration::from_secs_f64(0.5 * (index + 1) as f64);
+            assert!(beat.scheduled_time.duration_since(start_time).abs_diff(offset) < Duration::from_micros(1));
+            
+            let expected_volume = if beat_in_measure == 1 { 0.7 } else { 0.7 * MEDIUM_ACCENT_LEVEL };
+            assert_eq!(state.get_sound_for_beat(&beat), (SoundType::BuiltinWood, expected_volume));
+        }
+        assert!(!state.is_counting_in());
         
-        // Test 6/8 time (compound time)
-        state.time_signature = TimeSignature::Six;
-        let interval_6_8 = state.calculate_beat_interval();
-        // In our implementation, 6/8 time uses the same interval as 4/4 (500ms per beat)
-        assert_eq!(interval_6_8, Duration::from_millis(500));
+        // Then the real first bar starts from beat 1, with its subdivisions
+        let scheduled = state.get_next_beat_time().unwrap();
+        let beat = state.increment_beat_at(scheduled);
+        assert!(!beat.count_in);
+        assert_eq!((beat.sequence_number, beat.beat_in_measure), (1, 1));
+        assert_eq!(state.get_count_in_beat(), None);
+        assert!(beat.scheduled_time.duration_since(start_time).abs_diff(Duration::from_secs(2)) < Duration::from_micros(1));
+        assert_eq!(state.get_next_click_position(), (1, 1));
         
-        // Test other time signatures
-        state.time_signature = TimeSignature::Three;
-        let interval_3_4 = state.calculate_beat_interval();
-        assert_eq!(interval_3_4, Duration::from_millis(500)); // Same as 4/4
+        // Restarting counts in again
+        state.stop();
+        state.start();
+        assert!(state.is_counting_in());
+        
+        assert!(matches!(CountIn::new(0), Err(MetronomeError::InvalidCountIn(_))));
+        assert!(CountIn::new(5).is_err());
+        assert_eq!(CountIn::new(2).unwrap().total_beats(TimeSignature::new(6, 8)), 12);
     }
     
+    #[cfg(feature = "gui")]
     #[test]
-    fn test_accent_patterns() {
-        let config = MetronomeConfig::new(120);
-        let mut state = MetronomeState::new(&config);
+    fn test_count_in_config_loads() {
+        // Configs saved before count-ins existed load without one
+        let config: MetronomeConfig = serde_json::from_str(r#"{
+            "bpm": 100,
+            "time_signature": "Four",
+            "beat_sound": "BuiltinClick",
+            "accent_sound": "BuiltinWood",
+            "sound_enabled": true,
+            "visual_enabled": true,
+            "accent_enabled": true,
+            "volume": 0.7
+        }"#).unwrap();
+        assert_eq!(config.count_in, None);
         
-        // Test 4/4 accent pattern
-        state.time_signature = TimeSignature::Four;
-        let pattern_4_4 = state.get_accent_pattern();
-        assert_eq!(pattern_4_4, vec![true, false, true, false]); // Strong-weak-medium-weak
+        let config = config.with_count_in(CountIn::new(2).unwrap().with_sound(SoundType::BuiltinWood));
+        let loaded: MetronomeConfig = serde_json::from_str(&serde_json::to_string(&config).unwrap()).unwrap();
+        assert_eq!(loaded.count_in, config.count_in);
         
-        // Test 3/4 accent pattern
-        state.time_signature = TimeSignature::Three;
-        let pattern_3_4 = state.get_accent_pattern();
-        assert_eq!(pattern_3_4, vec![true, false, false]); // Strong-weak-weak
+        // The sound defaults to the beep
+        let count_in: CountIn = serde_json::from_str(r#"{"bars": 1}"#).unwrap();
+        assert_eq!(count_in, CountIn::new(1).unwrap());
         
-        // Test 6/8 accent pattern
-        state.time_signature = TimeSignature::Six;
-        let pattern_6_8 = state.get_accent_pattern();
-        assert_eq!(pattern_6_8, vec![true, false, false, true, false, false]); // Strong-weak-weak-medium-weak-weak
+        // Loaded count-ins get the same bar check as new ones
+        let path = std::env::temp_dir().join(format!("cli-metronome-count-in-{}.json", std::process::id()));
+        for bars in [0, 500] {
+            let mut json = serde_json::to_value(&config).unwrap();
+            json["count_in"]["bars"] = serde_json::json!(bars);
+            std::fs::write(&path, json.to_string()).unwrap();
+            assert!(matches!(MetronomeConfig::load_from_file(&path), Err(MetronomeError::InvalidCountIn(_))));
+        }
+        std::fs::remove_file(&path).unwrap();
     }
     
     #[test]
-    fn test_accent_strength_calculation() {
-        let config = MetronomeConfig::new(120);
-        let mut state = MetronomeState::new(&config);
-        
-        // Test 4/4 accent strengths
-        state.time_signature = TimeSignature::Four;
-        
-        state.current_beat_in_measure = 1;
-        assert_eq!(state.get_accent_strength(), 1.0); // Strong beat
-        
-        state.current_beat_in_measure = 2;
-        assert_eq!(state.get_accent_strength(), 0.0); // Weak beat
-        
-        state.current_beat_in_measure = 3;
-        assert_eq!(state.get_accent_strength(), 0.5); // Medium beat
+    fn test_session_limit_parsing() {
+        assert_eq!(SessionLimit::parse_duration("10m").unwrap(), SessionLimit::Duration(Duration::from_secs(600)));
+        assert_eq!(SessionLimit::parse_duration(" 90s ").unwrap(), SessionLimit::Duration(Duration::from_secs(90)));
+        assert_eq!(SessionLimit::parse_duration("1h30m").unwrap(), SessionLimit::Duration(Duration::from_secs(5400)));
+        assert_eq!(SessionLimit::parse_duration("2:30").unwrap(), SessionLimit::Duration(Duration::from_secs(150)));
+        for invalid in ["", "10", "m", "10x", "0m", "25h", "2:75", "1m30"] {
+            assert!(matches!(SessionLimit::parse_duration(invalid), Err(MetronomeError::InvalidSessionLimit(_))), "{}", invalid);
+        }
+        assert!(SessionLimit::bars(0).is_err());
         
-        state.current_beat_in_measure = 4;
-        assert_eq!(state.get_accent_strength(), 0.0); // Weak beat
+        assert_eq!(SessionLimit::Duration(Duration::from_secs(600)).to_string(), "10:00");
+        assert_eq!(SessionLimit::Duration(Duration::from_secs(3725)).to_string(), "1:02:05");
+        assert_eq!(SessionLimit::Duration(Duration::from_millis(1200)).to_string(), "0:02");
+        assert_eq!(SessionLimit::Bars(1).to_string(), "1 bar");
+        assert_eq!(SessionLimit::Bars(64).to_string(), "64 bars");
     }
     
     #[test]
-    fn test_beat_accent_methods() {
-        // Test 4/4 time signature beats
-        let beat1 = Beat::new(1, TimeSignature::Four, 120);
-        assert!(beat1.is_strong_beat());
-        assert!(!beat1.is_medium_beat());
-        assert!(!beat1.is_weak_beat());
-        assert_eq!(beat1.get_accent_strength(), 1.0);
+    fn test_bar_limit_stops_after_last_bar() {
+        let config = MetronomeConfig::new(120)
+            .with_time_signature(TimeSignature::new(2, 4))
+            .with_subdivision(Subdivision::Eighths)
+            .with_count_in(CountIn::new(1).unwrap())
+            .with_session_limit(SessionLimit::bars(2).unwrap());
+        let mut state = MetronomeState::new(&config);
+        assert_eq!(state.get_session_remaining(), Some(SessionLimit::Bars(2)));
+        state.start();
         
-        let beat2 = Beat::new(2, TimeSignature::Four, 120);
-        assert!(!beat2.is_strong_beat());
-        assert!(!beat2.is_medium_beat());
-        assert!(beat2.is_weak_beat());
-        assert_eq!(beat2.get_accent_strength(), 0.0);
+        // Two count-in beats, then two bars of two beats with their subdivision clicks
+        let mut clicks = Vec::new();
+        while let Some(scheduled) = state.get_next_beat_time() {
+            let beat = state.increment_beat_at(scheduled);
+            clicks.push((beat.count_in, beat.sequence_number, beat.subdivision_index, state.get_session_remaining()));
+            assert!(clicks.len() <= 10);
+        }
+        assert_eq!(clicks.len(), 10);
+        assert!(clicks[..2].iter().all(|click| click.0 && click.3 == Some(SessionLimit::Bars(2))));
+        assert_eq!(clicks[2], (false, 1, 0, Some(SessionLimit::Bars(2))));
+        assert_eq!(clicks[6], (false, 3, 0, Some(SessionLimit::Bars(1))));
+        assert_eq!(clicks[9], (false, 4, 1, Some(SessionLimit::Bars(0))));
         
-        let beat3 = Beat::new(3, TimeSignature::Four, 120);
-        assert!(!beat3.is_strong_beat());
-        assert!(beat3.is_medium_beat());
-        assert!(!beat3.is_weak_beat());
-        assert_eq!(beat3.get_accent_strength(), 0.5);
+        assert!(state.is_session_complete());
+        assert!(!state.is_beat_due(Instant::now() + Duration::from_secs(60)));
+        assert_eq!(state.get_bars_played(), 2);
+        
+        // A session stopped by its limit has no bars left
+        state.stop();
+        assert_eq!(state.get_session_remaining(), Some(SessionLimit::Bars(0)));
+        
+        // Restarting begins a new session
+        state.start();
+        assert!(!state.is_session_complete());
+        assert_eq!(state.get_session_remaining(), Some(SessionLimit::Bars(2)));
     }
     
     #[test]
-    fn test_timing_accuracy_calculation() {
-        let config = MetronomeConfig::new(120);
+    fn test_time_limit_schedules_no_beat_past_it() {
+        let config = MetronomeConfig::new(120).with_session_limit(SessionLimit::parse_duration("3s").unwrap());
         let mut state = MetronomeState::new(&config);
         state.start();
-        
-        // Test that timing accuracy calculation works
-        // We'll test with a known time offset
         let start_time = state.start_time.unwrap();
-        let expected_first_beat = start_time + state.calculate_beat_interval();
-        
-        // Simulate checking accuracy at the expected time
-        let accuracy = state.get_timing_accuracy(expected_first_beat);
-        assert!(accuracy.is_some());
         
-        // The accuracy should be very small (close to 0) when timing is perfect
-        if let Some(acc) = accuracy {
-            assert!(acc.as_millis() <= 1); // Should be very close to expected time
+        // Beats fall every half second from 0.5s; the one at 3s would be on the limit
+        let mut beats = 0;
+        while let Some(scheduled) = state.get_next_beat_time() {
+            assert!(scheduled.duration_since(start_time) < Duration::from_secs(3));
+            state.increment_beat_at(scheduled);
+            beats += 1;
         }
+        assert_eq!(beats, 5);
+        assert!(state.is_session_complete());
+        
+        state.update_session_limit(None);
+        assert!(!state.is_session_complete());
+        assert!(state.get_next_beat_time().is_some());
+        assert_eq!(state.get_session_remaining(), None);
     }
     
     #[test]
-    fn test_volume_functionality() {
-        // Test MetronomeConfig with volume
-        let config = MetronomeConfig::new(120).with_volume(0.5);
-        assert_eq!(config.volume, 0.5);
+    fn test_subdivision_parsing() {
+        assert_eq!("triplets".parse::<Subdivision>(), Ok(Subdivision::Triplets));
+        assert_eq!("Sixteenths".parse::<Subdivision>(), Ok(Subdivision::Sixteenths));
+        assert_eq!("none".parse::<Subdivision>(), Ok(Subdivision::None));
+        assert!("sevenths".parse::<Subdivision>().is_err());
         
-        // Test volume clamping
-        let config_high = MetronomeConfig::new(120).with_volume(1.5);
-        assert_eq!(config_high.volume, 1.0); // Should be clamped to 1.0
+        assert_eq!(Subdivision::None.pulses_per_beat(), 1);
+        assert_eq!(Subdivision::Quintuplets.pulses_per_beat(), 5);
+    }
+    
+    #[cfg(feature = "gui")]
+    #[test]
+    fn test_config_without_subdivision_fields_loads() {
+        let json = r#"{
+            "bpm": 100,
+            "time_signature": "Three",
+            "beat_sound": "BuiltinClick",
+            "accent_sound": "BuiltinWood",
+            "sound_enabled": true,
+            "visual_enabled": true,
+            "accent_enabled": true,
+            "volume": 0.7
+        }"#;
+        let config: MetronomeConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.bpm, 100);
+        assert_eq!(config.time_signature, TimeSignature::new(3, 4));
+        assert_eq!(config.subdivision, Subdivision::None);
+        assert_eq!(config.subdivision_sound, SoundType::BuiltinBeep);
+        assert_eq!(config.subdivision_volume, 0.5);
+        assert_eq!(config.swing, STRAIGHT_SWING);
+        assert_eq!(config.accent_pattern, None);
+        assert_eq!(config.tempo_ramp, None);
+        assert_eq!(config.speed_trainer, None);
+        assert_eq!(config.gap_click, None);
+        assert_eq!(config.dropout, None);
+        assert_eq!(config.session_limit, None);
+        assert_eq!(config.resample_quality, ResampleQuality::Balanced);
+    }
+    
+    #[cfg(feature = "gui")]
+    #[test]
+    fn test_resample_quality_setting() {
+        assert_eq!("high".parse::<ResampleQuality>(), Ok(ResampleQuality::High));
+        assert_eq!(" Fast ".parse::<ResampleQuality>(), Ok(ResampleQuality::Fast));
+        assert!("best".parse::<ResampleQuality>().is_err());
+        assert_eq!(ResampleQuality::Balanced.to_string(), "balanced");
         
-        let config_low = MetronomeConfig::new(120).with_volume(-0.5);
-        assert_eq!(config_low.volume, 0.0); // Should be clamped to 0.0
+        let config = MetronomeConfig::new(120).with_resample_quality(ResampleQuality::High);
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(json.contains("\"resample_quality\":\"High\""));
+        let loaded: MetronomeConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.resample_quality, ResampleQuality::High);
+    }
+    
+    #[cfg(feature = "gui")]
+    #[test]
+    fn test_fractional_tempo_survives_saving() {
+        let config = MetronomeConfig::new(72.5).with_tempo_range(TempoRange::new(40.0, 300.0).unwrap());
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(json.contains("\"bpm\":72.5"));
         
-        // Test MetronomeState volume update
-        let config = MetronomeConfig::new(120);
-        let mut state = MetronomeState::new(&config);
+        let loaded: MetronomeConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.bpm.bpm(), 72.5);
+        assert_eq!(loaded.tempo_range, TempoRange::new(40.0, 300.0).unwrap());
+        assert!(loaded.validate().is_ok());
+    }
+    
+    #[cfg(feature = "gui")]
+    #[test]
+    fn test_time_signature_serialization() {
+        let config = MetronomeConfig::new(140).with_time_signature(TimeSignature::new(11, 8));
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(json.contains("\"time_signature\":\"11/8\""));
         
-        assert_eq!(state.volume, 0.7); // Default volume
+        let loaded: MetronomeConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.time_signature, TimeSignature::new(11, 8));
         
-        // Test valid volume update
-        assert!(state.update_volume(0.8).is_ok());
-        assert_eq!(state.volume, 0.8);
+        let grouped = config.with_time_signature("2+2+3/8".parse().unwrap());
+        let loaded: MetronomeConfig = serde_json::from_str(&serde_json::to_string(&grouped).unwrap()).unwrap();
+        assert_eq!(loaded.time_signature.groups(), vec![2, 2, 3]);
         
-        // Test invalid volume updates
-        assert!(state.update_volume(-0.1).is_err());
-        assert!(state.update_volume(1.1).is_err());
-        assert_eq!(state.volume, 0.8); // Should remain unchanged after invalid update
+        let backbeat = AccentPattern::new(vec![AccentLevel::Mute, AccentLevel::Strong, AccentLevel::Mute, AccentLevel::Strong]).unwrap();
+        let with_pattern = MetronomeConfig::new(120).with_accent_pattern(backbeat.clone());
+        let json_pattern = serde_json::to_string(&with_pattern).unwrap();
+        assert!(json_pattern.contains("\"accent_pattern\":[\"Mute\",\"Strong\",\"Mute\",\"Strong\"]"));
+        let loaded: MetronomeConfig = serde_json::from_str(&json_pattern).unwrap();
+        assert_eq!(loaded.accent_pattern, Some(backbeat));
+        
+        let invalid = json.replace("11/8", "11/7");
+        assert!(serde_json::from_str::<MetronomeConfig>(&invalid).is_err());
     }
     
+    #[cfg(feature = "gui")]
     #[test]
-    fn test_gui_state_volume() {
-        let gui_state = GuiState::new();
-        assert_eq!(gui_state.volume, 0.7); // Default volume
+    fn test_beat_unit_serialization() {
+        let time_signature = TimeSignature::new(6, 8).with_beat_unit(BeatUnit::DottedQuarter).unwrap();
+        let config = MetronomeConfig::new(60).with_time_signature(time_signature);
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(json.contains("\"time_signature\":\"6/8 (dotted quarter)\""));
+        let loaded: MetronomeConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.time_signature.beats_per_measure(), 2);
+    }
+    
+    #[cfg(feature = "gui")]
+    #[test]
+    fn test_setlist_file_round_trip() {
+        let setlist = Setlist::new()
+            .with_song(Song::new("Opener", 140).unwrap().with_time_signature(TimeSignature::new(6, 8)))
+            .with_song(Song::new("Ballad", 72).unwrap().with_sounds(SoundType::BuiltinBeep, SoundType::BuiltinClick))
+            .with_song(Song::new("Suite", 100).unwrap()
+                .with_section(SongSection::new(8, 100, TimeSignature::new(4, 4)).unwrap().with_label("A"))
+                .with_section(SongSection::new(2, 130, TimeSignature::new(7, 8)).unwrap()));
+        let path = std::env::temp_dir().join(format!("cli-metronome-setlist-{}.json", std::process::id()));
+        setlist.save_to_file(&path).unwrap();
+        let loaded = Setlist::load_from_file(&path, &TempoRange::default()).unwrap();
+        assert_eq!(loaded, setlist);
+        
+        // Songs only need a name and tempo; an invalid song rejects the whole file
+        std::fs::write(&path, r#"{"songs":[{"name":"Minimal","bpm":100},{"name":"Too fast","bpm":500}]}"#).unwrap();
+        assert!(matches!(Setlist::load_from_file(&path, &TempoRange::default()), Err(MetronomeError::InvalidBpm(bpm)) if bpm == 500.0));
+        assert!(Setlist::load_from_file(&path, &TempoRange::new(20.0, 600.0).unwrap()).is_ok());
+        std::fs::write(&path, r#"{"songs":[{"name":"Minimal","bpm":100}]}"#).unwrap();
+        let loaded = Setlist::load_from_file(&path, &TempoRange::default()).unwrap();
+        assert_eq!(loaded.songs[0], Song::new("Minimal", 100).unwrap());
+        std::fs::remove_file(&path).unwrap();
     }
-}
\ No newline at end of file
+}
@@ -136,6 +136,67 @@ fn test_custom_sound_loading_strict() {
     assert!(result.is_err());
 }
 
+/// 48 kHz stereo 16-bit PCM WAV file with two frames
+fn stereo_wav_bytes() -> Vec<u8> {
+    let samples: [i16; 4] = [16384, -16384, 8192, 0];
+    let mut bytes = b"RIFF".to_vec();
+    bytes.extend_from_slice(&44u32.to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    for field in [16u32, 0x0002_0001, 48000, 48000 * 4, 0x0010_0004] {
+        bytes.extend_from_slice(&field.to_le_bytes());
+    }
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&8u32.to_le_bytes());
+    bytes.extend(samples.iter().flat_map(|s| s.to_le_bytes()));
+    bytes
+}
+
+#[test]
+fn test_custom_wav_file_is_decoded() {
+    let dir = std::env::temp_dir();
+    let path = dir.join(format!("cli-metronome-rimshot-{}.wav", std::process::id()));
+    let broken = dir.join(format!("cli-metronome-broken-{}.wav", std::process::id()));
+
+    std::fs::write(&path, stereo_wav_bytes()).unwrap();
+    std::fs::write(&broken, b"RIFF\x04\0\0\0WAVE").unwrap();
+
+    let mut audio = CrossPlatformAudio::new();
+    let sound_type = audio.load_custom_sound_strict(&path).unwrap();
+    let sound = audio.get_sound_data(&sound_type).unwrap();
+    assert_eq!(sound.sample_rate, 48000);
+    assert_eq!(sound.channels, 2);
+    assert_eq!(sound.samples(), vec![0.5, -0.5, 0.25, 0.0]);
+    assert_eq!(sound.frame_count(), 2);
+
+    let error = audio.load_custom_sound_strict(&broken).unwrap_err();
+    assert!(error.to_string().contains("no fmt chunk"));
+
+    std::fs::remove_file(&path).unwrap();
+    std::fs::remove_file(&broken).unwrap();
+}
+
+#[test]
+fn test_custom_sound_format_detected_by_content() {
+    let dir = std::env::temp_dir();
+    let mislabeled = dir.join(format!("cli-metronome-mislabeled-{}.ogg", std::process::id()));
+    let broken = dir.join(format!("cli-metronome-broken-{}.flac", std::process::id()));
+    std::fs::write(&mislabeled, stereo_wav_bytes()).unwrap();
+    std::fs::write(&broken, b"fLaC\0\0\0\x22 truncated stream info").unwrap();
+
+    // A WAV file with an .ogg extension is still decoded as WAV
+    assert!(CrossPlatformAudio::validate_sound_file(&mislabeled).is_ok());
+    let sound = SoundData::from_file(&mislabeled).unwrap();
+    assert_eq!(sound.sample_rate, 48000);
+    assert_eq!(sound.frame_count(), 2);
+
+    // Validation probes the stream instead of trusting the extension
+    let error = CrossPlatformAudio::validate_sound_file(&broken).unwrap_err();
+    assert!(error.to_string().contains("FLAC"));
+
+    std::fs::remove_file(&mislabeled).unwrap();
+    std::fs::remove_file(&broken).unwrap();
+}
+
 #[test]
 fn test_custom_sound_file_validation() {
     // Test non-existent file
@@ -147,7 +208,8 @@ fn test_custom_sound_file_validation() {
     assert!(supported.contains(&"wav"));
     assert!(supported.contains(&"mp3"));
     assert!(supported.contains(&"ogg"));
-    assert_eq!(supported.len(), 3);
+    assert!(supported.contains(&"flac"));
+    assert_eq!(supported.len(), 4);
 }
 
 #[test]
@@ -197,9 +259,9 @@ fn test_beat_playback_from_beat_info() {
     let audio = CrossPlatformAudio::new();
     
     // Create beats with different accent patterns
-    let strong_beat = Beat::new(1, TimeSignature::Four, 120); // First beat - strong
-    let weak_beat = Beat::new(2, TimeSignature::Four, 120);   // Second beat - weak
-    let medium_beat = Beat::new(3, TimeSignature::Four, 120); // Third beat - medium
+    let strong_beat = Beat::new(1, TimeSignature::new(4, 4), 120); // First beat - strong
+    let weak_beat = Beat::new(2, TimeSignature::new(4, 4), 120);   // Second beat - weak
+    let medium_beat = Beat::new(3, TimeSignature::new(4, 4), 120); // Third beat - medium
     
     // Test that beats have correct accent properties
     assert!(strong_beat.is_accent);
@@ -299,8 +361,8 @@ fn test_audio_engine_beat_info_playback() {
     let engine = AudioEngine::default();
     
     // Test playing from beat info
-    let regular_beat = Beat::new(2, TimeSignature::Four, 120); // Second beat in 4/4 (weak)
-    let accent_beat = Beat::new(1, TimeSignature::Four, 120);  // First beat in 4/4 (strong)
+    let regular_beat = Beat::new(2, TimeSignature::new(4, 4), 120); // Second beat in 4/4 (weak)
+    let accent_beat = Beat::new(1, TimeSignature::new(4, 4), 120);  // First beat in 4/4 (strong)
     
     assert!(engine.play_beat_from_info(&regular_beat).is_ok());
     assert!(engine.play_beat_from_info(&accent_beat).is_ok());
@@ -327,6 +389,7 @@ fn test_audio_engine_status_and_caching() {
     assert!(extensions.contains(&"wav"));
     assert!(extensions.contains(&"mp3"));
     assert!(extensions.contains(&"ogg"));
+    assert!(extensions.contains(&"flac"));
 }
 
 #[test]
@@ -334,32 +397,32 @@ fn test_time_signature_beat_patterns() {
     // Test different time signatures produce correct beat patterns
     
     // 2/4 time
-    let beat1_2_4 = Beat::new(1, TimeSignature::Two, 120);
-    let beat2_2_4 = Beat::new(2, TimeSignature::Two, 120);
+    let beat1_2_4 = Beat::new(1, TimeSignature::new(2, 4), 120);
+    let beat2_2_4 = Beat::new(2, TimeSignature::new(2, 4), 120);
     assert!(beat1_2_4.is_accent);  // Strong
     assert!(!beat2_2_4.is_accent); // Weak
     
     // 3/4 time
-    let beat1_3_4 = Beat::new(1, TimeSignature::Three, 120);
-    let beat2_3_4 = Beat::new(2, TimeSignature::Three, 120);
-    let beat3_3_4 = Beat::new(3, TimeSignature::Three, 120);
+    let beat1_3_4 = Beat::new(1, TimeSignature::new(3, 4), 120);
+    let beat2_3_4 = Beat::new(2, TimeSignature::new(3, 4), 120);
+    let beat3_3_4 = Beat::new(3, TimeSignature::new(3, 4), 120);
     assert!(beat1_3_4.is_accent);  // Strong
     assert!(!beat2_3_4.is_accent); // Weak
     assert!(!beat3_3_4.is_accent); // Weak
     
     // 4/4 time
-    let beat1_4_4 = Beat::new(1, TimeSignature::Four, 120);
-    let beat2_4_4 = Beat::new(2, TimeSignature::Four, 120);
-    let beat3_4_4 = Beat::new(3, TimeSignature::Four, 120);
-    let beat4_4_4 = Beat::new(4, TimeSignature::Four, 120);
+    let beat1_4_4 = Beat::new(1, TimeSignature::new(4, 4), 120);
+    let beat2_4_4 = Beat::new(2, TimeSignature::new(4, 4), 120);
+    let beat3_4_4 = Beat::new(3, TimeSignature::new(4, 4), 120);
+    let beat4_4_4 = Beat::new(4, TimeSignature::new(4, 4), 120);
     assert!(beat1_4_4.is_accent);  // Strong
     assert!(!beat2_4_4.is_accent); // Weak
     assert!(beat3_4_4.is_accent);  // Medium (treated as accent)
     assert!(!beat4_4_4.is_accent); // Weak
     
     // 6/8 time
-    let beat1_6_8 = Beat::new(1, TimeSignature::Six, 120);
-    let beat4_6_8 = Beat::new(4, TimeSignature::Six, 120);
+    let beat1_6_8 = Beat::new(1, TimeSignature::new(6, 8), 120);
+    let beat4_6_8 = Beat::new(4, TimeSignature::new(6, 8), 120);
     assert!(beat1_6_8.is_accent);  // Strong
     assert!(beat4_6_8.is_accent);  // Medium (treated as accent)
 }
@@ -371,7 +434,7 @@ fn test_audio_playback_with_different_time_signatures() {
     let accent_sound = SoundType::BuiltinWood;
     
     // Test playback for different time signature patterns
-    let time_signatures = [TimeSignature::Two, TimeSignature::Three, TimeSignature::Four, TimeSignature::Six];
+    let time_signatures = [TimeSignature::new(2, 4), TimeSignature::new(3, 4), TimeSignature::new(4, 4), TimeSignature::new(6, 8)];
     
     for time_sig in &time_signatures {
         let beats_per_measure = time_sig.beats_per_measure();
@@ -59,7 +59,7 @@ fn test_gui_mode_components_integration() {
         
         // Test GUI state validation
         assert!(gui_state.validate_bpm("120").is_ok());
-        assert_eq!(gui_state.selected_time_signature, TimeSignature::Four);
+        assert_eq!(gui_state.selected_time_signature, TimeSignature::new(4, 4));
         assert_eq!(gui_state.selected_beat_sound, SoundType::BuiltinClick);
     }
     
@@ -78,7 +78,7 @@ fn test_dual_mode_consistency_bpm_validation() {
     let cli_result_valid = cli_metronome::cli::validate_bpm(120);
     assert!(cli_result_valid.is_ok());
     
-    let cli_result_invalid = cli_metronome::cli::validate_bpm(59);
+    let cli_result_invalid = cli_metronome::cli::validate_bpm(19);
     assert!(cli_result_invalid.is_err());
     
     // GUI mode validation
@@ -89,7 +89,7 @@ fn test_dual_mode_consistency_bpm_validation() {
         let gui_result_valid = gui_state.validate_bpm("120");
         assert!(gui_result_valid.is_ok());
         
-        let gui_result_invalid = gui_state.validate_bpm("59");
+        let gui_result_invalid = gui_state.validate_bpm("19");
         assert!(gui_result_invalid.is_err());
     }
     
@@ -97,7 +97,7 @@ fn test_dual_mode_consistency_bpm_validation() {
     let config_valid = MetronomeConfig::new(120);
     assert!(config_valid.validate().is_ok());
     
-    let config_invalid = MetronomeConfig::new(59);
+    let config_invalid = MetronomeConfig::new(19);
     assert!(config_invalid.validate().is_err());
 }
 
@@ -106,16 +106,16 @@ fn test_dual_mode_consistency_time_signatures() {
     // Test that time signature handling is consistent between modes
     
     let time_signatures = [
-        TimeSignature::Two,
-        TimeSignature::Three,
-        TimeSignature::Four,
-        TimeSignature::Six,
+        TimeSignature::new(2, 4),
+        TimeSignature::new(3, 4),
+        TimeSignature::new(4, 4),
+        TimeSignature::new(6, 8),
     ];
     
     for time_sig in &time_signatures {
         // Test time signature properties are consistent
         let beats_per_measure = time_sig.beats_per_measure();
-        let display_str = time_sig.as_str();
+        let display_str = time_sig.to_string();
         
         assert!(beats_per_measure > 0);
         assert!(!display_str.is_empty());
@@ -177,7 +177,7 @@ fn test_dual_mode_metronome_core_consistency() {
     // Test that the core metronome functionality works the same in both modes
     
     let bpm = 120;
-    let time_sig = TimeSignature::Four;
+    let time_sig = TimeSignature::new(4, 4);
     
     // Create metronome for CLI mode
     let cli_controller = MetronomeController::new(bpm).unwrap();
@@ -248,22 +248,22 @@ fn test_dual_mode_error_handling_consistency() {
     use cli_metronome::error::MetronomeError;
     
     // Test invalid BPM error in both modes
-    let cli_error = cli_metronome::cli::validate_bpm(59);
+    let cli_error = cli_metronome::cli::validate_bpm(19);
     assert!(cli_error.is_err());
     
-    let metronome_error = cli_metronome::metronome::MetronomeController::new(59);
+    let metronome_error = cli_metronome::metronome::MetronomeController::new(19);
     assert!(metronome_error.is_err());
     
     #[cfg(feature = "gui")]
     {
         let mut gui_state = cli_metronome::models::GuiState::new();
-        let gui_error = gui_state.validate_bpm("59");
+        let gui_error = gui_state.validate_bpm("19");
         assert!(gui_error.is_err());
     }
     
     // Test that all errors are of the same type family
     match metronome_error {
-        Err(MetronomeError::InvalidBpm(bpm)) => assert_eq!(bpm, 59),
+        Err(MetronomeError::InvalidBpm(bpm)) => assert_eq!(bpm, 19.0),
         _ => panic!("Expected InvalidBpm error"),
     }
 }
@@ -274,7 +274,7 @@ fn test_dual_mode_beat_generation_consistency() {
     
     use cli_metronome::models::Beat;
     
-    let time_sig = TimeSignature::Four;
+    let time_sig = TimeSignature::new(4, 4);
     let bpm = 120;
     
     // Generate beats and test consistency
@@ -315,14 +315,14 @@ fn test_dual_mode_configuration_consistency() {
     // Test that configuration handling is consistent between modes
     
     let config = MetronomeConfig::new(120)
-        .with_time_signature(TimeSignature::Three)
+        .with_time_signature(TimeSignature::new(3, 4))
         .with_sounds(SoundType::BuiltinWood, SoundType::BuiltinBeep)
         .with_sound(true)
         .with_visual(true);
     
     assert!(config.validate().is_ok());
     assert_eq!(config.bpm, 120);
-    assert_eq!(config.time_signature, TimeSignature::Three);
+    assert_eq!(config.time_signature, TimeSignature::new(3, 4));
     assert_eq!(config.beat_sound, SoundType::BuiltinWood);
     assert_eq!(config.accent_sound, SoundType::BuiltinBeep);
     assert!(config.sound_enabled);
@@ -347,7 +347,7 @@ fn test_dual_mode_display_consistency() {
     // (GUI mode display is handled by egui and harder to test)
     
     let display = DisplayEngine::new();
-    let time_sig = TimeSignature::Four;
+    let time_sig = TimeSignature::new(4, 4);
     let audio_status = cli_metronome::audio::AudioStatus::Available;
     
     // Test that display methods don't panic
@@ -368,7 +368,7 @@ fn test_mode_switching_simulation() {
     // underlying functionality works in both contexts
     
     let bpm = 140;
-    let time_sig = TimeSignature::Three;
+    let time_sig = TimeSignature::new(3, 4);
     
     // Simulate CLI mode operation
     {
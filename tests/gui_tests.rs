@@ -1,9 +1,9 @@
 #[cfg(feature = "gui")]
 mod gui_tests {
-    use cli_metronome::models::{GuiState, TimeSignature, SoundType, MetronomeState, MetronomeConfig};
+    use cli_metronome::models::{BeatUnit, ChangeTiming, GuiState, SessionLimit, Tempo, TempoRange, Song, TimeSignature, TrainerEnd, SoundType, MetronomeState, MetronomeConfig};
     use cli_metronome::gui::MetronomeApp;
     use cli_metronome::error::MetronomeError;
-    use std::time::Instant;
+    use std::time::{Duration, Instant};
 
     #[test]
     fn test_gui_state_creation() {
@@ -11,7 +11,7 @@ mod gui_tests {
         
         assert_eq!(gui_state.bpm_input, "120");
         assert!(gui_state.bpm_valid);
-        assert_eq!(gui_state.selected_time_signature, TimeSignature::Four);
+        assert_eq!(gui_state.selected_time_signature, TimeSignature::new(4, 4));
         assert_eq!(gui_state.selected_beat_sound, SoundType::BuiltinClick);
         assert_eq!(gui_state.selected_accent_sound, SoundType::BuiltinWood);
         assert!(!gui_state.is_running);
@@ -31,15 +31,15 @@ mod gui_tests {
         assert!(gui_state.error_message.is_none());
         
         // Test BPM at lower boundary
-        let result = gui_state.validate_bpm("60");
+        let result = gui_state.validate_bpm("20");
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), 60);
+        assert_eq!(result.unwrap(), 20);
         assert!(gui_state.bpm_valid);
         
         // Test BPM at upper boundary
-        let result = gui_state.validate_bpm("200");
+        let result = gui_state.validate_bpm("400");
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), 200);
+        assert_eq!(result.unwrap(), 400);
         assert!(gui_state.bpm_valid);
     }
 
@@ -48,14 +48,14 @@ mod gui_tests {
         let mut gui_state = GuiState::new();
         
         // Test BPM below minimum
-        let result = gui_state.validate_bpm("59");
+        let result = gui_state.validate_bpm("19");
         assert!(result.is_err());
         assert!(!gui_state.bpm_valid);
         assert!(gui_state.error_message.is_some());
-        assert!(gui_state.error_message.as_ref().unwrap().contains("60 and 200"));
+        assert!(gui_state.error_message.as_ref().unwrap().contains("20 and 400"));
         
         // Test BPM above maximum
-        let result = gui_state.validate_bpm("201");
+        let result = gui_state.validate_bpm("401");
         assert!(result.is_err());
         assert!(!gui_state.bpm_valid);
         assert!(gui_state.error_message.is_some());
@@ -113,7 +113,7 @@ mod gui_tests {
         assert!(gui_state.error_message.is_none());
         
         // Test handling error result
-        let err_result: Result<u32, MetronomeError> = Err(MetronomeError::InvalidBpm(59));
+        let err_result: Result<u32, MetronomeError> = Err(MetronomeError::InvalidBpm(19.0));
         let value = gui_state.handle_result(err_result);
         assert_eq!(value, None);
         assert!(gui_state.error_message.is_some());
@@ -142,14 +142,260 @@ mod gui_tests {
         let mut gui_state = GuiState::new();
         
         // Test default time signature
-        assert_eq!(gui_state.selected_time_signature, TimeSignature::Four);
+        assert_eq!(gui_state.selected_time_signature, TimeSignature::new(4, 4));
         
         // Test changing time signature
-        gui_state.selected_time_signature = TimeSignature::Three;
-        assert_eq!(gui_state.selected_time_signature, TimeSignature::Three);
+        gui_state.selected_time_signature = TimeSignature::new(3, 4);
+        assert_eq!(gui_state.selected_time_signature, TimeSignature::new(3, 4));
+        
+        gui_state.selected_time_signature = TimeSignature::new(6, 8);
+        assert_eq!(gui_state.selected_time_signature, TimeSignature::new(6, 8));
+    }
+
+    #[test]
+    fn test_gui_state_grouping_input() {
+        let mut gui_state = GuiState::new();
+        gui_state.selected_time_signature = TimeSignature::new(7, 8);
+        
+        // Valid grouping is applied to the selected time signature
+        let result = gui_state.validate_grouping("2 + 2 + 3");
+        assert!(result.is_ok());
+        assert_eq!(gui_state.selected_time_signature.groups(), vec![2, 2, 3]);
+        assert_eq!(GuiState::grouping_text(gui_state.selected_time_signature), "2+2+3");
+        assert!(gui_state.error_message.is_none());
+        
+        // Groups that do not add up to the numerator are rejected
+        let result = gui_state.validate_grouping("3+3");
+        assert!(result.is_err());
+        assert!(gui_state.error_message.is_some());
+        assert_eq!(gui_state.selected_time_signature.groups(), vec![2, 2, 3]);
         
-        gui_state.selected_time_signature = TimeSignature::Six;
-        assert_eq!(gui_state.selected_time_signature, TimeSignature::Six);
+        // Empty input returns to the default grouping
+        let result = gui_state.validate_grouping("");
+        assert!(result.is_ok());
+        assert_eq!(gui_state.selected_time_signature, TimeSignature::new(7, 8));
+        assert_eq!(GuiState::grouping_text(gui_state.selected_time_signature), "");
+    }
+
+    #[test]
+    fn test_gui_state_tempo_ramp() {
+        let mut gui_state = GuiState::new();
+        
+        // No ramp until it is switched on
+        assert!(gui_state.get_tempo_ramp().unwrap().is_none());
+        
+        gui_state.ramp_enabled = true;
+        gui_state.bpm_input = "90".to_string();
+        gui_state.ramp_target_bpm = 140.0;
+        gui_state.ramp_bars = 32;
+        let ramp = gui_state.get_tempo_ramp().unwrap().unwrap();
+        assert_eq!(ramp.start_bpm, 90);
+        assert_eq!(ramp.target_bpm, 140);
+        assert_eq!(ramp.bars, 32);
+        
+        // The ramp starts from the entered BPM, so it must be valid
+        gui_state.bpm_input = "abc".to_string();
+        assert!(gui_state.get_tempo_ramp().is_err());
+    }
+
+    #[test]
+    fn test_gui_state_speed_trainer() {
+        let mut gui_state = GuiState::new();
+        assert!(gui_state.get_speed_trainer().unwrap().is_none());
+        
+        gui_state.trainer_enabled = true;
+        gui_state.bpm_input = "80".to_string();
+        gui_state.trainer_target_bpm = 120.0;
+        gui_state.trainer_increment = 10;
+        gui_state.trainer_bars = 2;
+        gui_state.trainer_end = TrainerEnd::DropBack(0);
+        gui_state.trainer_drop_bpm = 20;
+        let trainer = gui_state.get_speed_trainer().unwrap().unwrap();
+        assert_eq!(trainer.start_bpm, 80);
+        assert_eq!(trainer.target_bpm, 120);
+        assert_eq!(trainer.increment, 10);
+        assert_eq!(trainer.bars_per_step, 2);
+        assert_eq!(trainer.end, TrainerEnd::DropBack(20));
+        
+        // The target must be above the entered BPM
+        gui_state.bpm_input = "130".to_string();
+        assert!(gui_state.get_speed_trainer().is_err());
+    }
+
+    #[test]
+    fn test_gui_state_gap_click() {
+        let mut gui_state = GuiState::new();
+        assert!(gui_state.get_gap_click().unwrap().is_none());
+        
+        gui_state.gap_enabled = true;
+        gui_state.gap_play_bars = 4;
+        gui_state.gap_mute_bars = 2;
+        let gap_click = gui_state.get_gap_click().unwrap().unwrap();
+        assert_eq!((gap_click.play_bars, gap_click.mute_bars), (4, 2));
+        
+        gui_state.gap_mute_bars = 0;
+        assert!(gui_state.get_gap_click().is_err());
+    }
+
+    #[test]
+    fn test_gui_state_dropout() {
+        let mut gui_state = GuiState::new();
+        
+        // A zero chance means dropout is off
+        assert!(gui_state.get_dropout().unwrap().is_none());
+        
+        gui_state.dropout_probability = 0.3;
+        gui_state.dropout_keep_downbeat = false;
+        let dropout = gui_state.get_dropout().unwrap().unwrap();
+        assert_eq!(dropout.probability, 0.3);
+        assert!(!dropout.keep_downbeat);
+        assert_eq!(dropout.seed, None);
+    }
+
+    #[test]
+    fn test_gui_state_fractional_bpm_and_range() {
+        let mut gui_state = GuiState::new();
+        
+        assert_eq!(gui_state.validate_bpm("72.5").unwrap().bpm(), 72.5);
+        assert!(gui_state.bpm_valid);
+        
+        // A narrower range rejects tempos the default range would allow
+        gui_state.tempo_range = TempoRange::new(60.0, 200.0).unwrap();
+        assert!(gui_state.validate_bpm("250").is_err());
+        assert!(gui_state.error_message.as_ref().unwrap().contains("60 and 200"));
+        assert!(gui_state.validate_bpm("199.5").is_ok());
+    }
+
+    #[test]
+    fn test_gui_state_session_limit() {
+        let mut gui_state = GuiState::new();
+        assert_eq!(gui_state.get_session_limit().unwrap(), None);
+        
+        gui_state.session_limit_enabled = true;
+        assert_eq!(gui_state.get_session_limit().unwrap(), Some(SessionLimit::Duration(Duration::from_secs(600))));
+        
+        gui_state.session_limit_by_bars = true;
+        gui_state.session_bars = 32;
+        assert_eq!(gui_state.get_session_limit().unwrap(), Some(SessionLimit::Bars(32)));
+        
+        gui_state.session_bars = 0;
+        assert!(matches!(gui_state.get_session_limit(), Err(MetronomeError::InvalidSessionLimit(_))));
+    }
+
+    #[test]
+    fn test_gui_state_pending_changes() {
+        assert_eq!(GuiState::new().change_timing, ChangeTiming::Immediate);
+        assert_eq!(GuiState::pending_change_text(None, None), None);
+        assert_eq!(GuiState::pending_change_text(None, Some(TimeSignature::new(7, 8))).unwrap(), "pending: 7/8 at next bar");
+        assert_eq!(
+            GuiState::pending_change_text(Some(Tempo::from(92.5)), Some(TimeSignature::new(7, 8))).unwrap(),
+            "pending: 92.5 BPM, 7/8 at next bar"
+        );
+    }
+
+    #[test]
+    fn test_gui_state_keeps_beat_unit() {
+        let mut gui_state = GuiState::new();
+        assert_eq!(gui_state.with_selected_beat_unit(TimeSignature::new(6, 8)), TimeSignature::new(6, 8));
+        
+        gui_state.selected_time_signature = TimeSignature::new(6, 8).with_beat_unit(BeatUnit::DottedQuarter).unwrap();
+        let twelve_eight = gui_state.with_selected_beat_unit(TimeSignature::new(12, 8));
+        assert_eq!(twelve_eight.beats_per_measure(), 4);
+        
+        // 7/8 cannot be counted in dotted quarters and goes back to eighths
+        assert_eq!(gui_state.with_selected_beat_unit(TimeSignature::new(7, 8)), TimeSignature::new(7, 8));
+    }
+
+    #[test]
+    fn test_gui_state_count_in() {
+        let mut gui_state = GuiState::new();
+        
+        // No bars means no count-in
+        assert!(gui_state.get_count_in().unwrap().is_none());
+        
+        gui_state.count_in_bars = 2;
+        gui_state.count_in_sound = SoundType::BuiltinWood;
+        let count_in = gui_state.get_count_in().unwrap().unwrap();
+        assert_eq!(count_in.bars, 2);
+        assert_eq!(count_in.sound, SoundType::BuiltinWood);
+        
+        gui_state.count_in_bars = 8;
+        assert!(matches!(gui_state.get_count_in(), Err(MetronomeError::InvalidCountIn(_))));
+    }
+
+    #[test]
+    fn test_gui_state_setlist_songs() {
+        let mut gui_state = GuiState::new();
+        gui_state.bpm_input = "96".to_string();
+        gui_state.selected_time_signature = TimeSignature::new(6, 8);
+        gui_state.selected_beat_sound = SoundType::BuiltinBeep;
+        
+        let song = gui_state.song_from_inputs("Opener").unwrap();
+        assert_eq!(song.name, "Opener");
+        assert_eq!(song.bpm, 96);
+        assert_eq!(song.time_signature, TimeSignature::new(6, 8));
+        assert_eq!(song.beat_sound, SoundType::BuiltinBeep);
+        assert!(gui_state.song_from_inputs("").is_err());
+        
+        // Loading a song fills in the controls and switches off tempo automation
+        gui_state.ramp_enabled = true;
+        let waltz = Song::new("Waltz", 84).unwrap().with_time_signature(TimeSignature::new(3, 4));
+        gui_state.load_song(&waltz);
+        assert_eq!(gui_state.bpm_input, "84");
+        assert_eq!(gui_state.selected_time_signature, TimeSignature::new(3, 4));
+        assert_eq!(gui_state.selected_accent_sound, SoundType::BuiltinWood);
+        assert!(!gui_state.ramp_enabled);
+    }
+
+    #[test]
+    fn test_gui_state_tap_tempo() {
+        let mut gui_state = GuiState::new();
+        gui_state.trainer_enabled = true;
+        let start = Instant::now();
+        
+        assert_eq!(gui_state.register_tap(start), None);
+        assert_eq!(gui_state.bpm_input, "120");
+        assert!(gui_state.trainer_enabled);
+        
+        // Two taps 0.75 s apart give 80 BPM and take over from the trainer
+        assert_eq!(gui_state.register_tap(start + std::time::Duration::from_millis(750)), Some(Tempo::from(80.0)));
+        assert_eq!(gui_state.bpm_input, "80");
+        assert!(!gui_state.trainer_enabled);
+        assert_eq!(gui_state.tap_tempo.tap_count(), 2);
+    }
+
+    #[test]
+    fn test_gui_state_input_bpm_uses_tempo_range() {
+        let mut gui_state = GuiState::new();
+        gui_state.tempo_range = TempoRange::new(60.0, 600.0).unwrap();
+        
+        gui_state.bpm_input = "480.5".to_string();
+        assert_eq!(gui_state.get_input_bpm().unwrap(), Tempo::from(480.5));
+        gui_state.bpm_input = "40".to_string();
+        assert!(matches!(gui_state.get_input_bpm(), Err(MetronomeError::InvalidBpm(_))));
+        gui_state.bpm_input = "fast".to_string();
+        assert!(gui_state.get_input_bpm().is_err());
+    }
+
+    #[test]
+    fn test_gui_state_tap_tempo_checks_tempo_range() {
+        let mut gui_state = GuiState::new();
+        gui_state.tempo_range = TempoRange::new(60.0, 200.0).unwrap();
+        let start = Instant::now();
+        
+        // Taps 0.25 s apart give 240 BPM, above the narrowed range
+        gui_state.register_tap(start);
+        assert_eq!(gui_state.register_tap(start + Duration::from_millis(250)), None);
+        assert_eq!(gui_state.bpm_input, "120");
+        assert!(gui_state.bpm_valid);
+        assert!(gui_state.error_message.is_some());
+        
+        // A fractional tempo within the range is kept as tapped
+        gui_state.tap_tempo.reset();
+        gui_state.register_tap(start + Duration::from_secs(1));
+        let tempo = gui_state.register_tap(start + Duration::from_millis(1700)).unwrap();
+        assert!((tempo.bpm() - 60.0 / 0.7).abs() < 1e-9);
+        assert_eq!(gui_state.bpm_input, "85.71");
     }
 
     #[test]
@@ -191,9 +437,9 @@ mod gui_tests {
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), 120);
         
-        // Test decimal input (should fail)
+        // Test decimal input
         let result = gui_state.validate_bpm("120.5");
-        assert!(result.is_err());
+        assert_eq!(result.unwrap().bpm(), 120.5);
         
         // Test negative input (should fail)
         let result = gui_state.validate_bpm("-120");
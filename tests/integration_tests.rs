@@ -33,14 +33,14 @@ fn test_bpm_validation_integration() {
     assert!(validate_bpm(120).is_ok());
     assert!(validate_bpm(200).is_ok());
     
-    assert!(validate_bpm(59).is_err());
-    assert!(validate_bpm(201).is_err());
+    assert!(validate_bpm(19).is_err());
+    assert!(validate_bpm(401).is_err());
     
     // Test with MetronomeConfig
     let config = MetronomeConfig::new(120);
     assert!(config.validate().is_ok());
     
-    let config = MetronomeConfig::new(59);
+    let config = MetronomeConfig::new(19);
     assert!(config.validate().is_err());
 }
 
@@ -102,12 +102,12 @@ fn test_error_handling_integration() {
     use cli_metronome::MetronomeError;
     
     // Test invalid BPM error propagation
-    let result = MetronomeController::new(300);
+    let result = MetronomeController::new(500);
     assert!(result.is_err());
     
     if let Err(error) = result {
         match error {
-            MetronomeError::InvalidBpm(bpm) => assert_eq!(bpm, 300),
+            MetronomeError::InvalidBpm(bpm) => assert_eq!(bpm, 500.0),
             _ => panic!("Expected InvalidBpm error"),
         }
     } else {
@@ -121,9 +121,9 @@ fn test_display_functionality() {
     let display = DisplayEngine::new();
     
     // These should not panic or fail
-    display.show_startup_info(120, cli_metronome::models::TimeSignature::Four, &cli_metronome::audio::AudioStatus::FallbackMode);
-    display.show_status(120, 1, Duration::from_secs(1), cli_metronome::models::TimeSignature::Four, 1);
-    let beat = cli_metronome::models::Beat::new(1, cli_metronome::models::TimeSignature::Four, 120);
+    display.show_startup_info(120, cli_metronome::models::TimeSignature::new(4, 4), &cli_metronome::audio::AudioStatus::FallbackMode);
+    display.show_status(120, 1, Duration::from_secs(1), cli_metronome::models::TimeSignature::new(4, 4), 1);
+    let beat = cli_metronome::models::Beat::new(1, cli_metronome::models::TimeSignature::new(4, 4), 120);
     display.show_visual_beat(&beat);
     display.clear_line();
 }
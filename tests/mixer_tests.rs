@@ -0,0 +1,89 @@
+use cli_metronome::audio::{ClickMixer, OutputFormat, SoundData};
+use cli_metronome::models::{ResampleQuality, SoundType};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Allocator that counts allocations made by threads that opted in
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static COUNTING: Cell<bool> = const { Cell::new(false) };
+}
+
+fn record() {
+    if COUNTING.try_with(|counting| counting.get()).unwrap_or(false) {
+        ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record();
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        record();
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        record();
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Count the allocations and frees `f` makes on this thread
+fn allocations_during(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.load(Ordering::SeqCst);
+    COUNTING.with(|counting| counting.set(true));
+    f();
+    COUNTING.with(|counting| counting.set(false));
+    ALLOCATIONS.load(Ordering::SeqCst) - before
+}
+
+#[test]
+fn test_mixer_renders_clicks_without_allocating() {
+    let format = OutputFormat::new(48000, 2);
+    let (mut mixer, handle) = ClickMixer::new(format);
+
+    let click: Arc<[f32]> = SoundData::from_builtin(SoundType::BuiltinClick)
+        .unwrap()
+        .to_output_format(format, ResampleQuality::Balanced)
+        .into();
+    let accent: Arc<[f32]> = SoundData::from_builtin(SoundType::BuiltinWood)
+        .unwrap()
+        .to_output_format(format, ResampleQuality::Balanced)
+        .into();
+
+    // Sixteenth notes at 300 BPM with accents, so clicks overlap
+    let mut rendered = Vec::with_capacity(40 * 2400 * format.channels as usize);
+    for step in 0..40u64 {
+        let start = step * 2400;
+        handle.play_at(Arc::clone(&click), 0.8, start).unwrap();
+        if step % 4 == 0 {
+            handle.play_at(Arc::clone(&accent), 1.0, start).unwrap();
+        }
+
+        let allocations = allocations_during(|| {
+            for _ in 0..2400 * format.channels as usize {
+                rendered.push(mixer.next().unwrap());
+            }
+        });
+        assert_eq!(allocations, 0, "rendering step {} allocated", step);
+    }
+
+    assert!(rendered.iter().any(|sample| *sample != 0.0));
+    assert!(rendered.iter().all(|sample| sample.abs() <= 1.0));
+
+    // Sounds of finished clicks are released by the control side
+    handle.play(vec![].into(), 1.0).unwrap();
+    assert_eq!(Arc::strong_count(&click), 1);
+}